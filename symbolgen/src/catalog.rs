@@ -0,0 +1,134 @@
+//! A local SQLite file of glyphs worth keeping, with the settings that generate them, tags and
+//! ratings, for projects that look through thousands of seeds.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+use symbolgen_core::{AlphabetConfig, GenerationConfig};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS glyphs (
+    id INTEGER PRIMARY KEY,
+    seed INTEGER NOT NULL,
+    row INTEGER NOT NULL,
+    settings TEXT NOT NULL,
+    rating INTEGER,
+    added TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (seed, row, settings)
+);
+CREATE TABLE IF NOT EXISTS tags (
+    glyph INTEGER NOT NULL REFERENCES glyphs (id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (glyph, tag)
+);
+";
+
+/// A glyph in the catalog.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub seed: u64,
+    /// Row of the sheet whose alphabet generates the glyph
+    pub row: i32,
+    pub alphabet: AlphabetConfig,
+    pub tags: Vec<String>,
+    pub rating: Option<u8>,
+}
+
+/// Which glyphs to list from the catalog.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    /// Tags every glyph listed has
+    pub tags: Vec<String>,
+    pub min_rating: Option<u8>,
+}
+
+pub struct Catalog {
+    connection: Connection,
+}
+
+impl Catalog {
+    /// Open the catalog at the given path, creating it if there isn't one.
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Add glyphs, or for glyphs already in the catalog, add their tags and change their
+    /// rating if one is given.
+    pub fn add(&mut self, entries: &[Entry]) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        for entry in entries {
+            let settings = settings_toml(&entry.alphabet);
+            transaction.execute(
+                "INSERT INTO glyphs (seed, row, settings, rating) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (seed, row, settings)
+                 DO UPDATE SET rating = coalesce(excluded.rating, rating)",
+                // SQLite integers are signed, so seeds are stored with the same bits
+                params![entry.seed as i64, entry.row, settings, entry.rating],
+            )?;
+            let id: i64 = transaction.query_row(
+                "SELECT id FROM glyphs WHERE seed = ?1 AND row = ?2 AND settings = ?3",
+                params![entry.seed as i64, entry.row, settings],
+                |row| row.get(0),
+            )?;
+            for tag in &entry.tags {
+                transaction.execute(
+                    "INSERT OR IGNORE INTO tags (glyph, tag) VALUES (?1, ?2)",
+                    params![id, tag],
+                )?;
+            }
+        }
+        transaction.commit()
+    }
+
+    /// The glyphs matching the query, in the order they were first added.
+    pub fn list(&self, query: &Query) -> rusqlite::Result<Vec<Entry>> {
+        let mut glyphs = self.connection.prepare(
+            "SELECT id, seed, row, settings, rating FROM glyphs
+             WHERE ?1 IS NULL OR rating >= ?1 ORDER BY id",
+        )?;
+        let mut tags = self
+            .connection
+            .prepare("SELECT tag FROM tags WHERE glyph = ?1 ORDER BY tag")?;
+        let mut entries = Vec::new();
+        let mut rows = glyphs.query(params![query.min_rating])?;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let seed: i64 = row.get(1)?;
+            let settings: String = row.get(3)?;
+            let entry = Entry {
+                seed: seed as u64,
+                row: row.get(2)?,
+                alphabet: GenerationConfig::from_toml(&settings)
+                    .map_err(|error| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            3,
+                            rusqlite::types::Type::Text,
+                            Box::new(error),
+                        )
+                    })?
+                    .alphabet,
+                tags: tags
+                    .query_map(params![id], |row| row.get(0))?
+                    .collect::<rusqlite::Result<_>>()?,
+                rating: row.get(4)?,
+            };
+            if query.tags.iter().all(|tag| entry.tags.contains(tag)) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Alphabet settings as stored, in the config file's TOML, so equal settings are equal text.
+fn settings_toml(alphabet: &AlphabetConfig) -> String {
+    GenerationConfig {
+        alphabet: alphabet.clone(),
+        ..GenerationConfig::default()
+    }
+    .to_toml()
+    .expect("Settings can always be written as TOML")
+}
@@ -0,0 +1,215 @@
+//! A window for exploring alphabet settings, with a sheet of glyphs redrawn as they change.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use eframe::egui;
+use symbolgen_core::{AlphabetConfig, GenerationConfig, Glyph};
+use symbolgen_render::{Renderer, SvgRenderer};
+
+use crate::sheet_drawing;
+
+/// Glyphs across and down the window.
+const COLUMNS: usize = 8;
+const ROWS: usize = 4;
+/// Most points along each axis the resolution slider goes to.
+const MAX_RESOLUTION: i32 = 12;
+/// Most lines per resolution the density slider goes to.
+const MAX_DENSITY: i32 = 8;
+/// Furthest seed the seed slider goes to, though higher ones can be typed in.
+const MAX_SEED: u64 = 9999;
+const SYMMETRIES: [&str; 7] = [
+    "asymmetric",
+    "horizontal",
+    "vertical",
+    "horizontalvertical",
+    "rotation90",
+    "rotation180",
+    "rotational3",
+];
+const MOTIFS: [&str; 5] = ["orthogonal", "diagonal", "arcs", "curved", "walk"];
+
+/// Open the explorer, starting from the given settings, until its window is closed.
+pub fn run(config: GenerationConfig) {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([960.0, 600.0]),
+        ..eframe::NativeOptions::default()
+    };
+    eframe::run_native(
+        "symbolgen",
+        options,
+        Box::new(|_| Ok(Box::new(Explorer::new(config)))),
+    )
+    .expect("Couldn't open window");
+}
+
+struct Explorer {
+    config: GenerationConfig,
+    /// First seed of the glyphs shown
+    seed: u64,
+    /// The settings and seed the glyphs were generated with, and the glyphs, or the error
+    /// generating them
+    generated: Option<(AlphabetConfig, u64, Result<Vec<Glyph>, String>)>,
+    /// File to export the sheet to
+    export_path: String,
+    /// What happened on the last export
+    status: String,
+}
+
+impl Explorer {
+    fn new(mut config: GenerationConfig) -> Self {
+        // One alphabet for every glyph, as the third row of a sheet would have by default
+        config.alphabet.resolution.get_or_insert(4);
+        Self {
+            config,
+            seed: 0,
+            generated: None,
+            export_path: "symbolgen.svg".to_owned(),
+            status: String::new(),
+        }
+    }
+
+    /// The glyphs for the current settings, generating them again if they've changed.
+    fn glyphs(&mut self) -> &Result<Vec<Glyph>, String> {
+        let current = matches!(
+            &self.generated,
+            Some((alphabet, seed, _)) if *alphabet == self.config.alphabet && *seed == self.seed
+        );
+        if !current {
+            let glyphs = self
+                .config
+                .alphabet
+                .build(0)
+                .and_then(|alphabet| {
+                    alphabet.generate_batch(self.seed..self.seed + (COLUMNS * ROWS) as u64)
+                })
+                .map_err(|error| error.to_string());
+            self.generated = Some((self.config.alphabet.clone(), self.seed, glyphs));
+        }
+        let (_, _, glyphs) = self.generated.as_ref().expect("Glyphs were just generated");
+        glyphs
+    }
+
+    /// Write the glyphs shown as an SVG sheet, and the settings beside it as TOML, for the
+    /// sheet command to draw again.
+    fn export(&mut self) -> Result<String, String> {
+        let glyphs = self.glyphs().clone()?;
+        let rows: Vec<Vec<Glyph>> = glyphs.chunks(COLUMNS).map(<[Glyph]>::to_vec).collect();
+        let mut config = self.config.clone();
+        config.sheet.rows = ROWS as i32;
+        config.sheet.columns = COLUMNS as i32;
+        let drawing = sheet_drawing(&config.sheet, &rows, None);
+
+        let path = Path::new(&self.export_path);
+        let file = File::create(path).map_err(|error| error.to_string())?;
+        SvgRenderer::default()
+            .render(&drawing, &mut BufWriter::new(file))
+            .map_err(|error| error.to_string())?;
+        let settings = path.with_extension("toml");
+        let toml = config.to_toml().map_err(|error| error.to_string())?;
+        std::fs::write(&settings, toml).map_err(|error| error.to_string())?;
+        Ok(format!(
+            "Wrote {} and {}, from seed {}",
+            path.display(),
+            settings.display(),
+            self.seed
+        ))
+    }
+
+    fn settings(&mut self, ui: &mut egui::Ui) {
+        let alphabet = &mut self.config.alphabet;
+        let resolution = alphabet.resolution.get_or_insert(4);
+        ui.add(egui::Slider::new(resolution, 2..=MAX_RESOLUTION).text("resolution"));
+        ui.add(egui::Slider::new(&mut alphabet.density, 1..=MAX_DENSITY).text("density"));
+        choose(ui, "symmetry", &SYMMETRIES, &mut alphabet.symmetry);
+        choose(ui, "motif", &MOTIFS, &mut alphabet.motif);
+
+        ui.separator();
+        ui.add(egui::Slider::new(&mut self.seed, 0..=MAX_SEED).text("seed"));
+        ui.horizontal(|ui| {
+            let page = (COLUMNS * ROWS) as u64;
+            if ui.button("◀ Previous").clicked() {
+                self.seed = self.seed.saturating_sub(page);
+            }
+            if ui.button("Next ▶").clicked() {
+                self.seed = self.seed.saturating_add(page);
+            }
+        });
+
+        ui.separator();
+        ui.label("Export to");
+        ui.text_edit_singleline(&mut self.export_path);
+        if ui.button("Export").clicked() {
+            self.status = self.export().unwrap_or_else(|error| error);
+        }
+        ui.label(&self.status);
+    }
+
+    fn sheet(&mut self, ui: &mut egui::Ui) {
+        let glyphs = match self.glyphs() {
+            Ok(glyphs) => glyphs.clone(),
+            Err(error) => {
+                ui.label(format!("Can't generate glyphs: {}", error));
+                return;
+            }
+        };
+        let area = ui.available_rect_before_wrap();
+        let cell = (area.width() / COLUMNS as f32).min(area.height() / ROWS as f32);
+        let color = ui.visuals().text_color();
+        let painter = ui.painter();
+        for (index, glyph) in glyphs.iter().enumerate() {
+            let corner = area.min
+                + egui::vec2(
+                    (index % COLUMNS) as f32 * cell,
+                    (index / COLUMNS) as f32 * cell,
+                );
+            // A glyph in the middle half of its cell, with its seed under it
+            let size = cell / 2.0;
+            let origin = corner + egui::vec2(cell / 4.0, cell / 4.0);
+            for polyline in glyph.to_polylines() {
+                let points = polyline
+                    .iter()
+                    .map(|point| origin + egui::vec2(point.x as f32, point.y as f32) * size)
+                    .collect();
+                painter.add(egui::Shape::line(
+                    points,
+                    egui::Stroke::new((size / 24.0).max(1.0), color),
+                ));
+            }
+            painter.text(
+                corner + egui::vec2(cell / 2.0, cell * 7.0 / 8.0),
+                egui::Align2::CENTER_CENTER,
+                glyph.seed().to_string(),
+                egui::FontId::proportional(12.0),
+                ui.visuals().weak_text_color(),
+            );
+        }
+    }
+}
+
+impl eframe::App for Explorer {
+    fn update(&mut self, context: &egui::Context, _: &mut eframe::Frame) {
+        egui::SidePanel::left("settings").show(context, |ui| self.settings(ui));
+        egui::CentralPanel::default().show(context, |ui| self.sheet(ui));
+    }
+}
+
+/// A drop down list of the named choices for a setting, setting it to the one chosen.
+fn choose<T>(ui: &mut egui::Ui, label: &str, names: &[&str], value: &mut T)
+where
+    T: std::fmt::Display + std::str::FromStr,
+{
+    let selected = value.to_string();
+    egui::ComboBox::from_label(label)
+        .selected_text(&selected)
+        .show_ui(ui, |ui| {
+            for name in names {
+                if ui.selectable_label(selected == *name, *name).clicked() {
+                    if let Ok(choice) = name.parse() {
+                        *value = choice;
+                    }
+                }
+            }
+        });
+}
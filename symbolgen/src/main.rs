@@ -1,78 +1,2996 @@
+use std::collections::HashSet;
+use std::fmt;
 use std::fs::File;
-use std::io::{stdout, Write};
-use std::path::PathBuf;
+use std::io::{stdin, stdout, BufRead, BufWriter, Cursor, IsTerminal, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
 
-use cairo::{Context, Format, ImageSurface, LineCap};
+use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
 use structopt::StructOpt;
-use symbolgen_core::{Alphabet, Motif, Symmetry, Vector};
+#[cfg(feature = "font")]
+use symbolgen_core::to_font;
+#[cfg(feature = "font-source")]
+use symbolgen_core::{to_sfd, to_ufo, FontOptions};
+use symbolgen_core::{
+    write_json, Algorithm, Align, Alphabet, CharacterMap, Color, Comparison, Condition, Edge,
+    Error, GenerationConfig, Glyph, GlyphFilter, GlyphSet, GlyphStats, Identicon, Lattice,
+    Manifest, Metric, MetricsOptions, Motif, NamedSet, Paper, Point, SeedDerivation, SheetLayout,
+    Spacing, Sweep, SweepAxis, Symmetry, TextLayout, Theme, Unit, Vector, ALPHANUMERIC,
+};
+use symbolgen_render::{
+    png, Anchor, Drawing, GraphicsProtocol, HpglRenderer, InlineRenderer, Label, Renderer,
+    RgbaStyle, SvgRenderer, TerminalRenderer, TerminalStyle,
+};
+#[cfg(feature = "cairo")]
+use symbolgen_render::{PdfRenderer, PngRenderer};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+#[cfg(feature = "catalog")]
+mod catalog;
+#[cfg(feature = "gui")]
+mod gui;
+#[cfg(feature = "window")]
+mod window;
 
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "symbolgen",
     about = "Generate alphabets of configurable symbols."
 )]
-struct Options {
-    /// Output file, stdout if not present
+enum Command {
+    /// Draw a sheet of glyphs, each row from an alphabet one step finer than the last.
+    Sheet(SheetOptions),
+    /// Draw a sheet comparing alphabet settings, with one varying down the rows and another
+    /// across the columns.
+    Sweep(SweepOptions),
+    /// Draw a single glyph with the given seed.
+    Glyph(GlyphOptions),
+    /// Write text in an alphabet's glyphs, each character standing for the glyph of a seed.
+    Text(TextOptions),
+    /// Draw the glyphs named in a set file, as a sheet with each glyph's name under it, or
+    /// with --split as a file each.
+    Set(SetOptions),
+    /// Draw a single glyph standing in for some content, like an avatar for an email address.
+    Identicon(IdenticonOptions),
+    /// Draw the same sheet as a PNG made by symbolgen, using the settings saved in it.
+    Reproduce(ReproduceOptions),
+    /// Generate the glyphs in a manifest again, and check their geometry is exactly the same.
+    Verify(VerifyOptions),
+    /// Measure glyphs with a range of seeds, printing each glyph's measurements and a
+    /// histogram of each across them all.
+    Stats(StatsOptions),
+    /// Scan glyphs with a range of seeds for ones meeting thresholds on their measurements,
+    /// printing the seed and measurements of each.
+    Search(SearchOptions),
+    /// Draw glyphs in the terminal, as braille dots or blocks.
+    Preview(PreviewOptions),
+    /// Breed glyphs to taste in the terminal. Each generation is drawn as a numbered grid, and
+    /// the favourites picked from it are kept and crossed with each other, with mutations, to
+    /// make the next. With --output, the last favourites are written there as a sheet on
+    /// finishing. Bred glyphs can't be generated again from their seeds, so JSON, which holds
+    /// their segments, keeps them best.
+    Evolve(EvolveOptions),
+    /// Make a TrueType font, or a UFO or FontForge font source, of an alphabet's glyphs, with
+    /// each stroke widened into an outline, to type in it or refine in a font editor. Each
+    /// character is given a different glyph, or those of a character map or set file.
+    #[cfg(feature = "font-source")]
+    Font(FontExportOptions),
+    /// Keep track of glyphs worth keeping in a catalog file, with tags and ratings.
+    #[cfg(feature = "catalog")]
+    Catalog(CatalogCommand),
+    /// Open a window to explore settings in, with sliders and lists of choices, a sheet of
+    /// glyphs redrawn as they change, and a button to export the sheet.
+    #[cfg(feature = "gui")]
+    Gui(GuiOptions),
+    /// Open a window showing a sheet, without writing any files. The arrow keys, page up and
+    /// page down turn to the sheets of seeds before and after, home to the first, and escape
+    /// or q closes it.
+    #[cfg(feature = "window")]
+    Window(WindowOptions),
+}
+
+/// Settings for the alphabets glyphs are generated from, shared by every command.
+#[derive(Debug, StructOpt)]
+struct GenerationOptions {
+    /// TOML file of generation and sheet layout settings, overridden by any other options
+    #[structopt(long = "config", parse(from_os_str))]
+    config: Option<PathBuf>,
+
+    /// Profile in the config file to use, from its [profiles.NAME] tables, whose settings
+    /// take the place of the rest of the file's
+    #[structopt(long = "profile", requires = "config")]
+    profile: Option<String>,
+
+    /// The number of points along each axis of every glyph. By default the first row has 2
+    /// and each row after has one more.
+    #[structopt(long = "resolution")]
+    resolution: Option<i32>,
+
+    /// The number of lines to draw per resolution, 3 by default.
+    #[structopt(long = "density")]
+    density: Option<i32>,
+
+    /// Kind of segment to draw: orthogonal, diagonal (the default), arcs, curved, or walk for
+    /// a single meandering stroke.
+    #[structopt(long = "motif")]
+    motif: Option<Motif>,
+
+    /// Symmetry to use in generation, asymmetric by default. Separate several with commas to
+    /// apply them in order.
+    #[structopt(long = "symmetry")]
+    symmetry: Option<Symmetry>,
+
+    /// Edge behaviour, either clamping lines (the default) or wrapping them around.
+    #[structopt(long = "edge")]
+    edge: Option<Edge>,
+
+    /// Arrangement of points to draw between: square (the default), hexagonal, triangular, or
+    /// polar rings and spokes (e.g. polar4x12). Pair polar lattices with rotational symmetry of
+    /// the same order.
+    #[structopt(long = "lattice")]
+    lattice: Option<Lattice>,
+
+    /// Version of the generation algorithm, the latest by default. Set this to keep
+    /// reproducing glyphs made with an older release.
+    #[structopt(long = "algorithm")]
+    algorithm: Option<Algorithm>,
+}
+
+/// Where and how drawings are written, shared by every command.
+#[derive(Debug, StructOpt)]
+struct OutputOptions {
+    /// Output file, stdout if not present. With --split, the directory to write files to,
+    /// the current directory if not present. Sheets written to a .zip file are split into an
+    /// archive of files named as for --split, with a manifest.json of which glyph is in each.
     #[structopt(long = "output", parse(from_os_str))]
     output: Option<PathBuf>,
 
-    /// Symmetry to use in generation.
-    #[structopt(long = "symmetry", default_value = "asymmetric")]
-    symmetry: Symmetry,
+    /// Output format: png, svg, pdf, hpgl, json, zip or terminal. JSON holds each glyph's seed
+    /// and segments, unscaled, with the settings that generated them. Terminal draws each page
+    /// in the terminal, as chosen by --graphics. Defaults to the output file's extension, or
+    /// png, or svg when built without cairo. PNG, PDF and zip aren't written to a terminal.
+    #[structopt(long = "format")]
+    format: Option<OutputFormat>,
+
+    /// How --format terminal draws: kitty or sixel for an image in that graphics protocol,
+    /// text for braille dots, or auto for the protocol the terminal seems to have, if any, or
+    /// else text
+    #[structopt(long = "graphics", default_value = "auto")]
+    graphics: Graphics,
+
+    /// Size of each PDF page, in points such as 595x842 for A4, or in mm, cm or in such as
+    /// 210x297mm. The drawing is put in the middle, scaled to fit if its units are pixels.
+    /// Defaults to the size of the drawing.
+    #[cfg_attr(not(feature = "cairo"), allow(dead_code))]
+    #[structopt(long = "page-size")]
+    page_size: Option<PageSize>,
+
+    /// Paper to size each PDF page to: a3, a4, a5, letter or legal, as for --page-size
+    #[cfg_attr(not(feature = "cairo"), allow(dead_code))]
+    #[structopt(long = "paper", conflicts_with = "page-size")]
+    paper: Option<Paper>,
+
+    /// Turn the paper on its side
+    #[cfg_attr(not(feature = "cairo"), allow(dead_code))]
+    #[structopt(long = "landscape", requires = "paper")]
+    landscape: bool,
+
+    /// Pixels to the inch in PNG output and terminal images, for drawings in mm, cm, in or pt
+    #[structopt(long = "dpi", default_value = "96")]
+    dpi: f64,
+
+    /// Colours to draw in: light for black on white, dark for light grey on near black, or
+    /// rows or glyphs for a different colour for each row or glyph. Defaults to the config
+    /// file's [theme], if any.
+    #[structopt(long = "theme")]
+    theme: Option<Theme>,
+
+    /// Colour of the glyphs and text, as #rgb, #rrggbb or #rrggbbaa in hex, or a CSS colour
+    /// name, in place of the theme's. Black by default.
+    #[structopt(long = "stroke-color")]
+    stroke_color: Option<Color>,
+
+    /// Colour to fill the background with, as for --stroke-color, in place of the theme's. PNG
+    /// backgrounds are white by default, and other formats have none.
+    #[structopt(long = "background-color")]
+    background_color: Option<Color>,
+
+    /// Leave the background transparent, including in PNG output
+    #[structopt(long = "transparent", conflicts_with = "background-color")]
+    transparent: bool,
+
+    /// Don't record the settings used in PNG output. Otherwise they are saved in text chunks,
+    /// for the reproduce command to read back.
+    #[structopt(long = "no-metadata")]
+    no_metadata: bool,
+}
+
+/// The arrangement of glyphs on a sheet.
+#[derive(Debug, StructOpt)]
+struct LayoutOptions {
+    /// Rows of glyphs on the sheet, 4 by default
+    #[structopt(long = "rows")]
+    rows: Option<i32>,
+
+    /// Glyphs in each row, 26 by default
+    #[structopt(long = "columns")]
+    columns: Option<i32>,
+
+    #[structopt(flatten)]
+    drawing: DrawingOptions,
+}
+
+/// How glyphs are drawn on a sheet, whatever decides its rows and columns.
+#[derive(Debug, StructOpt)]
+struct DrawingOptions {
+    /// Size of each glyph in pixels, 25 by default
+    #[structopt(long = "scale")]
+    scale: Option<f64>,
+
+    /// Gap between glyphs and around the edge of the sheet, 25 by default
+    #[structopt(long = "spacing")]
+    spacing: Option<f64>,
+
+    /// Width of the lines glyphs are drawn with, 4 by default
+    #[structopt(long = "line-width")]
+    line_width: Option<f64>,
+
+    /// Space around the glyphs, inside the page as trimmed, 0 by default
+    #[structopt(long = "margin")]
+    margin: Option<f64>,
+
+    /// Space around the trimmed page that the background runs into, 0 by default
+    #[structopt(long = "bleed")]
+    bleed: Option<f64>,
+
+    /// Mark the corners of the trimmed page with hairlines outside the bleed
+    #[structopt(long = "crop-marks")]
+    crop_marks: bool,
+
+    /// Unit of the scale, spacing and line width: px (the default), pt, mm, cm or in.
+    /// Drawings in units other than pixels are drawn at their true size.
+    #[structopt(long = "units")]
+    units: Option<Unit>,
+
+    /// Split the sheet across pages with this many rows each, rather than as many as the sheet
+    /// has. PDFs hold every page, while other formats write a file for each, numbered after
+    /// the output file.
+    #[structopt(long = "rows-per-page")]
+    rows_per_page: Option<i32>,
+}
+
+/// Which glyphs are drawn on a sheet.
+#[derive(Debug, StructOpt)]
+struct SeedOptions {
+    /// Derive each glyph's seed from this project seed, rather than numbering glyphs from zero
+    #[structopt(long = "project-seed")]
+    project_seed: Option<u64>,
+
+    /// Number glyphs from this index, rather than from zero
+    #[structopt(long = "seed-start", default_value = "0")]
+    seed_start: u64,
+
+    /// Draw this many glyphs, rather than filling every row. Glyphs that don't fit continue
+    /// on more pages.
+    #[structopt(long = "count")]
+    count: Option<u64>,
+
+    /// Draw glyphs with exactly these seeds, such as 3,17,40-50, continuing on more pages as
+    /// for --count.
+    #[structopt(long = "seeds", conflicts_with_all = &["project-seed", "seed-start", "count"])]
+    seeds: Option<SeedList>,
+
+    /// Only draw glyphs meeting every one of these conditions, such as
+    /// lines>=6,connected,spans-grid, skipping seeds whose glyphs don't. Conditions compare
+    /// lines, length, coverage, symmetry, width or height with a value.
+    #[structopt(long = "filter")]
+    filter: Option<GlyphFilter>,
+}
+
+/// Seeds to try for the glyphs on a sheet, and the filter their glyphs must pass.
+struct Selection<'a> {
+    /// Seeds in the order they're tried, which may go on forever
+    seeds: Box<dyn Iterator<Item = u64> + 'a>,
+    /// Number of glyphs wanted, if there are enough seeds passing the filter
+    count: usize,
+    filter: Option<&'a GlyphFilter>,
+}
+
+impl<'a> From<&'a SeedList> for Selection<'a> {
+    fn from(seeds: &'a SeedList) -> Self {
+        Self {
+            seeds: Box::new(seeds.0.iter().copied()),
+            count: seeds.0.len(),
+            filter: None,
+        }
+    }
+}
+
+/// Writing each glyph of a sheet to its own file.
+#[derive(Debug, StructOpt)]
+struct SplitOptions {
+    /// Write each glyph to its own file, rather than all of them to a sheet
+    #[structopt(long = "split")]
+    split: bool,
+
+    /// Name of each file written with --split or into a zip archive. {seed}, {index}, {row}
+    /// and {column} are replaced by the glyph's, and may be zero padded to a width like
+    /// {seed:04}. {name} is replaced by the glyph's name in a set file, or its seed elsewhere.
+    #[structopt(long = "name-template", default_value = "glyph-{index:04}.png")]
+    name_template: NameTemplate,
+}
+
+/// Text written on a sheet, besides its glyphs.
+#[derive(Debug, Default, StructOpt)]
+struct AnnotationOptions {
+    /// Write each glyph's seed in small text under it
+    #[structopt(long = "label-seeds")]
+    label_seeds: bool,
+
+    /// Title to write above the glyphs on each page
+    #[structopt(long = "title")]
+    title: Option<String>,
+
+    /// Smaller text to write under the title
+    #[structopt(long = "subtitle")]
+    subtitle: Option<String>,
+
+    /// List the settings and seeds used above the glyphs, under any title
+    #[structopt(long = "summary")]
+    summary: bool,
+}
+
+#[derive(Debug, StructOpt)]
+struct SheetOptions {
+    #[structopt(flatten)]
+    annotations: AnnotationOptions,
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+    #[structopt(flatten)]
+    layout: LayoutOptions,
+    #[structopt(flatten)]
+    seeds: SeedOptions,
+    #[structopt(flatten)]
+    split: SplitOptions,
+    #[structopt(flatten)]
+    output: OutputOptions,
+
+    /// Also write a manifest of the version, settings, seeds and a hash of each glyph's
+    /// geometry to this file, for the verify command to check
+    #[structopt(long = "manifest", parse(from_os_str))]
+    manifest: Option<PathBuf>,
+
+    /// Load settings from this config file, as for --config, then keep running and draw the
+    /// sheet again each time the file is saved
+    #[structopt(long = "watch", parse(from_os_str), conflicts_with = "config")]
+    watch: Option<PathBuf>,
+}
+
+#[derive(Debug, StructOpt)]
+struct TextOptions {
+    /// Text to write. Letters are written the same in either case, and each line of the text
+    /// starts a new line.
+    text: String,
+
+    /// Characters and the seeds of their glyphs, as a TOML file of lines like `a = 17`. By
+    /// default, a to z and 0 to 9 each have a different glyph, from seeds counting up from
+    /// --seed-start.
+    #[structopt(long = "map", parse(from_os_str))]
+    map: Option<PathBuf>,
+
+    /// First seed to look for glyphs for the default map from, unless --map is given
+    #[structopt(long = "seed-start", default_value = "0")]
+    seed_start: u64,
+
+    /// Print the map of characters to seeds as TOML, to edit and use with --map, rather than
+    /// writing the text
+    #[structopt(long = "print-map")]
+    print_map: bool,
+
+    /// Row of the sheet whose alphabet to write in, as for the glyph command
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Width and height of each glyph, in the sheet's units
+    #[structopt(long = "size", default_value = "64")]
+    size: f64,
+
+    /// Gap between glyphs, as a fraction of their size
+    #[structopt(long = "letter-spacing", default_value = "0.25")]
+    letter_spacing: f64,
+
+    /// Width of a space, as a fraction of the size of a glyph
+    #[structopt(long = "word-spacing", default_value = "0.75")]
+    word_spacing: f64,
+
+    /// Distance from the top of one line to the next, as a fraction of the size of a glyph
+    #[structopt(long = "line-spacing", default_value = "1.25")]
+    line_spacing: f64,
+
+    /// Width to wrap lines at, between words, in the sheet's units
+    #[structopt(long = "max-width")]
+    max_width: Option<f64>,
+
+    /// Alignment of lines shorter than the widest: left, centre or right
+    #[structopt(long = "align", default_value = "left")]
+    align: Align,
+
+    /// Widths of glyphs: monospace, each as wide as its cell, or proportional, each as wide
+    /// as its ink and side bearings
+    #[structopt(long = "spacing", default_value = "monospace")]
+    spacing: Spacing,
+
+    /// Gap either side of the ink of proportionally spaced glyphs, as a fraction of their size
+    #[structopt(long = "side-bearing", default_value = "0.1")]
+    side_bearing: f64,
+
+    #[structopt(flatten)]
+    output: OutputOptions,
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct SetOptions {
+    /// Set file, in TOML, listing each glyph's name and seed, and the settings that generate
+    /// it
+    #[structopt(parse(from_os_str))]
+    set: PathBuf,
+
+    #[structopt(flatten)]
+    split: SplitOptions,
+    #[structopt(flatten)]
+    output: OutputOptions,
+    #[structopt(flatten)]
+    layout: LayoutOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct SweepOptions {
+    /// Setting to vary down the rows, and its values, such as resolution=2-8. Values are
+    /// separated by commas, and symmetries applied in turn joined with +.
+    #[structopt(long = "rows")]
+    rows: SweepAxis,
+
+    /// Setting to vary across the columns, and its values, such as density=1-6 or
+    /// motif=diagonal,arcs
+    #[structopt(long = "columns")]
+    columns: SweepAxis,
+
+    /// Seed of every glyph
+    #[structopt(long = "seed", default_value = "0")]
+    seed: u64,
+
+    /// Don't label each row and column with its setting, or add a caption of the settings
+    /// that stay the same
+    #[structopt(long = "no-labels")]
+    no_labels: bool,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+    #[structopt(flatten)]
+    drawing: DrawingOptions,
+    #[structopt(flatten)]
+    output: OutputOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct GlyphOptions {
+    /// Seed of the glyph
+    seed: u64,
+
+    /// Row of the sheet whose alphabet to draw the glyph from, which sets its resolution
+    /// unless --resolution is given
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Width and height of the image
+    #[structopt(long = "size", default_value = "128")]
+    size: f64,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+    #[structopt(flatten)]
+    output: OutputOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct ReproduceOptions {
+    /// PNG file to reproduce
+    #[structopt(parse(from_os_str))]
+    input: PathBuf,
+
+    #[structopt(flatten)]
+    split: SplitOptions,
+    #[structopt(flatten)]
+    output: OutputOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct StatsOptions {
+    /// Measure glyphs numbered from this seed
+    #[structopt(long = "seed-start", default_value = "0")]
+    seed_start: u64,
+
+    /// Number of glyphs to measure
+    #[structopt(long = "count", default_value = "100")]
+    count: u64,
+
+    /// Measure glyphs with exactly these seeds, such as 3,17,40-50
+    #[structopt(long = "seeds", conflicts_with_all = &["seed-start", "count"])]
+    seeds: Option<SeedList>,
+
+    /// Row of the sheet whose alphabet to generate glyphs from, as for the glyph command
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Bars in each histogram
+    #[structopt(long = "bins", default_value = "10")]
+    bins: usize,
+
+    /// Only print the histograms, not each glyph's measurements
+    #[structopt(long = "summary")]
+    summary: bool,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct SearchOptions {
+    /// Scan glyphs numbered from this seed
+    #[structopt(long = "seed-start", default_value = "0")]
+    seed_start: u64,
+
+    /// Most seeds to scan before giving up
+    #[structopt(long = "scan", default_value = "10000")]
+    scan: u64,
+
+    /// Stop after finding this many seeds
+    #[structopt(long = "limit", default_value = "50")]
+    limit: usize,
+
+    /// Row of the sheet whose alphabet to generate glyphs from, as for the glyph command
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Only find glyphs with at least this many lines
+    #[structopt(long = "min-lines")]
+    min_lines: Option<usize>,
+
+    /// Only find glyphs with at most this many lines
+    #[structopt(long = "max-lines")]
+    max_lines: Option<usize>,
+
+    /// Only find glyphs at least this symmetric, from 0 for not at all to 1 for matching
+    /// themselves exactly when reflected or turned
+    #[structopt(long = "symmetry-score")]
+    symmetry_score: Option<f64>,
+
+    /// Only find glyphs passing through at least this fraction of their cell
+    #[structopt(long = "min-coverage")]
+    min_coverage: Option<f64>,
+
+    /// Any other conditions glyphs must meet, as for the sheet command's --filter
+    #[structopt(long = "filter")]
+    filter: Option<GlyphFilter>,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct PreviewOptions {
+    /// Seed of the glyph
+    #[structopt(long = "seed", default_value = "0")]
+    seed: u64,
+
+    /// Draw a glyph for each of these seeds, such as 3,17,40-50, one under another
+    #[structopt(long = "seeds", conflicts_with = "seed")]
+    seeds: Option<SeedList>,
+
+    /// Row of the sheet whose alphabet to draw glyphs from, as for the glyph command
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Characters across each glyph
+    #[structopt(long = "width", default_value = "20")]
+    width: usize,
+
+    /// Draw with half blocks, two to a character, rather than braille dots, for fonts without
+    /// braille
+    #[structopt(long = "blocks")]
+    blocks: bool,
+
+    /// How to draw: kitty or sixel for an image in that graphics protocol, text for
+    /// characters, or auto for the protocol the terminal seems to have, if any, or else text
+    #[structopt(long = "graphics", default_value = "auto")]
+    graphics: Graphics,
+
+    /// Width and height of images drawn with a graphics protocol, in pixels
+    #[structopt(long = "size", default_value = "128")]
+    size: f64,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct EvolveOptions {
+    /// Seed of the first glyph in the first generation, which has the seeds after it. Later
+    /// generations' seeds are derived from it.
+    #[structopt(long = "seed-start", default_value = "0")]
+    seed_start: u64,
+
+    /// Glyphs in each generation, including the favourites kept from the last
+    #[structopt(long = "population", default_value = "12")]
+    population: usize,
+
+    /// Odds of each segment of a child being replaced, from 0 to 1. A child with none
+    /// replaced gains one.
+    #[structopt(long = "mutation", default_value = "0.2")]
+    mutation: f64,
+
+    /// Row of the sheet whose alphabet to breed glyphs from, as for the glyph command
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Characters across each glyph
+    #[structopt(long = "width", default_value = "10")]
+    width: usize,
+
+    #[structopt(flatten)]
+    output: OutputOptions,
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[cfg(feature = "font-source")]
+#[derive(Debug, StructOpt)]
+struct FontExportOptions {
+    /// Font to write: a .ttf file, or a font source for font editors with each glyph's seed
+    /// noted, a .ufo folder or a FontForge .sfd file
+    #[structopt(long = "output", parse(from_os_str))]
+    output: PathBuf,
+
+    /// Characters to give glyphs, as a range of characters or code points like a-z or
+    /// U+E000-U+E0FF. Defaults to printable ASCII.
+    #[structopt(long = "characters", default_value = "!-~")]
+    characters: CharacterRange,
+
+    /// Characters and the seeds of their glyphs, as a TOML file for the text command, in
+    /// place of --characters
+    #[structopt(long = "map", parse(from_os_str))]
+    map: Option<PathBuf>,
+
+    /// Set file of the glyphs to use, each with the character it has or one in the private
+    /// use area, in place of --characters. The set's own settings generate them.
+    #[structopt(long = "set", parse(from_os_str), conflicts_with = "map")]
+    set: Option<PathBuf>,
+
+    /// First seed to look for glyphs for --characters from
+    #[structopt(long = "seed-start", default_value = "0")]
+    seed_start: u64,
+
+    /// Row of the sheet whose alphabet to use, as for the glyph command
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Name the font is installed and picked under
+    #[structopt(long = "family", default_value = "Symbolgen")]
+    family: String,
+
+    /// Width of strokes, as a fraction of the height of a glyph
+    #[structopt(long = "weight", default_value = "0.08")]
+    weight: f64,
+
+    /// Widths of glyphs: monospace, each as wide as its cell, or proportional, each as wide
+    /// as its ink and side bearings
+    #[structopt(long = "spacing", default_value = "monospace")]
+    spacing: Spacing,
+
+    /// Gap either side of the ink of proportionally spaced glyphs, as a fraction of their
+    /// height
+    #[structopt(long = "side-bearing", default_value = "0.1")]
+    side_bearing: f64,
+
+    /// Width of a space, as a fraction of the height of a glyph
+    #[structopt(long = "word-spacing", default_value = "0.75")]
+    word_spacing: f64,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[cfg(feature = "catalog")]
+#[derive(Debug, StructOpt)]
+enum CatalogCommand {
+    /// Add glyphs to the catalog, or add tags to and rate glyphs already in it.
+    Add(CatalogAddOptions),
+    /// Print the seed, row, rating, tags and settings of each glyph in the catalog.
+    List(CatalogListOptions),
+    /// Draw the glyphs in the catalog as a sheet, each generated again from its settings.
+    Export(CatalogExportOptions),
+}
+
+#[cfg(feature = "catalog")]
+#[derive(Debug, StructOpt)]
+struct CatalogOptions {
+    /// Catalog file, created if there isn't one
+    #[structopt(long = "catalog", default_value = "symbolgen.db", parse(from_os_str))]
+    catalog: PathBuf,
+}
+
+/// Which glyphs in the catalog to use.
+#[cfg(feature = "catalog")]
+#[derive(Debug, StructOpt)]
+struct CatalogQueryOptions {
+    /// Only glyphs with this tag, which can be given more than once for glyphs with every
+    /// tag given
+    #[structopt(long = "tag", number_of_values = 1)]
+    tags: Vec<String>,
+
+    /// Only glyphs rated at least this
+    #[structopt(long = "min-rating")]
+    min_rating: Option<u8>,
+}
+
+#[cfg(feature = "catalog")]
+#[derive(Debug, StructOpt)]
+struct CatalogAddOptions {
+    /// Seeds of the glyphs, such as 3,17,40-50
+    #[structopt(long = "seeds")]
+    seeds: SeedList,
+
+    /// Row of the sheet whose alphabet generates the glyphs, as for the glyph command
+    #[structopt(long = "row", default_value = "2")]
+    row: i32,
+
+    /// Tag for the glyphs, which can be given more than once
+    #[structopt(long = "tag", number_of_values = 1)]
+    tags: Vec<String>,
+
+    /// Rating for the glyphs, such as 1 to 5
+    #[structopt(long = "rating")]
+    rating: Option<u8>,
+
+    #[structopt(flatten)]
+    catalog: CatalogOptions,
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[cfg(feature = "catalog")]
+#[derive(Debug, StructOpt)]
+struct CatalogListOptions {
+    #[structopt(flatten)]
+    query: CatalogQueryOptions,
+    #[structopt(flatten)]
+    catalog: CatalogOptions,
+}
+
+#[cfg(feature = "catalog")]
+#[derive(Debug, StructOpt)]
+struct CatalogExportOptions {
+    #[structopt(flatten)]
+    query: CatalogQueryOptions,
+    #[structopt(flatten)]
+    catalog: CatalogOptions,
+    #[structopt(flatten)]
+    output: OutputOptions,
+    #[structopt(flatten)]
+    layout: LayoutOptions,
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, StructOpt)]
+struct GuiOptions {
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+}
+
+#[cfg(feature = "window")]
+#[derive(Debug, StructOpt)]
+struct WindowOptions {
+    /// Number the first sheet's glyphs from this seed
+    #[structopt(long = "seed-start", default_value = "0")]
+    seed_start: u64,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+    #[structopt(flatten)]
+    layout: LayoutOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct VerifyOptions {
+    /// Manifest written by the sheet command, alone or in a zip archive
+    #[structopt(parse(from_os_str))]
+    manifest: PathBuf,
+}
+
+#[derive(Debug, StructOpt)]
+struct IdenticonOptions {
+    /// File whose contents to draw
+    #[structopt(parse(from_os_str), required_unless_one = &["stdin", "text"])]
+    file: Option<PathBuf>,
+
+    /// Draw the contents of stdin
+    #[structopt(long = "stdin", conflicts_with_all = &["file", "text"])]
+    stdin: bool,
+
+    /// Draw this text, such as an email address. Case and surrounding whitespace are ignored.
+    #[structopt(long = "text", conflicts_with = "file")]
+    text: Option<String>,
+
+    /// Width and height of the image
+    #[structopt(long = "size", default_value = "128")]
+    size: f64,
+
+    #[structopt(flatten)]
+    generation: GenerationOptions,
+    #[structopt(flatten)]
+    output: OutputOptions,
+}
+
+/// File formats drawings can be written in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    Png,
+    Svg,
+    Pdf,
+    Hpgl,
+    Json,
+    Zip,
+    Terminal,
+}
+
+impl OutputFormat {
+    /// Whether the format isn't text, so shouldn't be shown in a terminal.
+    fn is_binary(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Png | OutputFormat::Pdf | OutputFormat::Zip
+        )
+    }
+
+    /// Whether one file can hold a drawing of many pages.
+    fn is_multi_page(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Pdf | OutputFormat::Json | OutputFormat::Terminal
+        )
+    }
+
+    /// The format matching a file's extension, or the default.
+    fn of_file(path: Option<&Path>) -> Result<Self, String> {
+        match path.and_then(Path::extension) {
+            Some(extension) => extension
+                .to_string_lossy()
+                .to_lowercase()
+                .parse()
+                .map_err(|error| format!("{}, choose one with --format", error)),
+            None => Ok(DEFAULT_FORMAT),
+        }
+    }
+}
+
+/// Stop with an error in how the command was used, reported as clap reports its own.
+fn usage_error(message: &str) -> ! {
+    structopt::clap::Error::with_description(message, structopt::clap::ErrorKind::InvalidValue)
+        .exit()
 }
 
-fn generate(options: Options) {
-    let columns = 26;
-    let rows = 4;
+/// The format used when neither the options nor the file name give one.
+#[cfg(feature = "cairo")]
+const DEFAULT_FORMAT: OutputFormat = OutputFormat::Png;
+#[cfg(not(feature = "cairo"))]
+const DEFAULT_FORMAT: OutputFormat = OutputFormat::Svg;
 
-    let scale = 25.0;
-    let spacing = 25.0;
-    let line_width = 4.0;
+impl FromStr for OutputFormat {
+    type Err = String;
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "png" => Ok(OutputFormat::Png),
+            "svg" => Ok(OutputFormat::Svg),
+            "pdf" => Ok(OutputFormat::Pdf),
+            "hpgl" | "plt" => Ok(OutputFormat::Hpgl),
+            "json" => Ok(OutputFormat::Json),
+            "zip" => Ok(OutputFormat::Zip),
+            "terminal" => Ok(OutputFormat::Terminal),
+            _ => Err(format!("Unknown format '{}'", format)),
+        }
+    }
+}
 
-    let canvas_width = spacing as i32 + ((scale + spacing) as i32 * columns);
-    let canvas_height = spacing as i32 + ((scale + spacing) as i32 * rows);
-    let surface = ImageSurface::create(Format::ARgb32, canvas_width, canvas_height)
-        .expect("Couldn't create surface");
-    let context = Context::new(&surface);
+/// How to draw in a terminal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Graphics {
+    Auto,
+    Kitty,
+    Sixel,
+    Text,
+}
 
-    // paint canvas white
-    context.set_source_rgb(1.0, 1.0, 1.0);
-    context.paint();
-    // work with black objects
-    context.set_source_rgb(0.0, 0.0, 0.0);
+impl Graphics {
+    /// The graphics protocol to draw images with, or none to draw with text.
+    fn protocol(&self) -> Option<GraphicsProtocol> {
+        match self {
+            Graphics::Auto => GraphicsProtocol::detect(),
+            Graphics::Kitty => Some(GraphicsProtocol::Kitty),
+            Graphics::Sixel => Some(GraphicsProtocol::Sixel),
+            Graphics::Text => None,
+        }
+    }
+}
 
-    for row_number in 0..rows {
-        let offset_y = spacing + ((scale + spacing) * row_number as f64);
+impl FromStr for Graphics {
+    type Err = String;
+    fn from_str(graphics: &str) -> Result<Self, Self::Err> {
+        match graphics {
+            "auto" => Ok(Graphics::Auto),
+            "kitty" => Ok(Graphics::Kitty),
+            "sixel" => Ok(Graphics::Sixel),
+            "text" => Ok(Graphics::Text),
+            _ => Err(format!("Unknown graphics '{}'", graphics)),
+        }
+    }
+}
 
-        let alphabet = Alphabet::new(row_number + 2, 3, options.symmetry.clone(), Motif::Diagonal);
-        for column_number in 0..columns {
-            let glyph_number = row_number * columns + column_number;
-            let offset_x = spacing + ((scale + spacing) * column_number as f64);
-            let offset = Vector::new(offset_x, offset_y);
+/// Seeds listed one by one, or as inclusive ranges, separated by commas.
+#[derive(Clone, Debug, PartialEq)]
+struct SeedList(Vec<u64>);
 
-            for line in alphabet.generate(glyph_number as u64).lines().iter() {
-                let start = (line.start() * scale) + offset;
-                let end = (line.end() * scale) + offset;
-                context.move_to(start.x, start.y);
-                context.line_to(end.x, end.y);
+impl FromStr for SeedList {
+    type Err = String;
+    fn from_str(list: &str) -> Result<Self, Self::Err> {
+        let mut seeds = Vec::new();
+        for item in list.split(',').map(str::trim) {
+            let invalid = || format!("Seed '{}' isn't a number or range", item);
+            let mut bounds = item.splitn(2, '-').map(str::parse::<u64>);
+            match (bounds.next(), bounds.next()) {
+                (Some(Ok(seed)), None) => seeds.push(seed),
+                (Some(Ok(first)), Some(Ok(last))) if first <= last => seeds.extend(first..=last),
+                _ => return Err(invalid()),
             }
         }
+        Ok(SeedList(seeds))
     }
-    context.set_line_width(line_width);
-    context.set_line_cap(LineCap::Round);
-    context.stroke();
+}
 
-    let mut file: Box<dyn Write> = if let Some(output_path) = options.output {
-        Box::new(File::create(output_path).expect("Couldn't create file"))
-    } else {
-        Box::new(stdout())
-    };
-    surface
-        .write_to_png(&mut file)
-        .expect("Couldn't write to png");
+impl fmt::Display for SeedList {
+    /// The seeds with runs of consecutive seeds as ranges, which parses back to the same list.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut runs: Vec<(u64, u64)> = Vec::new();
+        for &seed in &self.0 {
+            match runs.last_mut() {
+                Some((_, last)) if last.checked_add(1) == Some(seed) => *last = seed,
+                _ => runs.push((seed, seed)),
+            }
+        }
+        let items: Vec<String> = runs
+            .iter()
+            .map(|&(first, last)| match last - first {
+                0 => first.to_string(),
+                1 => format!("{},{}", first, last),
+                _ => format!("{}-{}", first, last),
+            })
+            .collect();
+        write!(f, "{}", items.join(","))
+    }
 }
 
-fn main() {
-    let opt = Options::from_args();
-    generate(opt)
+/// Characters from one to another inclusive, each given as itself or as a code point like
+/// U+0041.
+#[cfg(feature = "font-source")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CharacterRange {
+    first: char,
+    last: char,
+}
+
+#[cfg(feature = "font-source")]
+impl CharacterRange {
+    fn characters(&self) -> String {
+        (self.first..=self.last).collect()
+    }
+}
+
+#[cfg(feature = "font-source")]
+impl FromStr for CharacterRange {
+    type Err = String;
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let character = |end: &str| {
+            let mut characters = end.chars();
+            match (characters.next(), characters.next()) {
+                (Some(character), None) => Some(character),
+                _ => end
+                    .strip_prefix("U+")
+                    .or_else(|| end.strip_prefix("u+"))
+                    .and_then(|code| u32::from_str_radix(code, 16).ok())
+                    .and_then(char::from_u32),
+            }
+        };
+        // Either end may itself be a hyphen, so try splitting at each
+        range
+            .match_indices('-')
+            .find_map(|(index, _)| {
+                let first = character(&range[..index])?;
+                let last = character(&range[index + 1..])?;
+                Some(CharacterRange { first, last }).filter(|_| first <= last)
+            })
+            .ok_or_else(|| format!("Characters '{}' aren't a range like a-z", range))
+    }
+}
+
+/// Width and height of a page, in points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PageSize {
+    width: f64,
+    height: f64,
+}
+
+impl FromStr for PageSize {
+    type Err = String;
+    fn from_str(size: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Page size '{}' isn't WIDTHxHEIGHT", size);
+        let number = size.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let points = match &size[number.len()..] {
+            "" => 1.0,
+            unit => {
+                let unit: Unit = unit.parse().map_err(|_| invalid())?;
+                unit.inches().ok_or_else(invalid)? * 72.0
+            }
+        };
+        let mut parts = number.splitn(2, 'x').map(str::parse::<f64>);
+        match (parts.next(), parts.next()) {
+            (Some(Ok(width)), Some(Ok(height))) if width > 0.0 && height > 0.0 => Ok(PageSize {
+                width: width * points,
+                height: height * points,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Names for files of single glyphs, with placeholders for details of each glyph.
+#[derive(Clone, Debug, PartialEq)]
+struct NameTemplate(String);
+
+impl NameTemplate {
+    /// The name for a glyph, with each placeholder replaced.
+    fn render(
+        &self,
+        seed: u64,
+        index: u64,
+        row: i32,
+        column: i32,
+        glyph_name: Option<&str>,
+    ) -> Result<String, String> {
+        let mut name = String::new();
+        let mut rest = self.0.as_str();
+        while let Some(open) = rest.find('{') {
+            name.push_str(&rest[..open]);
+            let close = rest[open..]
+                .find('}')
+                .ok_or_else(|| format!("Unclosed placeholder in '{}'", self.0))?
+                + open;
+            let placeholder = &rest[open + 1..close];
+            let mut parts = placeholder.splitn(2, ':');
+            let value = match parts.next().unwrap_or_default() {
+                "seed" => seed.to_string(),
+                "index" => index.to_string(),
+                "row" => row.to_string(),
+                "column" => column.to_string(),
+                "name" => glyph_name.map_or_else(|| seed.to_string(), str::to_owned),
+                _ => return Err(format!("Unknown placeholder '{{{}}}'", placeholder)),
+            };
+            let width = match parts.next() {
+                Some(width) => width
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid width in '{{{}}}'", placeholder))?,
+                None => 0,
+            };
+            name.push_str(&format!("{:0>width$}", value, width = width));
+            rest = &rest[close + 1..];
+        }
+        name.push_str(rest);
+        Ok(name)
+    }
+}
+
+impl FromStr for NameTemplate {
+    type Err = String;
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let template = NameTemplate(template.to_owned());
+        template.render(0, 0, 0, 0, None)?;
+        Ok(template)
+    }
+}
+
+impl OutputOptions {
+    /// Width and height of each PDF page in points, from the paper or page size if chosen.
+    #[cfg_attr(not(feature = "cairo"), allow(dead_code))]
+    fn page_points(&self) -> Option<(f64, f64)> {
+        if let Some(paper) = self.paper {
+            let (width, height) = paper.points();
+            return Some(if self.landscape {
+                (height, width)
+            } else {
+                (width, height)
+            });
+        }
+        self.page_size.map(|page| (page.width, page.height))
+    }
+
+    /// The chosen theme, or the config's.
+    fn theme<'a>(&'a self, config: &'a GenerationConfig) -> Option<&'a Theme> {
+        self.theme.as_ref().or(config.theme.as_ref())
+    }
+
+    /// The theme to colour each glyph from, unless a single stroke colour is chosen.
+    fn palette<'a>(&'a self, config: &'a GenerationConfig) -> Option<&'a Theme> {
+        self.theme(config).filter(|_| self.stroke_color.is_none())
+    }
+
+    /// The chosen stroke colour, or the theme's, or the format's default.
+    fn color(&self, theme: Option<&Theme>, default: [u8; 4]) -> [u8; 4] {
+        match (self.stroke_color, theme) {
+            (Some(color), _) => color.0,
+            (None, Some(theme)) => theme.text().0,
+            (None, None) => default,
+        }
+    }
+
+    /// The chosen background colour, or none if transparent, or the theme's, or the format's
+    /// default.
+    fn background(&self, theme: Option<&Theme>, default: Option<[u8; 4]>) -> Option<[u8; 4]> {
+        match (self.background_color, theme) {
+            _ if self.transparent => None,
+            (Some(color), _) => Some(color.0),
+            (None, Some(theme)) => theme.background.map(|color| color.0),
+            (None, None) => default,
+        }
+    }
+
+    /// The chosen format, or the one matching the file's extension, or the default.
+    fn format(&self, path: Option<&Path>) -> OutputFormat {
+        match self.format {
+            Some(format) => format,
+            None => OutputFormat::of_file(path).unwrap_or_else(|error| usage_error(&error)),
+        }
+    }
+}
+
+impl SeedOptions {
+    /// Seeds to try for the glyphs on the sheet. Numbered seeds carry on past the count, to
+    /// make up for any the filter skips.
+    fn selection(&self, sheet: &SheetLayout) -> Selection<'_> {
+        let mut selection = match &self.seeds {
+            Some(seeds) => Selection::from(seeds),
+            None => {
+                let project_seed = self.project_seed;
+                Selection {
+                    seeds: Box::new((self.seed_start..).map(move |index| match project_seed {
+                        Some(seed) => SeedDerivation::new(seed).glyph(index).seed(),
+                        None => index,
+                    })),
+                    count: self
+                        .count
+                        .unwrap_or((sheet.rows * sheet.columns).max(0) as u64)
+                        as usize,
+                    filter: None,
+                }
+            }
+        };
+        selection.filter = self.filter.as_ref();
+        selection
+    }
+}
+
+impl SearchOptions {
+    /// The thresholds given, and any other conditions, as one filter.
+    fn filter(&self) -> GlyphFilter {
+        let mut filter = self.filter.clone().unwrap_or_default();
+        let thresholds = [
+            (
+                Metric::Lines,
+                Comparison::GreaterOrEqual,
+                self.min_lines.map(|lines| lines as f64),
+            ),
+            (
+                Metric::Lines,
+                Comparison::LessOrEqual,
+                self.max_lines.map(|lines| lines as f64),
+            ),
+            (
+                Metric::Symmetry,
+                Comparison::GreaterOrEqual,
+                self.symmetry_score,
+            ),
+            (
+                Metric::Coverage,
+                Comparison::GreaterOrEqual,
+                self.min_coverage,
+            ),
+        ];
+        for (metric, comparison, value) in thresholds {
+            if let Some(value) = value {
+                filter
+                    .conditions
+                    .push(Condition::Compare(metric, comparison, value));
+            }
+        }
+        filter
+    }
+}
+
+impl GenerationOptions {
+    /// Settings from the config file, if any, with options given on the command line applied.
+    fn config(&self) -> GenerationConfig {
+        self.load(self.config.as_deref())
+            .expect("Couldn't load config")
+    }
+
+    /// Settings from the given config file, if any, with options given on the command line
+    /// applied.
+    fn load(&self, path: Option<&Path>) -> Result<GenerationConfig, Error> {
+        let mut config = match path {
+            Some(path) => GenerationConfig::load_profile(path, self.profile.as_deref())?,
+            None => GenerationConfig::default(),
+        };
+        if let Some(resolution) = self.resolution {
+            config.alphabet.resolution = Some(resolution);
+        }
+        if let Some(density) = self.density {
+            config.alphabet.density = density;
+        }
+        if let Some(motif) = &self.motif {
+            config.alphabet.motif = motif.clone();
+        }
+        if let Some(symmetry) = &self.symmetry {
+            config.alphabet.symmetry = symmetry.clone();
+        }
+        if let Some(edge) = &self.edge {
+            config.alphabet.edge = edge.clone();
+        }
+        if let Some(lattice) = &self.lattice {
+            config.alphabet.lattice = lattice.clone();
+        }
+        if let Some(algorithm) = self.algorithm {
+            config.alphabet.algorithm = algorithm;
+        }
+        Ok(config)
+    }
+}
+
+impl LayoutOptions {
+    /// Apply the options given on the command line to a sheet.
+    fn apply(&self, sheet: &mut SheetLayout) {
+        if let Some(rows) = self.rows {
+            sheet.rows = rows;
+        }
+        if let Some(columns) = self.columns {
+            sheet.columns = columns;
+        }
+        self.drawing.apply(sheet);
+    }
+}
+
+impl DrawingOptions {
+    /// Apply the options given on the command line to a sheet.
+    fn apply(&self, sheet: &mut SheetLayout) {
+        if let Some(scale) = self.scale {
+            sheet.scale = scale;
+        }
+        if let Some(spacing) = self.spacing {
+            sheet.spacing = spacing;
+        }
+        if let Some(line_width) = self.line_width {
+            sheet.line_width = line_width;
+        }
+        if let Some(units) = self.units {
+            sheet.units = units;
+        }
+        if let Some(margin) = self.margin {
+            sheet.margin = margin;
+        }
+        if let Some(bleed) = self.bleed {
+            sheet.bleed = bleed;
+        }
+        if self.crop_marks {
+            sheet.crop_marks = true;
+        }
+        if let Some(rows_per_page) = self.rows_per_page {
+            sheet.rows_per_page = Some(rows_per_page);
+        }
+    }
+}
+
+/// Keywords of the PNG text chunks settings are saved in.
+const CONFIG_KEYWORD: &str = "symbolgen:config";
+const SEEDS_KEYWORD: &str = "symbolgen:seeds";
+
+fn sheet(options: &SheetOptions) {
+    match &options.watch {
+        Some(path) => watch(path, || draw_sheet(options, Some(path))),
+        None => {
+            draw_sheet(options, options.generation.config.as_deref()).expect("Couldn't draw sheet")
+        }
+    }
+}
+
+/// Draw a sheet with the settings in the given config file, if any.
+fn draw_sheet(options: &SheetOptions, config: Option<&Path>) -> Result<(), Error> {
+    let mut config = options.generation.load(config)?;
+    options.layout.apply(&mut config.sheet);
+    let selection = options.seeds.selection(&config.sheet);
+    generate(
+        &options.output,
+        &options.split,
+        &options.annotations,
+        options.manifest.as_deref(),
+        &config,
+        selection,
+    )
+}
+
+/// How often to check whether a watched file has changed.
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Draw, then draw again each time the file at the path is changed, until interrupted. Drawing
+/// that fails, as on a half finished edit, is reported and waits for the next change.
+fn watch(path: &Path, mut draw: impl FnMut() -> Result<(), Error>) {
+    let modified = || {
+        std::fs::metadata(path)
+            .and_then(|file| file.modified())
+            .ok()
+    };
+    let mut last = modified();
+    eprintln!("Watching {} for changes, stop with Ctrl+C", path.display());
+    loop {
+        match draw() {
+            Ok(()) => eprintln!("Drew sheet from {}", path.display()),
+            Err(error) => eprintln!("Couldn't draw sheet from {}: {}", path.display(), error),
+        }
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+            let now = modified();
+            // Editors may delete and replace the file when saving, so wait for it to be back
+            if now.is_some() && now != last {
+                last = now;
+                break;
+            }
+        }
+    }
+}
+
+/// Generate glyphs with the selected seeds, filling as many rows of the sheet as they need.
+/// Fails if the settings can't generate glyphs.
+fn generate(
+    output: &OutputOptions,
+    split: &SplitOptions,
+    annotations: &AnnotationOptions,
+    manifest: Option<&Path>,
+    config: &GenerationConfig,
+    selection: Selection,
+) -> Result<(), Error> {
+    let mut sheet = config.sheet.clone();
+    let columns = sheet.columns.max(1);
+    let filter = selection.filter;
+    let (alphabets, rows, skipped) = generate_rows(config, columns as usize, selection)?;
+    let seeds = &SeedList(rows.iter().flatten().map(Glyph::seed).collect());
+
+    // Pages keep the sheet's rows, with as many pages as the seeds need
+    sheet.rows_per_page.get_or_insert(sheet.rows.max(1));
+    sheet.rows = (seeds.0.len() as i32 + columns - 1) / columns;
+    let header = header_lines(annotations, config, seeds, &sheet);
+    if !header.is_empty() {
+        let lines: f64 = header.iter().map(|(_, size)| size * LINE_HEIGHT).sum();
+        sheet.header += lines + sheet.spacing / 2.0;
+    }
+    let sheet = &sheet;
+
+    let record = Manifest {
+        filter: filter.cloned(),
+        skipped,
+        ..Manifest::new(config, &rows)
+    };
+    if let Some(path) = manifest {
+        let file = File::create(path).expect("Couldn't create file");
+        record
+            .write(BufWriter::new(file))
+            .expect("Couldn't write manifest");
+    }
+
+    if output.format(output.output.as_deref()) == OutputFormat::Zip {
+        write_archive(output, split, config, &record, &alphabets, &rows);
+        return Ok(());
+    }
+    if split.split {
+        for (row_number, (alphabet, row)) in (0..).zip(alphabets.iter().zip(&rows)) {
+            for (column_number, glyph) in (0..).zip(row) {
+                write_glyph(
+                    output,
+                    split,
+                    config,
+                    alphabet,
+                    row_number,
+                    column_number,
+                    glyph,
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    let mut text = Vec::new();
+    if !output.no_metadata {
+        let toml = config.to_toml().expect("Couldn't save settings");
+        text.push((CONFIG_KEYWORD, toml));
+        text.push((SEEDS_KEYWORD, seeds.to_string()));
+    }
+    let mut drawing = sheet_drawing(sheet, &rows, output.palette(config));
+    add_header(&mut drawing, sheet, &header);
+    if annotations.label_seeds {
+        let seeds: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| row.iter().map(|glyph| glyph.seed().to_string()).collect())
+            .collect();
+        add_labels(&mut drawing, sheet, &seeds);
+    }
+    let artifact = Artifact {
+        drawing,
+        sets: alphabets
+            .iter()
+            .zip(&rows)
+            .map(|(alphabet, glyphs)| GlyphSet { alphabet, glyphs })
+            .collect(),
+        text,
+        theme: output.theme(config),
+    };
+    write_drawing(output, output.output.as_deref(), &artifact);
+    Ok(())
+}
+
+/// Most seeds to skip for failing a filter before giving up on filling a sheet.
+const MAX_SKIPPED: usize = 10_000;
+
+/// Alphabets and their rows of glyphs, with the seeds skipped for failing a filter.
+type GeneratedRows = (Vec<Alphabet>, Vec<Vec<Glyph>>, Vec<u64>);
+
+/// Generate rows of glyphs from the selected seeds, each row with its own alphabet, until
+/// there are enough glyphs passing the filter or no more seeds. Returns the alphabets, the
+/// rows, and the seeds skipped for failing the filter.
+fn generate_rows(
+    config: &GenerationConfig,
+    columns: usize,
+    mut selection: Selection,
+) -> Result<GeneratedRows, Error> {
+    let mut alphabets = Vec::new();
+    let mut rows = Vec::new();
+    let mut skipped = Vec::new();
+    let mut remaining = selection.count;
+    let bar = progress_bar("Generating", selection.count as u64);
+    for row_number in 0.. {
+        if remaining == 0 {
+            break;
+        }
+        let alphabet = config.alphabet.build(row_number)?;
+        let wanted = remaining.min(columns);
+        let mut row = Vec::with_capacity(wanted);
+        let mut exhausted = false;
+        while row.len() < wanted && !exhausted {
+            let seeds: Vec<u64> = selection.seeds.by_ref().take(wanted - row.len()).collect();
+            exhausted = seeds.len() < wanted - row.len();
+            let glyphs = alphabet.generate_batch_with_progress(seeds, &|_| bar.inc(1))?;
+            for glyph in glyphs {
+                match selection.filter {
+                    Some(filter) if !filter.matches(&glyph) => {
+                        // Another seed is needed in its place
+                        bar.inc_length(1);
+                        skipped.push(glyph.seed());
+                    }
+                    _ => row.push(glyph),
+                }
+            }
+            if skipped.len() >= MAX_SKIPPED {
+                eprintln!(
+                    "warning: skipped {} seeds failing the filter, drawing the {} glyphs that passed",
+                    skipped.len(),
+                    selection.count - remaining + row.len()
+                );
+                exhausted = true;
+            }
+        }
+        remaining -= row.len();
+        if !row.is_empty() {
+            alphabets.push(alphabet);
+            rows.push(row);
+        }
+        if exhausted {
+            break;
+        }
+    }
+    bar.finish_and_clear();
+    Ok((alphabets, rows, skipped))
+}
+
+/// A bar on stderr showing how far through some work things are, drawn only if stderr is a
+/// terminal, and cleared if the work stops early.
+fn progress_bar(message: &'static str, length: u64) -> ProgressBar {
+    let style = ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len}, {eta} left")
+        .expect("Progress bar template is valid")
+        .progress_chars("=> ");
+    ProgressBar::new(length)
+        .with_style(style)
+        .with_message(message)
+        .with_finish(ProgressFinish::AndClear)
+}
+
+/// Generate a glyph for each row and column of a sweep, all with the same seed.
+fn sweep(options: &SweepOptions) {
+    let mut config = options.generation.config();
+    options.drawing.apply(&mut config.sheet);
+    let sweep = Sweep::new(options.rows.clone(), options.columns.clone());
+    let mut sheet = config.sheet.clone();
+    sheet.rows = sweep.rows.len() as i32;
+    sheet.columns = sweep.columns.len() as i32;
+
+    let alphabets = sweep
+        .build(&config.alphabet)
+        .expect("Invalid alphabet settings");
+    let rows: Vec<Vec<Glyph>> = alphabets
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|alphabet| alphabet.generate(options.seed))
+                .collect::<Result<_, _>>()
+                .expect("Couldn't generate glyph")
+        })
+        .collect();
+    let mut drawing = sheet_drawing(&sheet, &rows, options.output.palette(&config));
+    if !options.no_labels {
+        let mut caption = sweep.fixed(&config.alphabet);
+        caption.push(format!("seed={}", options.seed));
+        label_sweep(&mut drawing, &sheet, &sweep, &caption);
+    }
+    let artifact = Artifact {
+        drawing,
+        sets: alphabets
+            .iter()
+            .flatten()
+            .zip(rows.iter().flatten())
+            .map(|(alphabet, glyph)| GlyphSet {
+                alphabet,
+                glyphs: std::slice::from_ref(glyph),
+            })
+            .collect(),
+        text: Vec::new(),
+        theme: options.output.theme(&config),
+    };
+    write_drawing(&options.output, options.output.output.as_deref(), &artifact);
+}
+
+/// Roughly the width of a character, as a fraction of the size of the font.
+const CHARACTER_WIDTH: f64 = 0.6;
+/// Distance between the baselines of lines of text, as a fraction of the size of the font.
+const LINE_HEIGHT: f64 = 1.25;
+
+/// Lines of text to write above the glyphs on a sheet, and their sizes, largest first.
+fn header_lines(
+    annotations: &AnnotationOptions,
+    config: &GenerationConfig,
+    seeds: &SeedList,
+    sheet: &SheetLayout,
+) -> Vec<(String, f64)> {
+    let mut lines = Vec::new();
+    if let Some(title) = &annotations.title {
+        lines.push((title.clone(), sheet.spacing));
+    }
+    if let Some(subtitle) = &annotations.subtitle {
+        lines.push((subtitle.clone(), sheet.spacing * 0.6));
+    }
+    if annotations.summary {
+        let size = sheet.spacing * 0.4;
+        let mut settings: Vec<String> = config
+            .alphabet
+            .settings()
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        settings.push(format!("seeds={}", seeds));
+        let width = sheet.width() - 2.0 * sheet.offset(0, 0).x;
+        let line_length = (width / (CHARACTER_WIDTH * size)) as usize;
+        lines.extend(
+            wrap(&settings, line_length)
+                .into_iter()
+                .map(|line| (line, size)),
+        );
+    }
+    lines
+}
+
+/// Write lines of text in the space above the glyphs on each page of a sheet.
+fn add_header(drawing: &mut Drawing, sheet: &SheetLayout, lines: &[(String, f64)]) {
+    if lines.is_empty() {
+        return;
+    }
+    let start = sheet.offset(0, 0);
+    let mut labels = Vec::new();
+    let mut y = start.y - sheet.header;
+    for (text, size) in lines {
+        y += size * LINE_HEIGHT;
+        labels.push(Label {
+            text: text.clone(),
+            position: Point::new(start.x, y - size * (LINE_HEIGHT - 1.0)),
+            size: *size,
+            anchor: Anchor::Start,
+        });
+    }
+    drawing.labels = vec![labels; drawing.pages.len()];
+}
+
+/// Items joined with commas into lines of at most `line_length` characters, where they fit.
+fn wrap(items: &[String], line_length: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for item in items {
+        match lines.last_mut() {
+            Some(line) if line.len() + 2 + item.len() <= line_length => {
+                line.push_str(", ");
+                line.push_str(item);
+            }
+            _ => lines.push(item.clone()),
+        }
+    }
+    lines
+}
+
+/// Add headers naming each row and column's setting around a sweep's drawing, and a caption
+/// of other settings below it, wrapped to the width of the drawing.
+fn label_sweep(drawing: &mut Drawing, sheet: &SheetLayout, sweep: &Sweep, caption: &[String]) {
+    let longest = |labels: &[String]| {
+        labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0) as f64
+    };
+    let row_labels: Vec<String> = (0..sweep.rows.len())
+        .map(|row| sweep.rows.label(row))
+        .collect();
+    let column_labels: Vec<String> = (0..sweep.columns.len())
+        .map(|column| sweep.columns.label(column))
+        .collect();
+    // Small enough for column headers not to run into each other
+    let size = (sheet.spacing / 2.0)
+        .min((sheet.scale + sheet.spacing) / (longest(&column_labels) * CHARACTER_WIDTH));
+    let left = longest(&row_labels) * CHARACTER_WIDTH * size;
+    let line_length = ((drawing.width + left - sheet.spacing) / (CHARACTER_WIDTH * size)) as usize;
+    let lines = wrap(caption, line_length);
+    let (top, bottom) = (size * 1.5, size * (0.5 + LINE_HEIGHT * lines.len() as f64));
+
+    let margin = Vector::new(left, top);
+    for page in &mut drawing.pages {
+        for glyph in page.iter_mut() {
+            *glyph = glyph.translated(margin);
+        }
+    }
+    drawing.width += left;
+    drawing.height += top + bottom;
+
+    let label = |text: &str, x: f64, y: f64, anchor: Anchor| Label {
+        text: text.to_owned(),
+        position: Point::new(x, y),
+        size,
+        anchor,
+    };
+    drawing.labels = (0..drawing.pages.len() as i32)
+        .map(|page| {
+            let mut labels: Vec<Label> = (0..)
+                .zip(&column_labels)
+                .map(|(column, text)| {
+                    let x = left + sheet.offset(0, column).x + sheet.scale / 2.0;
+                    label(text, x, top, Anchor::Middle)
+                })
+                .collect();
+            for (row, text) in (0..).zip(&row_labels) {
+                let (row_page, offset) = sheet.page_offset(row, 0);
+                if row_page == page {
+                    let y = top + offset.y + sheet.scale / 2.0 + size / 3.0;
+                    labels.push(label(text, left + sheet.spacing / 2.0, y, Anchor::End));
+                }
+            }
+            for (line_number, line) in (1..).zip(&lines) {
+                let y = drawing.height - bottom + size * LINE_HEIGHT * line_number as f64;
+                labels.push(label(line, sheet.spacing / 2.0, y, Anchor::Start));
+            }
+            labels
+        })
+        .collect();
+}
+
+/// Write a label for each glyph, such as its seed, centred in the gap under it. Labels are
+/// given in rows, like the glyphs.
+fn add_labels(drawing: &mut Drawing, sheet: &SheetLayout, labels: &[Vec<String>]) {
+    let longest = labels
+        .iter()
+        .flatten()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0) as f64;
+    // Small enough to fit in the gap, without running into the next label along
+    let size =
+        (sheet.spacing / 2.5).min((sheet.scale + sheet.spacing) / (longest * CHARACTER_WIDTH));
+    drawing.labels.resize(drawing.pages.len(), Vec::new());
+    for (row_number, row) in (0..).zip(labels) {
+        for (column_number, label) in (0..).zip(row) {
+            let (page, offset) = sheet.page_offset(row_number, column_number);
+            drawing.labels[page as usize].push(Label {
+                text: label.clone(),
+                position: Point::new(
+                    offset.x + sheet.scale / 2.0,
+                    offset.y + sheet.scale + (sheet.spacing + size) / 2.0,
+                ),
+                size,
+                anchor: Anchor::Middle,
+            });
+        }
+    }
+}
+
+fn text(options: &TextOptions) {
+    let config = options.generation.config();
+    let alphabet = config
+        .alphabet
+        .build(options.row)
+        .expect("Invalid alphabet settings");
+    let map = match &options.map {
+        Some(path) => CharacterMap::load(path).expect("Couldn't load character map"),
+        None => CharacterMap::distinct(&alphabet, ALPHANUMERIC, options.seed_start)
+            .expect("Couldn't generate glyphs"),
+    };
+    if options.print_map {
+        print!("{}", map.to_toml().expect("Couldn't write character map"));
+        return;
+    }
+
+    let layout = TextLayout {
+        size: options.size,
+        letter_spacing: options.letter_spacing,
+        word_spacing: options.word_spacing,
+        line_spacing: options.line_spacing,
+        max_width: options.max_width,
+        align: options.align,
+        metrics: MetricsOptions {
+            spacing: options.spacing,
+            side_bearing: options.side_bearing,
+            ..MetricsOptions::default()
+        },
+    };
+    let text = layout
+        .set(&options.text, &map, &alphabet)
+        .expect("Couldn't generate glyphs");
+    if !text.missing.is_empty() {
+        let missing: String = text.missing.iter().collect();
+        eprintln!("warning: no glyphs for {:?}, left as gaps", missing);
+    }
+
+    // Lines as thick, for the size of the glyphs, as on a sheet, with as much space around
+    let sheet = &config.sheet;
+    let line_width = sheet.line_width * options.size / sheet.scale;
+    let margin = options.size * options.letter_spacing + line_width;
+    let palette = options.output.palette(&config);
+    let drawing = Drawing {
+        width: text.width + 2.0 * margin,
+        height: text.height + 2.0 * margin,
+        line_width,
+        labels: Vec::new(),
+        unit: sheet.units,
+        marks: Vec::new(),
+        colors: palette
+            .map(|theme| {
+                let colors = (0..text.glyphs.len()).map(|index| theme.color(0, index).0);
+                vec![colors.collect()]
+            })
+            .unwrap_or_default(),
+        pages: vec![text
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.translated(Vector::new(margin, margin)))
+            .collect()],
+    };
+
+    // Each character's glyph once, unscaled, for formats like JSON
+    let mut seeds: Vec<u64> = options
+        .text
+        .chars()
+        .filter_map(|character| map.seed(character))
+        .collect();
+    let mut seen = HashSet::new();
+    seeds.retain(|seed| seen.insert(*seed));
+    let glyphs = alphabet
+        .generate_batch(seeds)
+        .expect("Couldn't generate glyphs");
+    let artifact = Artifact {
+        drawing,
+        sets: vec![GlyphSet {
+            alphabet: &alphabet,
+            glyphs: &glyphs,
+        }],
+        text: Vec::new(),
+        theme: options.output.theme(&config),
+    };
+    write_drawing(&options.output, options.output.output.as_deref(), &artifact);
+}
+
+fn set(options: &SetOptions) {
+    let set = NamedSet::load(&options.set).expect("Couldn't load set");
+    let generated = set.generate().expect("Couldn't generate glyphs");
+    let mut config = GenerationConfig::default();
+    options.layout.apply(&mut config.sheet);
+    let columns = config.sheet.columns.max(1);
+
+    if options.split.split {
+        for ((index, named), (alphabet, glyph)) in (0..).zip(&set.glyphs).zip(&generated) {
+            let (row, column) = (index as i32 / columns, index as i32 % columns);
+            let name = options
+                .split
+                .name_template
+                .render(glyph.seed(), index, row, column, Some(&named.name))
+                .expect("Invalid name template");
+            let path = match &options.output.output {
+                Some(directory) => directory.join(name),
+                None => PathBuf::from(name),
+            };
+            let artifact = glyph_artifact(&options.output, &config, alphabet, row, index, glyph);
+            write_drawing(&options.output, Some(&path), &artifact);
+        }
+        return;
+    }
+
+    let glyphs: Vec<Glyph> = generated.iter().map(|(_, glyph)| glyph.clone()).collect();
+    let sets = generated
+        .iter()
+        .map(|(alphabet, glyph)| GlyphSet {
+            alphabet,
+            glyphs: std::slice::from_ref(glyph),
+        })
+        .collect();
+    let names: Vec<String> = set.glyphs.iter().map(|glyph| glyph.name.clone()).collect();
+    write_glyph_sheet(&options.output, config, &glyphs, sets, Some(&names));
+}
+
+/// Rows of unscaled glyphs laid out on the pages of a sheet, each coloured from the palette
+/// if there is one.
+fn sheet_drawing(sheet: &SheetLayout, rows: &[Vec<Glyph>], palette: Option<&Theme>) -> Drawing {
+    let pages = sheet.pages() as usize;
+    let mut drawing = Drawing {
+        width: sheet.width(),
+        height: sheet.page_height(),
+        line_width: sheet.line_width,
+        pages: vec![Vec::new(); pages],
+        labels: Vec::new(),
+        unit: sheet.units,
+        marks: sheet.crop_marks(),
+        colors: Vec::new(),
+    };
+    if palette.is_some() {
+        drawing.colors = vec![Vec::new(); pages];
+    }
+    let mut index = 0;
+    for (row_number, row) in (0..).zip(rows) {
+        for (column_number, glyph) in (0..).zip(row) {
+            let (page, offset) = sheet.page_offset(row_number, column_number);
+            let page = page as usize;
+            drawing.pages[page].push(glyph.scaled(sheet.scale).translated(offset));
+            if let Some(theme) = palette {
+                drawing.colors[page].push(theme.color(row_number as usize, index).0);
+            }
+            index += 1;
+        }
+    }
+    drawing
+}
+
+/// Generate the sheet again from the settings saved in a PNG.
+fn reproduce(options: &ReproduceOptions) {
+    let png = std::fs::read(&options.input).expect("Couldn't read file");
+    let text = png::text(&png);
+    let entry = |keyword: &str| {
+        text.iter()
+            .find(|(key, _)| key == keyword)
+            .map(|(_, value)| value.as_str())
+    };
+    let config = entry(CONFIG_KEYWORD).expect("No symbolgen settings saved in file");
+    let config = GenerationConfig::from_toml(config).expect("Invalid settings saved in file");
+    let seeds = match entry(SEEDS_KEYWORD) {
+        Some(seeds) => seeds.parse().expect("Invalid seeds saved in file"),
+        None => SeedList((0..(config.sheet.rows * config.sheet.columns) as u64).collect()),
+    };
+    generate(
+        &options.output,
+        &options.split,
+        &AnnotationOptions::default(),
+        None,
+        &config,
+        Selection::from(&seeds),
+    )
+    .expect("Couldn't generate glyphs");
+}
+
+/// Check the glyphs in a manifest generate exactly as they did, exiting with an error if not.
+fn verify(options: &VerifyOptions) {
+    let path = &options.manifest;
+    let file = File::open(path).expect("Couldn't read file");
+    let manifest = if OutputFormat::of_file(Some(path)) == Ok(OutputFormat::Zip) {
+        let mut archive = ZipArchive::new(file).expect("Couldn't read zip");
+        let entry = archive
+            .by_name("manifest.json")
+            .expect("No manifest.json in archive");
+        Manifest::read(entry)
+    } else {
+        Manifest::read(file)
+    }
+    .expect("Couldn't read manifest");
+    if manifest.version != Manifest::VERSION {
+        eprintln!(
+            "Manifest was written by version {}, checking with version {}",
+            manifest.version,
+            Manifest::VERSION
+        );
+    }
+
+    let mismatches = manifest.verify().expect("Invalid alphabet settings");
+    for mismatch in &mismatches {
+        let expected = &manifest.glyphs[mismatch.index].hash;
+        eprintln!(
+            "Glyph {} with seed {} in row {} has hash {}, not {}",
+            mismatch.index,
+            mismatch.seed,
+            mismatch.row,
+            mismatch.hash.as_deref().unwrap_or("(couldn't generate)"),
+            expected
+        );
+    }
+    if !mismatches.is_empty() {
+        eprintln!(
+            "{} of {} glyphs differ",
+            mismatches.len(),
+            manifest.glyphs.len()
+        );
+        std::process::exit(1);
+    }
+    println!("All {} glyphs match", manifest.glyphs.len());
+}
+
+/// Print the measurements of each glyph in a range of seeds, and histograms of them all.
+fn stats(options: &StatsOptions) {
+    let config = options.generation.config();
+    let alphabet = config
+        .alphabet
+        .build(options.row)
+        .expect("Invalid alphabet settings");
+    let seeds = match &options.seeds {
+        Some(seeds) => seeds.0.clone(),
+        None => (options.seed_start..options.seed_start + options.count).collect(),
+    };
+    let glyphs = alphabet
+        .generate_batch(seeds)
+        .expect("Couldn't generate glyph");
+    let stats: Vec<GlyphStats> = glyphs.iter().map(Glyph::stats).collect();
+
+    if !options.summary {
+        println!("{}", measurements_header());
+        for (glyph, stats) in glyphs.iter().zip(&stats) {
+            println!("{}", measurements(glyph.seed(), stats));
+        }
+        println!();
+    }
+    for metric in &Metric::ALL {
+        let values: Vec<f64> = stats.iter().map(|stats| metric.measure(stats)).collect();
+        print!(
+            "{}",
+            histogram(&metric.to_string(), &values, options.bins.max(1))
+        );
+    }
+}
+
+/// Names of the columns printed by `measurements`.
+fn measurements_header() -> String {
+    let names: Vec<String> = Metric::ALL.iter().map(ToString::to_string).collect();
+    format!("seed\t{}", names.join("\t"))
+}
+
+/// A glyph's seed and every metric of it, separated by tabs.
+fn measurements(seed: u64, stats: &GlyphStats) -> String {
+    // Whole numbers, like counts, without decimal places
+    let values: Vec<String> = Metric::ALL
+        .iter()
+        .map(|metric| match metric.measure(stats) {
+            value if value.fract() == 0.0 => value.to_string(),
+            value => format!("{:.3}", value),
+        })
+        .collect();
+    format!("{}\t{}", seed, values.join("\t"))
+}
+
+/// Glyphs generated at once while searching, between checks for enough matches.
+const SEARCH_BATCH: u64 = 256;
+
+fn search(options: &SearchOptions) {
+    let config = options.generation.config();
+    let alphabet = config
+        .alphabet
+        .build(options.row)
+        .expect("Invalid alphabet settings");
+    let filter = options.filter();
+
+    println!("{}", measurements_header());
+    let mut found = Vec::new();
+    let end = options.seed_start.saturating_add(options.scan);
+    let mut start = options.seed_start;
+    while start < end && found.len() < options.limit {
+        let batch = start..end.min(start.saturating_add(SEARCH_BATCH));
+        start = batch.end;
+        let glyphs = alphabet
+            .generate_batch(batch)
+            .expect("Couldn't generate glyph");
+        for glyph in glyphs.iter().filter(|glyph| filter.matches(glyph)) {
+            if found.len() == options.limit {
+                break;
+            }
+            println!("{}", measurements(glyph.seed(), &glyph.stats()));
+            found.push(glyph.seed());
+        }
+    }
+    eprintln!(
+        "Found {} seeds in {} scanned: {}",
+        found.len(),
+        start - options.seed_start,
+        SeedList(found)
+    );
+}
+
+/// Widest bar of a histogram, in characters.
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// The smallest, mean and largest of some values, and a bar for how many fall in each of
+/// the given number of equal ranges between the smallest and largest.
+fn histogram(name: &str, values: &[f64], bins: usize) -> String {
+    let mut text = String::new();
+    if values.is_empty() {
+        return text;
+    }
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    text.push_str(&format!(
+        "{}: min {:.3}, mean {:.3}, max {:.3}\n",
+        name, min, mean, max
+    ));
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0; bins];
+    for value in values {
+        let bin = if width > 0.0 {
+            ((value - min) / width) as usize
+        } else {
+            0
+        };
+        counts[bin.min(bins - 1)] += 1;
+    }
+    let most = counts.iter().copied().max().unwrap_or(0).max(1);
+    for (bin, count) in counts.iter().enumerate() {
+        if width == 0.0 && bin > 0 {
+            break;
+        }
+        let start = min + width * bin as f64;
+        text.push_str(&format!(
+            "  {:>9.3} - {:<9.3} {:<bar$} {}\n",
+            start,
+            start + width,
+            "#".repeat(count * HISTOGRAM_WIDTH / most),
+            count,
+            bar = HISTOGRAM_WIDTH
+        ));
+    }
+    text
+}
+
+/// Write a glyph to its own file, named from the template.
+fn write_glyph(
+    output: &OutputOptions,
+    split: &SplitOptions,
+    config: &GenerationConfig,
+    alphabet: &Alphabet,
+    row: i32,
+    column: i32,
+    glyph: &Glyph,
+) {
+    let index = (row * config.sheet.columns + column) as u64;
+    let name = split
+        .name_template
+        .render(glyph.seed(), index, row, column, None)
+        .expect("Invalid name template");
+    let path = match &output.output {
+        Some(directory) => directory.join(name),
+        None => PathBuf::from(name),
+    };
+    let artifact = glyph_artifact(output, config, alphabet, row, index, glyph);
+    write_drawing(output, Some(&path), &artifact);
+}
+
+/// A glyph alone with a sheet's spacing around it, in the colour it has on the sheet.
+fn glyph_artifact<'a>(
+    output: &'a OutputOptions,
+    config: &'a GenerationConfig,
+    alphabet: &'a Alphabet,
+    row: i32,
+    index: u64,
+    glyph: &'a Glyph,
+) -> Artifact<'a> {
+    let sheet = &config.sheet;
+    let size = sheet.scale + 2.0 * sheet.spacing;
+    Artifact {
+        drawing: Drawing {
+            width: size,
+            height: size,
+            line_width: sheet.line_width,
+            labels: Vec::new(),
+            unit: sheet.units,
+            marks: Vec::new(),
+            colors: output
+                .palette(config)
+                .map(|theme| vec![vec![theme.color(row as usize, index as usize).0]])
+                .unwrap_or_default(),
+            pages: vec![vec![glyph
+                .scaled(sheet.scale)
+                .translated(Vector::new(sheet.spacing, sheet.spacing))]],
+        },
+        sets: vec![GlyphSet {
+            alphabet,
+            glyphs: std::slice::from_ref(glyph),
+        }],
+        text: Vec::new(),
+        theme: output.theme(config),
+    }
+}
+
+/// What's in a zip archive of glyphs: a manifest, as written by --manifest, and the file
+/// each glyph is drawn in.
+#[derive(serde::Serialize)]
+struct ArchiveManifest {
+    #[serde(flatten)]
+    manifest: Manifest,
+    files: Vec<ArchiveFile>,
+}
+
+/// A file in a zip archive, and the glyph drawn in it.
+#[derive(serde::Serialize)]
+struct ArchiveFile {
+    name: String,
+    seed: u64,
+    index: u64,
+    row: i32,
+    column: i32,
+}
+
+/// Write each glyph to its own file in a zip archive, named from the template, with a
+/// manifest of the settings and which glyph is in each file.
+fn write_archive(
+    output: &OutputOptions,
+    split: &SplitOptions,
+    config: &GenerationConfig,
+    manifest: &Manifest,
+    alphabets: &[Alphabet],
+    rows: &[Vec<Glyph>],
+) {
+    match &output.output {
+        Some(path) => {
+            let file = File::create(path).expect("Couldn't create file");
+            fill_archive(
+                ZipWriter::new(file),
+                output,
+                split,
+                config,
+                manifest,
+                alphabets,
+                rows,
+            );
+        }
+        None if stdout().is_terminal() => {
+            usage_error("Not writing a zip archive to a terminal, redirect stdout or use --output")
+        }
+        None => {
+            // Stdout can't be sought back over, so build the archive in memory first
+            let archive = fill_archive(
+                ZipWriter::new(Cursor::new(Vec::new())),
+                output,
+                split,
+                config,
+                manifest,
+                alphabets,
+                rows,
+            );
+            stdout()
+                .lock()
+                .write_all(archive.get_ref())
+                .expect("Couldn't write to zip");
+        }
+    }
+}
+
+/// Add each glyph and the manifest to an archive, returning what it was written to.
+fn fill_archive<W: Write + Seek>(
+    mut archive: ZipWriter<W>,
+    output: &OutputOptions,
+    split: &SplitOptions,
+    config: &GenerationConfig,
+    manifest: &Manifest,
+    alphabets: &[Alphabet],
+    rows: &[Vec<Glyph>],
+) -> W {
+    let mut files = Vec::new();
+    for (row, (alphabet, glyphs)) in (0..).zip(alphabets.iter().zip(rows)) {
+        for (column, glyph) in (0..).zip(glyphs) {
+            let index = (row * config.sheet.columns + column) as u64;
+            let name = split
+                .name_template
+                .render(glyph.seed(), index, row, column, None)
+                .expect("Invalid name template");
+            let format = OutputFormat::of_file(Some(Path::new(&name)))
+                .unwrap_or_else(|error| usage_error(&error));
+            // Binary formats are compressed already
+            let method = if format.is_binary() {
+                CompressionMethod::Stored
+            } else {
+                CompressionMethod::Deflated
+            };
+            archive
+                .start_file(
+                    name.as_str(),
+                    FileOptions::default().compression_method(method),
+                )
+                .expect("Couldn't write to zip");
+            let artifact = glyph_artifact(output, config, alphabet, row, index, glyph);
+            write_artifact(output, format, &artifact, &mut archive);
+            files.push(ArchiveFile {
+                name,
+                seed: glyph.seed(),
+                index,
+                row,
+                column,
+            });
+        }
+    }
+    archive
+        .start_file("manifest.json", FileOptions::default())
+        .expect("Couldn't write to zip");
+    let manifest = ArchiveManifest {
+        manifest: manifest.clone(),
+        files,
+    };
+    serde_json::to_writer_pretty(&mut archive, &manifest).expect("Couldn't write to zip");
+    archive.finish().expect("Couldn't write to zip")
+}
+
+fn glyph(options: &GlyphOptions) {
+    let config = options.generation.config();
+    let style = config
+        .alphabet
+        .build(options.row)
+        .expect("Invalid alphabet settings");
+    let glyph = style
+        .generate(options.seed)
+        .expect("Couldn't generate glyph");
+    write_single(&options.output, &config, &style, &glyph, options.size);
+}
+
+fn preview(options: &PreviewOptions) {
+    let config = options.generation.config();
+    let alphabet = config
+        .alphabet
+        .build(options.row)
+        .expect("Invalid alphabet settings");
+    let seeds = match &options.seeds {
+        Some(seeds) => seeds.0.clone(),
+        None => vec![options.seed],
+    };
+    let glyphs = alphabet
+        .generate_batch(seeds)
+        .expect("Couldn't generate glyph");
+    let text = TerminalRenderer {
+        columns: options.width,
+        style: if options.blocks {
+            TerminalStyle::Blocks
+        } else {
+            TerminalStyle::Braille
+        },
+    };
+    let protocol = options.graphics.protocol();
+    let stdout = stdout();
+    let mut writer = stdout.lock();
+    for glyph in &glyphs {
+        if glyphs.len() > 1 {
+            writeln!(writer, "seed {}", glyph.seed()).expect("Couldn't write to stdout");
+        }
+        match protocol {
+            Some(protocol) => InlineRenderer::new(protocol)
+                .render(&single_drawing(glyph, options.size), &mut writer),
+            None => text.render(&Drawing::glyph(glyph, config.sheet.line_width), &mut writer),
+        }
+        .expect("Couldn't write to stdout");
+    }
+}
+
+/// Characters between glyphs in the evolve command's grid.
+const EVOLVE_GUTTER: usize = 2;
+
+fn evolve(options: &EvolveOptions) {
+    let config = options.generation.config();
+    let alphabet = config
+        .alphabet
+        .build(options.row)
+        .expect("Invalid alphabet settings");
+    let population = options.population.max(1);
+    let seeds = options.seed_start..options.seed_start + population as u64;
+    let mut glyphs = alphabet
+        .generate_batch(seeds)
+        .expect("Couldn't generate glyphs");
+    let text = TerminalRenderer {
+        columns: options.width,
+        style: TerminalStyle::Braille,
+    };
+    let across = (terminal_columns() / (options.width + EVOLVE_GUTTER)).max(1);
+    let root = SeedDerivation::new(options.seed_start);
+
+    let mut favourites: Vec<Glyph> = Vec::new();
+    let mut input = stdin().lock();
+    for generation in 1.. {
+        println!("Generation {}", generation);
+        for (row, chunk) in (0..).zip(glyphs.chunks(across)) {
+            print_grid(&text, chunk, row * across, config.sheet.line_width);
+        }
+        let picks = loop {
+            print!("Favourites to breed from, like 1 4 7, or q to finish: ");
+            stdout().flush().expect("Couldn't write to stdout");
+            let mut line = String::new();
+            let read = input.read_line(&mut line).expect("Couldn't read stdin");
+            if read == 0 || line.trim() == "q" {
+                break None;
+            }
+            match parse_picks(&line, glyphs.len()) {
+                Ok(picks) if !picks.is_empty() => break Some(picks),
+                Ok(_) => println!("Pick at least one"),
+                Err(error) => println!("{}", error),
+            }
+        };
+        let picks = match picks {
+            Some(picks) => picks,
+            None => break,
+        };
+
+        // Favourites carry on as they are, and the rest are their children
+        favourites = picks.iter().map(|pick| glyphs[*pick].clone()).collect();
+        let seeds = root.variant(generation);
+        let parents = favourites.len();
+        let children = (favourites.len()..population).map(|index| {
+            let seed = seeds.glyph(index as u64).seed();
+            let a = &favourites[index % parents];
+            let b = &favourites[(index + 1 + index / parents) % parents];
+            alphabet.mutate(&alphabet.crossover(a, b, seed), seed, options.mutation)
+        });
+        glyphs = favourites.iter().cloned().chain(children).collect();
+    }
+
+    if options.output.output.is_none() {
+        return;
+    }
+    if favourites.is_empty() {
+        favourites = glyphs;
+    }
+    let sets = vec![GlyphSet {
+        alphabet: &alphabet,
+        glyphs: &favourites,
+    }];
+    write_glyph_sheet(&options.output, config, &favourites, sets, None);
+}
+
+/// Write glyphs as a sheet of as many rows as they fill, and no more columns than they need,
+/// with each glyph's name under it if they are given.
+fn write_glyph_sheet(
+    output: &OutputOptions,
+    mut config: GenerationConfig,
+    glyphs: &[Glyph],
+    sets: Vec<GlyphSet>,
+    names: Option<&[String]>,
+) {
+    let columns = (config.sheet.columns.max(1) as usize).min(glyphs.len().max(1));
+    config.sheet.columns = columns as i32;
+    config.sheet.rows = glyphs.len().div_ceil(columns) as i32;
+    let rows: Vec<Vec<Glyph>> = glyphs.chunks(columns).map(<[Glyph]>::to_vec).collect();
+    let mut drawing = sheet_drawing(&config.sheet, &rows, output.palette(&config));
+    if let Some(names) = names {
+        let labels: Vec<Vec<String>> = names.chunks(columns).map(<[String]>::to_vec).collect();
+        add_labels(&mut drawing, &config.sheet, &labels);
+    }
+    let artifact = Artifact {
+        drawing,
+        sets,
+        text: Vec::new(),
+        theme: output.theme(&config),
+    };
+    write_drawing(output, output.output.as_deref(), &artifact);
+}
+
+/// Print glyphs side by side in braille, each numbered from one more than `first`.
+fn print_grid(text: &TerminalRenderer, glyphs: &[Glyph], first: usize, line_width: f64) {
+    let width = text.columns + EVOLVE_GUTTER;
+    let drawn: Vec<Vec<String>> = glyphs
+        .iter()
+        .map(|glyph| {
+            text.to_lines(&Drawing::glyph(glyph, line_width))
+                .expect("Couldn't draw glyph")
+        })
+        .collect();
+    let height = drawn.iter().map(Vec::len).max().unwrap_or(0);
+    for line in 0..height {
+        let row: String = drawn
+            .iter()
+            .map(|lines| format!("{:<width$}", lines.get(line).map_or("", String::as_str)))
+            .collect();
+        println!("{}", row.trim_end());
+    }
+    let numbers: String = (first + 1..=first + glyphs.len())
+        .map(|number| format!("{:<width$}", number))
+        .collect();
+    println!("{}\n", numbers.trim_end());
+}
+
+/// Glyphs picked by number, counting from one, out of those shown.
+fn parse_picks(line: &str, shown: usize) -> Result<Vec<usize>, String> {
+    let mut picks = Vec::new();
+    for pick in line.split(|c: char| c == ',' || c.is_whitespace()) {
+        if pick.is_empty() {
+            continue;
+        }
+        let number: usize = pick
+            .parse()
+            .map_err(|_| format!("Not a number: {}", pick))?;
+        if number == 0 || number > shown {
+            return Err(format!("No glyph {}, pick from 1 to {}", number, shown));
+        }
+        if !picks.contains(&(number - 1)) {
+            picks.push(number - 1);
+        }
+    }
+    Ok(picks)
+}
+
+#[cfg(feature = "font-source")]
+fn font(options: &FontExportOptions) {
+    let glyphs: Vec<(char, Glyph)> = match &options.set {
+        Some(path) => {
+            let set = NamedSet::load(path).expect("Couldn't load set");
+            let generated = set.generate().expect("Couldn't generate glyphs");
+            set.characters()
+                .into_iter()
+                .zip(generated.into_iter().map(|(_, glyph)| glyph))
+                .collect()
+        }
+        None => {
+            let config = options.generation.config();
+            let alphabet = config
+                .alphabet
+                .build(options.row)
+                .expect("Invalid alphabet settings");
+            let map = match &options.map {
+                Some(path) => CharacterMap::load(path).expect("Couldn't load character map"),
+                None => {
+                    let characters = options.characters.characters();
+                    let map = CharacterMap::distinct(&alphabet, &characters, options.seed_start)
+                        .expect("Couldn't generate glyphs");
+                    let wanted = characters.chars().count();
+                    if map.seeds.len() < wanted {
+                        eprintln!(
+                            "warning: only {} different glyphs for {} characters",
+                            map.seeds.len(),
+                            wanted
+                        );
+                    }
+                    map
+                }
+            };
+            map.seeds
+                .iter()
+                .map(|(&character, &seed)| Ok((character, alphabet.generate(seed)?)))
+                .collect::<symbolgen_core::Result<_>>()
+                .expect("Couldn't generate glyphs")
+        }
+    };
+    let font_options = FontOptions {
+        family: options.family.clone(),
+        weight: options.weight,
+        word_spacing: options.word_spacing,
+        metrics: MetricsOptions {
+            spacing: options.spacing,
+            side_bearing: options.side_bearing,
+            ..MetricsOptions::default()
+        },
+        ..FontOptions::default()
+    };
+    let extension = options
+        .output
+        .extension()
+        .and_then(|extension| extension.to_str());
+    match extension {
+        Some("ufo") => {
+            for (path, contents) in to_ufo(&glyphs, &font_options) {
+                let path = options.output.join(path);
+                let folder = path.parent().expect("Font files are in a folder");
+                std::fs::create_dir_all(folder).expect("Couldn't create font folder");
+                std::fs::write(path, contents).expect("Couldn't write font");
+            }
+        }
+        Some("sfd") => {
+            std::fs::write(&options.output, to_sfd(&glyphs, &font_options))
+                .expect("Couldn't write font");
+        }
+        #[cfg(feature = "font")]
+        _ => {
+            let font = to_font(&glyphs, &font_options).expect("Couldn't make font");
+            std::fs::write(&options.output, font).expect("Couldn't write font");
+        }
+        #[cfg(not(feature = "font"))]
+        _ => panic!("TrueType fonts need the font feature; write a .ufo or .sfd source instead"),
+    }
+}
+
+#[cfg(feature = "catalog")]
+fn catalog(command: &CatalogCommand) {
+    use catalog::{Catalog, Entry, Query};
+
+    let open =
+        |options: &CatalogOptions| Catalog::open(&options.catalog).expect("Couldn't open catalog");
+    let query = |options: &CatalogQueryOptions| Query {
+        tags: options.tags.clone(),
+        min_rating: options.min_rating,
+    };
+    match command {
+        CatalogCommand::Add(options) => {
+            let alphabet = options.generation.config().alphabet;
+            alphabet
+                .build(options.row)
+                .expect("Invalid alphabet settings");
+            let entries: Vec<Entry> = options
+                .seeds
+                .0
+                .iter()
+                .map(|seed| Entry {
+                    seed: *seed,
+                    row: options.row,
+                    alphabet: alphabet.clone(),
+                    tags: options.tags.clone(),
+                    rating: options.rating,
+                })
+                .collect();
+            open(&options.catalog)
+                .add(&entries)
+                .expect("Couldn't add to catalog");
+            eprintln!("Added {} glyphs", entries.len());
+        }
+        CatalogCommand::List(options) => {
+            let entries = open(&options.catalog)
+                .list(&query(&options.query))
+                .expect("Couldn't read catalog");
+            println!("seed\trow\trating\ttags\tsettings");
+            for entry in entries {
+                let settings: Vec<String> = entry
+                    .alphabet
+                    .settings()
+                    .iter()
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect();
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    entry.seed,
+                    entry.row,
+                    entry
+                        .rating
+                        .map(|rating| rating.to_string())
+                        .unwrap_or_default(),
+                    entry.tags.join(","),
+                    settings.join(" ")
+                );
+            }
+        }
+        CatalogCommand::Export(options) => {
+            let entries = open(&options.catalog)
+                .list(&query(&options.query))
+                .expect("Couldn't read catalog");
+            let alphabets: Vec<Alphabet> = entries
+                .iter()
+                .map(|entry| {
+                    entry
+                        .alphabet
+                        .build(entry.row)
+                        .expect("Invalid alphabet settings in catalog")
+                })
+                .collect();
+            let glyphs: Vec<Glyph> = entries
+                .iter()
+                .zip(&alphabets)
+                .map(|(entry, alphabet)| {
+                    alphabet
+                        .generate(entry.seed)
+                        .expect("Couldn't generate glyph")
+                })
+                .collect();
+            let sets = alphabets
+                .iter()
+                .zip(&glyphs)
+                .map(|(alphabet, glyph)| GlyphSet {
+                    alphabet,
+                    glyphs: std::slice::from_ref(glyph),
+                })
+                .collect();
+            let mut config = options.generation.config();
+            options.layout.apply(&mut config.sheet);
+            write_glyph_sheet(&options.output, config, &glyphs, sets, None);
+        }
+    }
+}
+
+fn identicon(options: &IdenticonOptions) {
+    let config = options.generation.config();
+    let style = config.alphabet.build(2).expect("Invalid alphabet settings");
+    let glyph = if let Some(text) = &options.text {
+        Identicon::from_email(text, &style)
+    } else {
+        let mut bytes = Vec::new();
+        match &options.file {
+            Some(path) if !options.stdin => File::open(path)
+                .and_then(|mut file| file.read_to_end(&mut bytes))
+                .expect("Couldn't read file"),
+            _ => stdin()
+                .read_to_end(&mut bytes)
+                .expect("Couldn't read stdin"),
+        };
+        Identicon::from_bytes(&bytes, &style)
+    }
+    .expect("Couldn't generate glyph");
+    write_single(&options.output, &config, &style, &glyph, options.size);
+}
+
+/// Write a glyph alone in a square image of the given size.
+fn write_single(
+    output: &OutputOptions,
+    config: &GenerationConfig,
+    style: &Alphabet,
+    glyph: &Glyph,
+    size: f64,
+) {
+    let artifact = Artifact {
+        drawing: single_drawing(glyph, size),
+        sets: vec![GlyphSet {
+            alphabet: style,
+            glyphs: std::slice::from_ref(glyph),
+        }],
+        text: Vec::new(),
+        theme: output.theme(config),
+    };
+    write_drawing(output, output.output.as_deref(), &artifact);
+}
+
+/// A drawing of one glyph in a square image of the given size in pixels, with a quarter of
+/// the image clear on each side.
+fn single_drawing(glyph: &Glyph, size: f64) -> Drawing {
+    Drawing {
+        width: size,
+        height: size,
+        line_width: size / 12.0,
+        labels: Vec::new(),
+        unit: Unit::Px,
+        marks: Vec::new(),
+        colors: Vec::new(),
+        pages: vec![vec![glyph
+            .scaled(size / 2.0)
+            .translated(Vector::new(size / 4.0, size / 4.0))]],
+    }
+}
+
+/// Everything that can be written for one output file.
+struct Artifact<'a> {
+    /// Glyphs in place on each page
+    drawing: Drawing,
+    /// The same glyphs unscaled, with the alphabets that generated them
+    sets: Vec<GlyphSet<'a>>,
+    /// Keywords and text saved in PNG output
+    #[cfg_attr(not(feature = "cairo"), allow(dead_code))]
+    text: Vec<(&'static str, String)>,
+    /// Colours of the text and background, unless chosen otherwise
+    theme: Option<&'a Theme>,
+}
+
+/// Write an artifact to a file or stdout in the chosen output format, or to a numbered file
+/// for each page if the format only holds one.
+fn write_drawing(options: &OutputOptions, path: Option<&Path>, artifact: &Artifact) {
+    let format = options.format(path);
+    if path.is_none() && format.is_binary() && stdout().is_terminal() {
+        usage_error(&format!(
+            "Not writing {:?} to a terminal, redirect stdout, use --output, or draw it here \
+             with --format terminal",
+            format
+        ));
+    }
+    let drawing = &artifact.drawing;
+    if drawing.pages.len() <= 1 || format.is_multi_page() {
+        let mut writer = output(path);
+        write_artifact(options, format, artifact, &mut writer);
+        writer.flush().expect("Couldn't write drawing");
+        return;
+    }
+    let path = path.unwrap_or_else(|| {
+        panic!(
+            "{} pages can't all be written to stdout as {:?}, use --output or --format pdf",
+            drawing.pages.len(),
+            format
+        )
+    });
+    for page in 0..drawing.pages.len() {
+        let page_artifact = Artifact {
+            drawing: drawing.page(page),
+            sets: Vec::new(),
+            text: artifact.text.clone(),
+            theme: artifact.theme,
+        };
+        let mut writer = output(Some(&page_path(path, page, drawing.pages.len())));
+        write_artifact(options, format, &page_artifact, &mut writer);
+        writer.flush().expect("Couldn't write drawing");
+    }
+}
+
+/// Characters across the terminal, or across a typical one if it doesn't say.
+fn terminal_columns() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
+
+/// Write an artifact in a format that holds all of its pages.
+fn write_artifact(
+    options: &OutputOptions,
+    format: OutputFormat,
+    artifact: &Artifact,
+    writer: &mut dyn Write,
+) {
+    let renderer: Box<dyn Renderer> = match format {
+        OutputFormat::Json => {
+            write_json(writer, &artifact.sets).expect("Couldn't write to json");
+            return;
+        }
+        OutputFormat::Zip => panic!("Only sheets can be written to a zip archive"),
+        OutputFormat::Terminal => {
+            let drawing = &artifact.drawing;
+            let renderer: Box<dyn Renderer> = match options.graphics.protocol() {
+                Some(protocol) => {
+                    let style = RgbaStyle::default();
+                    Box::new(InlineRenderer {
+                        style: RgbaStyle {
+                            color: options.color(artifact.theme, style.color),
+                            background: options
+                                .background(artifact.theme, Some(style.background))
+                                .unwrap_or([0; 4]),
+                        },
+                        scale: drawing
+                            .unit
+                            .inches()
+                            .map_or(1.0, |inches| inches * options.dpi),
+                        ..InlineRenderer::new(protocol)
+                    })
+                }
+                None => Box::new(TerminalRenderer {
+                    columns: terminal_columns(),
+                    ..TerminalRenderer::default()
+                }),
+            };
+            for page in 0..drawing.pages.len() {
+                renderer
+                    .render(&drawing.page(page), writer)
+                    .expect("Couldn't write drawing");
+            }
+            return;
+        }
+        OutputFormat::Svg => {
+            let svg = SvgRenderer::default();
+            Box::new(SvgRenderer {
+                color: options.color(artifact.theme, svg.color),
+                background: options.background(artifact.theme, svg.background),
+                ..svg
+            })
+        }
+        OutputFormat::Hpgl => Box::new(HpglRenderer::default()),
+        #[cfg(feature = "cairo")]
+        OutputFormat::Png => {
+            let png = PngRenderer::default();
+            Box::new(PngRenderer {
+                text: artifact
+                    .text
+                    .iter()
+                    .map(|(keyword, text)| (keyword.to_string(), text.clone()))
+                    .collect(),
+                color: options.color(artifact.theme, png.color),
+                background: options.background(artifact.theme, png.background),
+                dpi: options.dpi,
+            })
+        }
+        #[cfg(feature = "cairo")]
+        OutputFormat::Pdf => {
+            let pdf = PdfRenderer::default();
+            Box::new(PdfRenderer {
+                page_size: options.page_points(),
+                color: options.color(artifact.theme, pdf.color),
+                background: options.background(artifact.theme, pdf.background),
+            })
+        }
+        #[cfg(not(feature = "cairo"))]
+        OutputFormat::Png | OutputFormat::Pdf => {
+            panic!("Built without cairo, so can't write {:?}", format)
+        }
+    };
+    let bar = progress_bar("Drawing", artifact.drawing.glyph_count() as u64);
+    renderer
+        .render_with_progress(&artifact.drawing, writer, &mut |progress| {
+            bar.set_position(progress.done as u64)
+        })
+        .expect("Couldn't write drawing");
+    bar.finish_and_clear();
+}
+
+/// The file for a page of a drawing, numbered from one after the file's name, like
+/// `sheet-02.svg`, with enough digits for every page.
+fn page_path(path: &Path, page: usize, pages: usize) -> PathBuf {
+    let digits = pages.to_string().len();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!("{}-{:0digits$}", stem, page + 1, digits = digits);
+    if let Some(extension) = path.extension() {
+        name.push('.');
+        name.push_str(&extension.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+/// The output file, or stdout if there isn't one, buffered.
+fn output(path: Option<&Path>) -> Box<dyn Write> {
+    if let Some(output_path) = path {
+        Box::new(BufWriter::new(
+            File::create(output_path).expect("Couldn't create file"),
+        ))
+    } else {
+        Box::new(BufWriter::new(stdout().lock()))
+    }
+}
+
+fn main() {
+    match Command::from_args() {
+        Command::Sheet(options) => sheet(&options),
+        Command::Sweep(options) => sweep(&options),
+        Command::Glyph(options) => glyph(&options),
+        Command::Text(options) => text(&options),
+        Command::Set(options) => set(&options),
+        Command::Identicon(options) => identicon(&options),
+        Command::Reproduce(options) => reproduce(&options),
+        Command::Verify(options) => verify(&options),
+        Command::Stats(options) => stats(&options),
+        Command::Search(options) => search(&options),
+        Command::Preview(options) => preview(&options),
+        Command::Evolve(options) => evolve(&options),
+        #[cfg(feature = "font-source")]
+        Command::Font(options) => font(&options),
+        #[cfg(feature = "catalog")]
+        Command::Catalog(command) => catalog(&command),
+        #[cfg(feature = "gui")]
+        Command::Gui(options) => gui::run(options.generation.config()),
+        #[cfg(feature = "window")]
+        Command::Window(options) => {
+            let mut config = options.generation.config();
+            options.layout.apply(&mut config.sheet);
+            window::run(config, options.seed_start)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_formats_from_extensions() {
+        let format = |name: &str| OutputFormat::of_file(Some(Path::new(name)));
+        assert_eq!(format("sheet.svg"), Ok(OutputFormat::Svg));
+        assert_eq!(format("plot.PLT"), Ok(OutputFormat::Hpgl));
+        assert_eq!(format("glyphs.zip"), Ok(OutputFormat::Zip));
+        assert_eq!(OutputFormat::of_file(None), Ok(DEFAULT_FORMAT));
+        assert_eq!(
+            format("sheet.txt"),
+            Err("Unknown format 'txt', choose one with --format".to_owned())
+        );
+    }
+
+    #[test]
+    fn archives_list_every_file_in_their_manifest() {
+        let output = OutputOptions::from_iter(vec!["symbolgen"]);
+        let split =
+            SplitOptions::from_iter(vec!["symbolgen", "--name-template", "glyph-{index}.svg"]);
+        let config = GenerationConfig::default();
+        let alphabets: Vec<Alphabet> = (0..2)
+            .map(|row| config.alphabet.build(row).unwrap())
+            .collect();
+        let rows: Vec<Vec<Glyph>> = alphabets
+            .iter()
+            .map(|alphabet| {
+                (0..3)
+                    .map(|seed| alphabet.generate(seed).unwrap())
+                    .collect()
+            })
+            .collect();
+        let manifest = Manifest::new(&config, &rows);
+        let archive = fill_archive(
+            ZipWriter::new(Cursor::new(Vec::new())),
+            &output,
+            &split,
+            &config,
+            &manifest,
+            &alphabets,
+            &rows,
+        );
+
+        let mut archive = ZipArchive::new(archive).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_owned).collect();
+        names.sort();
+        let listed: serde_json::Value =
+            serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+        let mut files: Vec<String> = listed["files"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|file| file["name"].as_str().unwrap().to_owned())
+            .collect();
+        files.push("manifest.json".to_owned());
+        files.sort();
+        assert_eq!(names.len(), 7);
+        assert_eq!(names, files);
+
+        // Verify reads the manifest back from the archive
+        let read = Manifest::read(archive.by_name("manifest.json").unwrap()).unwrap();
+        assert_eq!(read, manifest);
+    }
+
+    #[test]
+    fn failing_draws_return_errors() {
+        let path =
+            std::env::temp_dir().join(format!("symbolgen-watch-{}.toml", std::process::id()));
+        let options = SheetOptions::from_iter(vec!["symbolgen"]);
+
+        // A half finished edit doesn't parse
+        std::fs::write(&path, "[alphabet]\ndensity = ").unwrap();
+        assert!(matches!(
+            draw_sheet(&options, Some(&path)),
+            Err(Error::Config(_))
+        ));
+
+        // Nor can glyphs be generated from settings out of range
+        std::fs::write(&path, "[alphabet]\ndensity = 0\n").unwrap();
+        assert!(matches!(
+            draw_sheet(&options, Some(&path)),
+            Err(Error::Density(0))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
 }
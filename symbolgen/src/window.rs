@@ -0,0 +1,160 @@
+//! A window showing a sheet of glyphs, paged through from the keyboard.
+
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use softbuffer::{Context, Surface};
+use symbolgen_core::GenerationConfig;
+use symbolgen_render::{render_to_rgba, Drawing, RgbaStyle};
+use winit::application::ApplicationHandler;
+use winit::event::{ElementState, KeyEvent, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::keyboard::{Key, NamedKey};
+use winit::window::{Window, WindowId};
+
+use crate::{generate_rows, sheet_drawing, SeedList, Selection};
+
+/// Open a window of sheets from the given settings, the first starting at the given seed,
+/// until it's closed.
+pub fn run(config: GenerationConfig, seed_start: u64) {
+    let event_loop = EventLoop::new().expect("Couldn't open window");
+    let mut preview = Preview {
+        config,
+        seed_start,
+        page: 0,
+        drawing: None,
+        window: None,
+    };
+    event_loop
+        .run_app(&mut preview)
+        .expect("Couldn't open window");
+}
+
+struct Preview {
+    config: GenerationConfig,
+    seed_start: u64,
+    /// Page of seeds shown, counting from the first
+    page: u64,
+    /// The page drawn, and its drawing
+    drawing: Option<(u64, Drawing)>,
+    window: Option<(Rc<Window>, WindowSurface)>,
+}
+
+/// Pixels drawn into a window.
+type WindowSurface = Surface<Rc<Window>, Rc<Window>>;
+
+impl Preview {
+    /// Glyphs on each page, filling every row.
+    fn page_size(&self) -> u64 {
+        (self.config.sheet.rows * self.config.sheet.columns).max(1) as u64
+    }
+
+    /// Seeds on the page shown.
+    fn seeds(&self) -> SeedList {
+        let start = self.seed_start + self.page * self.page_size();
+        SeedList((start..start + self.page_size()).collect())
+    }
+
+    /// The drawing of the page shown, generating it if the page has changed.
+    fn drawing(&mut self) -> &Drawing {
+        if !matches!(&self.drawing, Some((page, _)) if *page == self.page) {
+            let seeds = self.seeds();
+            let columns = self.config.sheet.columns.max(1) as usize;
+            let (_, rows, _) = generate_rows(&self.config, columns, Selection::from(&seeds))
+                .expect("Couldn't generate glyphs");
+            let drawing = sheet_drawing(&self.config.sheet, &rows, self.config.theme.as_ref());
+            self.drawing = Some((self.page, drawing));
+        }
+        let (_, drawing) = self.drawing.as_ref().expect("Page was just drawn");
+        drawing
+    }
+
+    /// Show another page, retitling the window for it.
+    fn turn_to(&mut self, page: u64) {
+        self.page = page;
+        if let Some((window, _)) = &self.window {
+            window.set_title(&self.title());
+            window.request_redraw();
+        }
+    }
+
+    fn title(&self) -> String {
+        format!("symbolgen: seeds {}", self.seeds())
+    }
+
+    fn redraw(&mut self) {
+        let style = match &self.config.theme {
+            Some(theme) => RgbaStyle {
+                color: theme.text().0,
+                background: theme.background.map_or([255; 4], |color| color.0),
+            },
+            None => RgbaStyle::default(),
+        };
+        let size = match &self.window {
+            Some((window, _)) => window.inner_size(),
+            None => return,
+        };
+        let (width, height) = match (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) {
+            (Some(width), Some(height)) => (width, height),
+            _ => return,
+        };
+        let pixels = render_to_rgba(self.drawing(), size.width, size.height, &style)
+            .expect("Couldn't draw sheet");
+
+        let (_, surface) = self.window.as_mut().expect("Window was just found");
+        surface
+            .resize(width, height)
+            .expect("Couldn't resize window");
+        let mut buffer = surface.buffer_mut().expect("Couldn't draw window");
+        for (pixel, rgba) in buffer.iter_mut().zip(pixels.chunks_exact(4)) {
+            // The background is opaque, so alpha can be left out
+            *pixel = u32::from(rgba[0]) << 16 | u32::from(rgba[1]) << 8 | u32::from(rgba[2]);
+        }
+        buffer.present().expect("Couldn't draw window");
+    }
+}
+
+impl ApplicationHandler for Preview {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_some() {
+            return;
+        }
+        let attributes = Window::default_attributes().with_title(self.title());
+        let window = Rc::new(
+            event_loop
+                .create_window(attributes)
+                .expect("Couldn't open window"),
+        );
+        let context = Context::new(window.clone()).expect("Couldn't open window");
+        let surface = Surface::new(&context, window.clone()).expect("Couldn't open window");
+        self.window = Some((window, surface));
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::RedrawRequested => self.redraw(),
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key,
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => match logical_key {
+                Key::Named(NamedKey::ArrowRight | NamedKey::PageDown | NamedKey::Space) => {
+                    self.turn_to(self.page + 1)
+                }
+                Key::Named(NamedKey::ArrowLeft | NamedKey::PageUp) => {
+                    self.turn_to(self.page.saturating_sub(1))
+                }
+                Key::Named(NamedKey::Home) => self.turn_to(0),
+                Key::Named(NamedKey::Escape) => event_loop.exit(),
+                Key::Character(character) if character == "q" => event_loop.exit(),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Glyphs changing over time.
+
+use std::cmp::Ordering;
+
+use crate::{Curve, Glyph, Line, Point, Segment, Stroke};
+
+/// Points each segment is measured between, to share out drawing time.
+const LENGTH_SAMPLES: usize = 16;
+
+/// How the pen speeds up and slows down along each stroke.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Start and finish each stroke slowly.
+    EaseInOut,
+}
+
+impl Easing {
+    /// How far along a stroke the pen is, a fraction `t` of the way through its time.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// A stroke of an animation, drawn between two times.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timing {
+    /// The segment drawn
+    pub segment: Segment,
+    /// Whether the segment is drawn from its end to its start
+    pub reversed: bool,
+    /// When drawing starts, from 0 to 1
+    pub start: f64,
+    /// When drawing finishes, from 0 to 1
+    pub end: f64,
+}
+
+/// Draws a glyph over time, one segment after another.
+///
+/// Segments are drawn in the order of the glyph's single stroke path, each taking time in
+/// proportion to its length, so the pen keeps a steady pace between strokes.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Animator {
+    pub easing: Easing,
+}
+
+impl Default for Animator {
+    fn default() -> Self {
+        Self {
+            easing: Easing::Linear,
+        }
+    }
+}
+
+impl Animator {
+    pub fn new(easing: Easing) -> Self {
+        Self { easing }
+    }
+
+    /// The order and times that each segment of the glyph is drawn.
+    pub fn schedule(&self, glyph: &Glyph) -> Vec<Timing> {
+        let strokes: Vec<(Segment, bool)> = glyph
+            .to_single_stroke()
+            .into_iter()
+            .filter_map(|stroke| match stroke {
+                Stroke::Draw { segment, reversed } => Some((segment, reversed)),
+                Stroke::Travel(_) => None,
+            })
+            .collect();
+        let lengths: Vec<f64> = strokes
+            .iter()
+            .map(|(segment, _)| segment.length(LENGTH_SAMPLES))
+            .collect();
+        let total: f64 = lengths.iter().sum();
+
+        let mut start = 0.0;
+        strokes
+            .into_iter()
+            .zip(lengths)
+            .map(|((segment, reversed), length)| {
+                let end = if total > 0.0 {
+                    start + length / total
+                } else {
+                    1.0
+                };
+                let timing = Timing {
+                    segment,
+                    reversed,
+                    start,
+                    end,
+                };
+                start = end;
+                timing
+            })
+            .collect()
+    }
+
+    /// The glyph as drawn by time `t`, from nothing at 0 to the whole glyph at 1.
+    ///
+    /// The segment being drawn is cut short, from whichever end it is drawn from.
+    pub fn frame(&self, glyph: &Glyph, t: f64) -> Vec<Segment> {
+        self.schedule(glyph)
+            .into_iter()
+            .filter(|timing| t > timing.start)
+            .map(|timing| {
+                if t >= timing.end {
+                    return timing.segment;
+                }
+                let progress = self
+                    .easing
+                    .apply((t - timing.start) / (timing.end - timing.start));
+                if timing.reversed {
+                    timing.segment.between(1.0 - progress, 1.0)
+                } else {
+                    timing.segment.between(0.0, progress)
+                }
+            })
+            .filter(|segment| !segment.is_degenerate())
+            .collect()
+    }
+}
+
+/// A glyph part way between two others, at `t` from 0 for `a` to 1 for `b`.
+///
+/// Each segment of `a` is paired with the nearest unpaired segment of `b`. Segments left
+/// over grow out of, or shrink into, a point at the middle of themselves. Lines stay lines,
+/// other segments move as curves. The result keeps the seed of whichever glyph is nearer.
+pub fn morph(a: &Glyph, b: &Glyph, t: f64) -> Glyph {
+    let (from, to) = (pieces(a), pieces(b));
+    let mut paired = vec![false; to.len()];
+    let mut pairs = Vec::new();
+    for start in from.iter() {
+        let nearest = to
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !paired[*index])
+            .map(|(index, end)| (index, (midpoint(end) - midpoint(start)).norm()))
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let end = match nearest {
+            Some((index, _)) => {
+                paired[index] = true;
+                to[index].clone()
+            }
+            None => collapsed(start),
+        };
+        pairs.push((start.clone(), end));
+    }
+    for (end, _) in to.iter().zip(paired).filter(|(_, paired)| !paired) {
+        pairs.push((collapsed(end), end.clone()));
+    }
+
+    let segments = pairs
+        .iter()
+        .map(|(start, end)| match (start, end) {
+            (Segment::Line(start), Segment::Line(end)) => Line::new(
+                lerp(start.start(), end.start(), t),
+                lerp(start.end(), end.end(), t),
+            )
+            .into(),
+            (start, end) => {
+                let (start, end) = (as_curve(start).points(), as_curve(end).points());
+                let point = |index: usize| lerp(start[index], end[index], t);
+                Curve::new(point(0), point(1), point(2), point(3)).into()
+            }
+        })
+        .filter(|segment: &Segment| !segment.is_degenerate())
+        .collect();
+    let seed = if t < 0.5 { a.seed() } else { b.seed() };
+    Glyph::new(seed, segments)
+}
+
+/// Segments of a glyph as lines and curves, with arcs split into curves.
+fn pieces(glyph: &Glyph) -> Vec<Segment> {
+    glyph
+        .segments()
+        .iter()
+        .flat_map(|segment| match segment {
+            Segment::Arc(arc) => arc.to_curves().into_iter().map(Segment::from).collect(),
+            segment => vec![segment.clone()],
+        })
+        .collect()
+}
+
+/// A line or curve as a cubic curve.
+fn as_curve(segment: &Segment) -> Curve {
+    match segment {
+        Segment::Curve(curve) => curve.clone(),
+        segment => {
+            let (start, end) = (segment.start(), segment.end());
+            Curve::new(
+                start,
+                lerp(start, end, 1.0 / 3.0),
+                lerp(start, end, 2.0 / 3.0),
+                end,
+            )
+        }
+    }
+}
+
+/// A segment of the same kind shrunk to a point at its middle.
+fn collapsed(segment: &Segment) -> Segment {
+    let middle = midpoint(segment);
+    match segment {
+        Segment::Line(_) => Line::new(middle, middle).into(),
+        _ => Curve::new(middle, middle, middle, middle).into(),
+    }
+}
+
+fn midpoint(segment: &Segment) -> Point {
+    segment.point_at(0.5)
+}
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    a + (b - a) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_draw_segments_in_turn() {
+        let glyph = Glyph::new(
+            0,
+            vec![
+                Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0)).into(),
+                Line::new(Point::new(1.0, 0.0), Point::new(1.0, 1.0)).into(),
+            ],
+        );
+        let animator = Animator::default();
+        assert!(animator.frame(&glyph, 0.0).is_empty());
+        assert_eq!(animator.frame(&glyph, 1.0).len(), 2);
+
+        let quarter = animator.frame(&glyph, 0.25);
+        assert_eq!(quarter.len(), 1);
+        assert!((quarter[0].length(2) - 0.5).abs() < 1e-6);
+        assert_eq!(glyph.frame(0.75).len(), 2);
+    }
+
+    #[test]
+    fn morph_moves_between_glyphs() {
+        let line =
+            |x0, y0, x1, y1| Segment::from(Line::new(Point::new(x0, y0), Point::new(x1, y1)));
+        let a = Glyph::new(1, vec![line(0.0, 0.0, 1.0, 0.0)]);
+        let b = Glyph::new(2, vec![line(0.0, 1.0, 1.0, 1.0), line(0.0, 2.0, 1.0, 2.0)]);
+
+        assert_eq!(morph(&a, &b, 0.0), a);
+        assert_eq!(morph(&a, &b, 1.0), b);
+        let halfway = morph(&a, &b, 0.5);
+        assert_eq!(halfway.seed(), 2);
+        assert_eq!(halfway.segments().len(), 2);
+        assert!(halfway.segments().contains(&line(0.0, 0.5, 1.0, 0.5)));
+        assert!(halfway.segments().contains(&line(0.25, 2.0, 0.75, 2.0)));
+    }
+}
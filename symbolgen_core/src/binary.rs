@@ -0,0 +1,64 @@
+//! Generated alphabets saved in a compact binary file, to load rather than regenerate.
+//!
+//! A file starts with the bytes `SYMG` and a format version, followed by the alphabet's
+//! settings and its glyphs encoded with bincode.
+
+use std::io::{Read, Write};
+
+use crate::{Alphabet, Error, Glyph, Result};
+
+const MAGIC: &[u8; 4] = b"SYMG";
+const VERSION: u8 = 1;
+
+impl Alphabet {
+    /// Save the alphabet's settings and glyphs it generated.
+    pub fn save<W: Write>(&self, mut writer: W, glyphs: &[Glyph]) -> Result<()> {
+        writer.write_all(MAGIC).map_err(binary_error)?;
+        writer.write_all(&[VERSION]).map_err(binary_error)?;
+        bincode::serialize_into(writer, &(self, glyphs)).map_err(binary_error)
+    }
+
+    /// Load an alphabet's settings and glyphs saved with `Alphabet::save`.
+    pub fn load<R: Read>(mut reader: R) -> Result<(Alphabet, Vec<Glyph>)> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header).map_err(binary_error)?;
+        if &header[..4] != MAGIC {
+            return Err(Error::Binary("not a saved alphabet".to_owned()));
+        }
+        if header[4] != VERSION {
+            return Err(Error::Binary(format!("unknown version {}", header[4])));
+        }
+        bincode::deserialize_from(reader).map_err(binary_error)
+    }
+}
+
+impl Glyph {
+    /// Load the glyphs saved with `Alphabet::save`, without their alphabet's settings.
+    pub fn load<R: Read>(reader: R) -> Result<Vec<Glyph>> {
+        Alphabet::load(reader).map(|(_, glyphs)| glyphs)
+    }
+}
+
+fn binary_error<E: std::fmt::Display>(error: E) -> Error {
+    Error::Binary(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Motif, Symmetry};
+
+    #[test]
+    fn saved_alphabet_loads() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Horizontal, Motif::Diagonal).unwrap();
+        let glyphs = alphabet.generate_batch(0..10).unwrap();
+        let mut bytes = Vec::new();
+        alphabet.save(&mut bytes, &glyphs).unwrap();
+
+        let (loaded, loaded_glyphs) = Alphabet::load(bytes.as_slice()).unwrap();
+        assert_eq!(loaded_glyphs, glyphs);
+        assert_eq!(loaded.generate(10).unwrap(), alphabet.generate(10).unwrap());
+        assert_eq!(Glyph::load(bytes.as_slice()).unwrap(), glyphs);
+        assert!(Glyph::load(&b"PNG"[..]).is_err());
+    }
+}
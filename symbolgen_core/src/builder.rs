@@ -0,0 +1,223 @@
+//! Step by step construction of alphabets.
+
+use crate::{
+    Algorithm, Alphabet, Edge, Error, GlyphTransform, Lattice, LineCount, Motif, Point, Result,
+    Span, Stage, Symmetry,
+};
+
+/// Builds an `Alphabet`, checking the settings make sense together.
+///
+/// Defaults to a 4 by 4 square grid with 3 diagonal lines per resolution and no symmetry.
+#[derive(Debug)]
+pub struct AlphabetBuilder {
+    resolution_x: i32,
+    resolution_y: i32,
+    density: i32,
+    symmetry: Symmetry,
+    motif: Motif,
+    lattice: Lattice,
+    edge: Edge,
+    span: Span,
+    max_length: Option<i32>,
+    axis: Point,
+    algorithm: Algorithm,
+    line_count: LineCount,
+    min_lines: Option<usize>,
+    max_lines: Option<usize>,
+    deduplicate: bool,
+    stages: Vec<Box<dyn GlyphTransform>>,
+}
+
+impl Default for AlphabetBuilder {
+    fn default() -> Self {
+        Self {
+            resolution_x: 4,
+            resolution_y: 4,
+            density: 3,
+            symmetry: Symmetry::Asymmetric,
+            motif: Motif::Diagonal,
+            lattice: Lattice::Square,
+            edge: Edge::Clamp,
+            span: Span::Unit,
+            max_length: None,
+            axis: Point::new(0.5, 0.5),
+            algorithm: Algorithm::LATEST,
+            line_count: LineCount::Attempts,
+            min_lines: None,
+            max_lines: None,
+            deduplicate: true,
+            stages: Vec::new(),
+        }
+    }
+}
+
+impl AlphabetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of points along each axis.
+    pub fn resolution(self, resolution: i32) -> Self {
+        self.rectangular(resolution, resolution)
+    }
+
+    /// Different numbers of points along each axis.
+    pub fn rectangular(mut self, resolution_x: i32, resolution_y: i32) -> Self {
+        self.resolution_x = resolution_x;
+        self.resolution_y = resolution_y;
+        self
+    }
+
+    /// The number of lines to draw per resolution.
+    pub fn density(mut self, density: i32) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    pub fn motif(mut self, motif: Motif) -> Self {
+        self.motif = motif;
+        self
+    }
+
+    pub fn lattice(mut self, lattice: Lattice) -> Self {
+        self.lattice = lattice;
+        self
+    }
+
+    pub fn edge(mut self, edge: Edge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    /// How many steps each line spans, a single step by default.
+    pub fn span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// The longest line allowed, in steps, as many as fit across the grid by default.
+    pub fn max_length(mut self, max_length: i32) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// The point through which the mirror axes pass.
+    pub fn axis(mut self, axis: Point) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// The version of the generation algorithm, the latest by default.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn line_count(mut self, line_count: LineCount) -> Self {
+        self.line_count = line_count;
+        self
+    }
+
+    /// The fewest segments a glyph may have, after the pipeline.
+    pub fn min_lines(mut self, min_lines: usize) -> Self {
+        self.min_lines = Some(min_lines);
+        self
+    }
+
+    /// The most segments a glyph may have, after the pipeline.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    /// Whether to skip segments already drawn and remove symmetric copies, as by default.
+    pub fn deduplicate(mut self, deduplicate: bool) -> Self {
+        self.deduplicate = deduplicate;
+        self
+    }
+
+    /// Add a stage to the end of the pipeline, after symmetry and deduplication.
+    pub fn transform<T: GlyphTransform + 'static>(mut self, stage: T) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    pub fn build(self) -> Result<Alphabet> {
+        if let Some(max_length) = self.max_length.filter(|length| *length < 1) {
+            return Err(Error::MaxLength(max_length));
+        }
+        if let (Some(min), Some(max)) = (self.min_lines, self.max_lines) {
+            if min > max {
+                return Err(Error::LineLimits { min, max });
+            }
+        }
+
+        let mut alphabet = Alphabet::rectangular(
+            self.resolution_x,
+            self.resolution_y,
+            self.density,
+            self.symmetry,
+            self.motif,
+        )?;
+        alphabet.grid.lattice = self.lattice;
+        alphabet.grid.edge = self.edge;
+        alphabet.grid.span = self.span;
+        if let Some(max_length) = self.max_length {
+            alphabet.grid.max_length = max_length;
+        }
+        alphabet.axis = self.axis;
+        alphabet.algorithm = self.algorithm;
+        alphabet.line_count = self.line_count;
+        alphabet.min_lines = self.min_lines;
+        alphabet.max_lines = self.max_lines;
+        alphabet.deduplicate = self.deduplicate;
+        if !self.deduplicate {
+            alphabet
+                .pipeline
+                .retain(|stage| stage.stage() != Some(Stage::Deduplicate));
+        }
+        alphabet.pipeline.extend(self.stages);
+        Ok(alphabet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_rejects_single_point_grid() {
+        assert!(Alphabet::builder().resolution(1).build().is_err());
+        assert!(Alphabet::builder().resolution(2).build().is_ok());
+    }
+
+    #[test]
+    fn lines_span_up_to_max_length() {
+        assert_eq!(
+            Alphabet::builder().max_length(0).build().unwrap_err(),
+            Error::MaxLength(0)
+        );
+        let alphabet = Alphabet::builder()
+            .resolution(8)
+            .density(4)
+            .span(Span::Uniform)
+            .max_length(3)
+            .build()
+            .unwrap();
+        let step = alphabet.grid.step_x;
+        let mut longest = 0.0_f64;
+        for seed in 0..50 {
+            for segment in alphabet.generate(seed).unwrap().segments() {
+                let (start, end) = (segment.start(), segment.end());
+                let steps = ((end.x - start.x).abs().max((end.y - start.y).abs()) / step).round();
+                longest = longest.max(steps);
+            }
+        }
+        assert_eq!(longest, 3.0);
+    }
+}
@@ -0,0 +1,419 @@
+//! Settings for a whole sheet of glyphs, which can be loaded from TOML.
+
+#[cfg(feature = "config")]
+use std::path::Path;
+
+#[cfg(feature = "config")]
+use crate::Error;
+use crate::{
+    Algorithm, Alphabet, Edge, Lattice, Line, Motif, Point, Result, Symmetry, Theme, Unit, Vector,
+};
+
+/// Everything needed to generate and lay out a sheet of glyphs.
+///
+/// Missing sections and fields take their default values. A TOML file may also hold named
+/// profiles as `[profiles.NAME]` tables, laid out like the whole config, whose fields are
+/// used in place of the rest of the file's when the profile is chosen.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct GenerationConfig {
+    pub alphabet: AlphabetConfig,
+    pub sheet: SheetLayout,
+    /// Colours to draw in, in place of the defaults of each output format
+    pub theme: Option<Theme>,
+}
+
+impl GenerationConfig {
+    /// Parse a config from TOML, ignoring any profiles.
+    #[cfg(feature = "config")]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        Self::from_toml_profile(toml, None)
+    }
+
+    /// Parse a config from TOML, with the fields of the named profile, if any, in place of
+    /// the rest of the file's.
+    #[cfg(feature = "config")]
+    pub fn from_toml_profile(toml: &str, profile: Option<&str>) -> Result<Self> {
+        let config_error = |error: &dyn std::fmt::Display| Error::Config(error.to_string());
+        let mut table: toml::Table = toml::from_str(toml).map_err(|error| config_error(&error))?;
+        let profiles = table.remove("profiles");
+        if let Some(name) = profile {
+            let overrides = profiles
+                .as_ref()
+                .and_then(|profiles| profiles.get(name))
+                .and_then(toml::Value::as_table)
+                .ok_or_else(|| Error::Config(format!("No profile '{}'", name)))?;
+            merge(&mut table, overrides);
+        }
+        table.try_into().map_err(|error| config_error(&error))
+    }
+
+    /// Read a config from a TOML file, ignoring any profiles.
+    #[cfg(feature = "config")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::load_profile(path, None)
+    }
+
+    /// Read a config from a TOML file, with the named profile applied as for
+    /// `from_toml_profile`.
+    #[cfg(feature = "config")]
+    pub fn load_profile<P: AsRef<Path>>(path: P, profile: Option<&str>) -> Result<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .map_err(|error| Error::Config(format!("{}: {}", path.display(), error)))?;
+        Self::from_toml_profile(&toml, profile)
+    }
+
+    /// The config as TOML, which loads back to the same config.
+    #[cfg(feature = "config")]
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|error| Error::Config(error.to_string()))
+    }
+}
+
+/// Replace fields of a TOML table with those of another, merging tables found in both.
+#[cfg(feature = "config")]
+fn merge(table: &mut toml::Table, overrides: &toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(overrides)) => {
+                merge(table, overrides)
+            }
+            _ => {
+                table.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// The alphabet used for each row of a sheet.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct AlphabetConfig {
+    /// The number of points along each axis. If unset, the first row has 2 and each row
+    /// after has one more.
+    pub resolution: Option<i32>,
+    /// The number of lines to draw per resolution
+    pub density: i32,
+    pub symmetry: Symmetry,
+    pub motif: Motif,
+    pub edge: Edge,
+    pub lattice: Lattice,
+    /// Version of the generation algorithm. Set this to keep glyphs the same in future
+    /// releases.
+    pub algorithm: Algorithm,
+}
+
+impl Default for AlphabetConfig {
+    fn default() -> Self {
+        Self {
+            resolution: None,
+            density: 3,
+            symmetry: Symmetry::Asymmetric,
+            motif: Motif::Diagonal,
+            edge: Edge::Clamp,
+            lattice: Lattice::Square,
+            algorithm: Algorithm::LATEST,
+        }
+    }
+}
+
+impl AlphabetConfig {
+    /// Each setting and its value, as on the command line, like `("motif", "diagonal")`.
+    /// An unset resolution is `row+2`.
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        let resolution = match self.resolution {
+            Some(resolution) => resolution.to_string(),
+            None => "row+2".to_owned(),
+        };
+        vec![
+            ("resolution", resolution),
+            ("density", self.density.to_string()),
+            ("motif", self.motif.to_string()),
+            ("symmetry", self.symmetry.to_string()),
+            ("edge", self.edge.to_string()),
+            ("lattice", self.lattice.to_string()),
+            ("algorithm", self.algorithm.to_string()),
+        ]
+    }
+
+    /// Build the alphabet for the given row of a sheet.
+    pub fn build(&self, row: i32) -> Result<Alphabet> {
+        Alphabet::builder()
+            .resolution(self.resolution.unwrap_or(row + 2))
+            .density(self.density)
+            .symmetry(self.symmetry.clone())
+            .motif(self.motif.clone())
+            .edge(self.edge.clone())
+            .lattice(self.lattice.clone())
+            .algorithm(self.algorithm)
+            .build()
+    }
+}
+
+/// The arrangement of glyphs in rows and columns on a sheet.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct SheetLayout {
+    pub rows: i32,
+    pub columns: i32,
+    /// Size of each glyph
+    pub scale: f64,
+    /// Gap between glyphs, and around the edge of the sheet
+    pub spacing: f64,
+    /// Width of the lines glyphs are drawn with
+    pub line_width: f64,
+    /// Split the rows across pages of this many rows each, for formats with pages
+    pub rows_per_page: Option<i32>,
+    /// Unit of the scale, spacing and line width
+    pub units: Unit,
+    /// Space around the glyphs, inside the page as trimmed
+    pub margin: f64,
+    /// Space around the trimmed page, for the background to run past the edge
+    pub bleed: f64,
+    /// Mark the corners of the trimmed page with lines outside the bleed, as long as the
+    /// spacing
+    pub crop_marks: bool,
+    /// Space above the glyphs on each page, for a title
+    pub header: f64,
+}
+
+impl Default for SheetLayout {
+    fn default() -> Self {
+        Self {
+            rows: 4,
+            columns: 26,
+            scale: 25.0,
+            spacing: 25.0,
+            line_width: 4.0,
+            rows_per_page: None,
+            units: Unit::Px,
+            margin: 0.0,
+            bleed: 0.0,
+            crop_marks: false,
+            header: 0.0,
+        }
+    }
+}
+
+impl SheetLayout {
+    /// Width of the whole sheet, including any margin, bleed and crop marks.
+    pub fn width(&self) -> f64 {
+        self.spacing + (self.scale + self.spacing) * self.columns as f64 + 2.0 * self.border()
+    }
+
+    /// Height of the whole sheet, including any margin, bleed and crop marks.
+    pub fn height(&self) -> f64 {
+        self.spacing
+            + (self.scale + self.spacing) * self.rows as f64
+            + 2.0 * self.border()
+            + self.header
+    }
+
+    /// Space around the glyphs on each side of a page, for margins, bleed and crop marks.
+    fn border(&self) -> f64 {
+        let marks = if self.crop_marks { self.spacing } else { 0.0 };
+        self.margin + self.bleed + marks
+    }
+
+    /// Top left and bottom right corners of each page as trimmed, inside the bleed.
+    pub fn trim(&self) -> (Point, Point) {
+        let outside = self.border() - self.margin;
+        (
+            Point::new(outside, outside),
+            Point::new(self.width() - outside, self.page_height() - outside),
+        )
+    }
+
+    /// Lines marking the corners of each page as trimmed, outside the bleed, if the sheet
+    /// has crop marks.
+    pub fn crop_marks(&self) -> Vec<Line> {
+        if !self.crop_marks {
+            return Vec::new();
+        }
+        let (top_left, bottom_right) = self.trim();
+        let (near, far) = (self.bleed, self.bleed + self.spacing);
+        let mut marks = Vec::new();
+        for (x, outwards_x) in [(top_left.x, -1.0), (bottom_right.x, 1.0)] {
+            for (y, outwards_y) in [(top_left.y, -1.0), (bottom_right.y, 1.0)] {
+                marks.push(Line::new(
+                    Point::new(x + outwards_x * near, y),
+                    Point::new(x + outwards_x * far, y),
+                ));
+                marks.push(Line::new(
+                    Point::new(x, y + outwards_y * near),
+                    Point::new(x, y + outwards_y * far),
+                ));
+            }
+        }
+        marks
+    }
+
+    /// Rows on each page, which is all of them unless split across pages.
+    pub fn page_rows(&self) -> i32 {
+        self.rows_per_page
+            .unwrap_or(self.rows)
+            .clamp(1, self.rows.max(1))
+    }
+
+    /// The number of pages the rows are split across.
+    pub fn pages(&self) -> i32 {
+        (self.rows + self.page_rows() - 1) / self.page_rows()
+    }
+
+    /// Height of each page, including any margin, bleed and crop marks.
+    pub fn page_height(&self) -> f64 {
+        self.spacing
+            + (self.scale + self.spacing) * self.page_rows() as f64
+            + 2.0 * self.border()
+            + self.header
+    }
+
+    /// The page the glyph in the given row and column is on, and its top left corner there.
+    pub fn page_offset(&self, row: i32, column: i32) -> (i32, Vector) {
+        let page_rows = self.page_rows();
+        (row / page_rows, self.offset(row % page_rows, column))
+    }
+
+    /// Top left corner of the glyph in the given row and column.
+    pub fn offset(&self, row: i32, column: i32) -> Vector {
+        let start = self.border() + self.spacing;
+        Vector::new(
+            start + (self.scale + self.spacing) * column as f64,
+            start + self.header + (self.scale + self.spacing) * row as f64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_split_across_pages() {
+        let sheet = SheetLayout {
+            rows: 5,
+            rows_per_page: Some(2),
+            ..SheetLayout::default()
+        };
+        assert_eq!(sheet.pages(), 3);
+        assert_eq!(sheet.page_offset(3, 1), (1, sheet.offset(1, 1)));
+        assert_eq!(sheet.page_height(), 125.0);
+        assert_eq!(SheetLayout::default().pages(), 1);
+    }
+
+    #[test]
+    fn crop_marks_outside_bleed() {
+        let sheet = SheetLayout {
+            rows: 1,
+            columns: 1,
+            spacing: 10.0,
+            margin: 5.0,
+            bleed: 3.0,
+            crop_marks: true,
+            ..SheetLayout::default()
+        };
+        assert_eq!(sheet.width(), 45.0 + 2.0 * 18.0);
+        assert_eq!(sheet.offset(0, 0), Vector::new(28.0, 28.0));
+        let (top_left, bottom_right) = sheet.trim();
+        assert_eq!(top_left, Point::new(13.0, 13.0));
+        assert_eq!(bottom_right, Point::new(68.0, 68.0));
+
+        let marks = sheet.crop_marks();
+        assert_eq!(marks.len(), 8);
+        assert_eq!(
+            marks[0],
+            Line::new(Point::new(10.0, 13.0), Point::new(0.0, 13.0))
+        );
+        assert!(SheetLayout::default().crop_marks().is_empty());
+
+        let titled = SheetLayout {
+            header: 40.0,
+            ..sheet
+        };
+        assert_eq!(titled.offset(0, 0), Vector::new(28.0, 68.0));
+        assert_eq!(titled.trim().1, Point::new(68.0, 108.0));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn partial_config_keeps_defaults() {
+        let config = GenerationConfig::from_toml(
+            r#"
+            [alphabet]
+            symmetry = "horizontal"
+            lattice = { polar = { rings = 3, spokes = 8 } }
+
+            [sheet]
+            rows = 2
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.alphabet.symmetry, Symmetry::Horizontal);
+        assert_eq!(
+            config.alphabet.lattice,
+            Lattice::Polar {
+                rings: 3,
+                spokes: 8
+            }
+        );
+        assert_eq!(config.alphabet.density, 3);
+        assert_eq!(config.sheet.rows, 2);
+        assert_eq!(config.sheet.columns, 26);
+
+        assert!(GenerationConfig::from_toml("[sheet]\nrow = 2").is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn profile_overrides_file() {
+        let toml = r#"
+            [alphabet]
+            symmetry = "horizontal"
+            density = 2
+
+            [sheet]
+            rows = 2
+
+            [profiles.poster.alphabet]
+            density = 5
+
+            [profiles.poster.sheet]
+            scale = 100.0
+            "#;
+        let base = GenerationConfig::from_toml(toml).unwrap();
+        assert_eq!(base.alphabet.density, 2);
+        assert_eq!(base.sheet.scale, 25.0);
+
+        let poster = GenerationConfig::from_toml_profile(toml, Some("poster")).unwrap();
+        assert_eq!(poster.alphabet.symmetry, Symmetry::Horizontal);
+        assert_eq!(poster.alphabet.density, 5);
+        assert_eq!(poster.sheet.rows, 2);
+        assert_eq!(poster.sheet.scale, 100.0);
+
+        assert!(GenerationConfig::from_toml_profile(toml, Some("zine")).is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn config_round_trips_through_toml() {
+        let mut config = GenerationConfig::default();
+        config.alphabet.resolution = Some(4);
+        config.alphabet.symmetry =
+            Symmetry::Composite(vec![Symmetry::Horizontal, Symmetry::Rotational(3)]);
+        config.alphabet.lattice = Lattice::Polar {
+            rings: 3,
+            spokes: 8,
+        };
+        let toml = config.to_toml().unwrap();
+        assert_eq!(GenerationConfig::from_toml(&toml).unwrap(), config);
+        let defaults = GenerationConfig::default().to_toml().unwrap();
+        assert_eq!(
+            GenerationConfig::from_toml(&defaults).unwrap(),
+            GenerationConfig::default()
+        );
+    }
+}
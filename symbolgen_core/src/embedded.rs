@@ -0,0 +1,88 @@
+//! Glyphs drawn with embedded-graphics, for small displays driven by microcontrollers.
+
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::{DrawTarget, Drawable, Point as PixelPoint, Primitive};
+use embedded_graphics::primitives::{Polyline, PrimitiveStyle};
+
+use crate::Glyph;
+
+/// A glyph scaled to fill a square of pixels, drawn in a stroke style.
+#[derive(Clone, Copy, Debug)]
+pub struct GlyphDrawable<'a, C: PixelColor> {
+    pub glyph: &'a Glyph,
+    /// Pixel at the top left of the square
+    pub top_left: PixelPoint,
+    /// Width and height of the square in pixels
+    pub size: u32,
+    pub style: PrimitiveStyle<C>,
+}
+
+impl Glyph {
+    /// The glyph ready to draw on a display, filling a square of pixels.
+    pub fn drawable<C: PixelColor>(
+        &self,
+        top_left: PixelPoint,
+        size: u32,
+        style: PrimitiveStyle<C>,
+    ) -> GlyphDrawable<'_, C> {
+        GlyphDrawable {
+            glyph: self,
+            top_left,
+            size,
+            style,
+        }
+    }
+}
+
+impl<C: PixelColor> Drawable for GlyphDrawable<'_, C> {
+    type Color = C;
+    type Output = ();
+
+    /// Draw each run of joined segments as a polyline, with points rounded to the nearest
+    /// pixel. The last row and column of pixels fall on the far edges of the glyph.
+    fn draw<D: DrawTarget<Color = C>>(&self, target: &mut D) -> Result<(), D::Error> {
+        let scale = f64::from(self.size.saturating_sub(1));
+        for polyline in self.glyph.to_polylines() {
+            let pixels: Vec<PixelPoint> = polyline
+                .iter()
+                .map(|point| {
+                    self.top_left
+                        + PixelPoint::new(
+                            (point.x * scale).round() as i32,
+                            (point.y * scale).round() as i32,
+                        )
+                })
+                .collect();
+            Polyline::new(&pixels)
+                .into_styled(self.style)
+                .draw(target)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Line, Point};
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    #[test]
+    fn lines_drawn_across_square() {
+        let glyph = Glyph::new(
+            0,
+            vec![Line::new(Point::new(0.0, 0.5), Point::new(1.0, 0.5)).into()],
+        );
+        let mut display = MockDisplay::new();
+        glyph
+            .drawable(
+                PixelPoint::new(1, 1),
+                5,
+                PrimitiveStyle::with_stroke(BinaryColor::On, 1),
+            )
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(&["      ", "      ", "      ", " #####"]);
+    }
+}
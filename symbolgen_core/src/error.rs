@@ -0,0 +1,41 @@
+//! Errors from configuring alphabets and generating glyphs.
+
+use thiserror::Error;
+
+#[non_exhaustive]
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Resolution must be at least 2 in each direction, not {0}x{1}")]
+    Resolution(i32, i32),
+    #[error("Density must be at least 1, not {0}")]
+    Density(i32),
+    #[error("Maximum line length must be at least 1 step, not {0}")]
+    MaxLength(i32),
+    #[error("Minimum lines {min} is more than maximum lines {max}")]
+    LineLimits { min: usize, max: usize },
+    #[error("No glyph within the line limits after {0} attempts")]
+    Unsatisfiable(u64),
+    #[error("Could not parse {kind} '{value}'")]
+    Parse { kind: &'static str, value: String },
+    #[error("Invalid config: {0}")]
+    Config(String),
+    #[error("Invalid saved alphabet: {0}")]
+    Binary(String),
+    #[error("Invalid manifest: {0}")]
+    Manifest(String),
+    #[error("Couldn't tessellate glyph: {0}")]
+    Tessellation(String),
+    #[error("Couldn't make font: {0}")]
+    Font(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn parse(kind: &'static str, value: &str) -> Self {
+        Error::Parse {
+            kind,
+            value: value.to_owned(),
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! Conditions glyphs must meet, to leave out those that don't.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, Glyph, GlyphStats, Result};
+
+/// How close to the edge of the cell a glyph must reach to span it.
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+/// A measurement of a glyph, as a number.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    /// Number of segments
+    Lines,
+    /// Total length of the segments
+    Length,
+    /// Fraction of the cell the segments pass through
+    Coverage,
+    /// How nearly the glyph matches itself reflected or turned, from 0 to 1
+    Symmetry,
+    Width,
+    Height,
+}
+
+impl Metric {
+    /// Every metric, in the order they are usually listed.
+    pub const ALL: [Metric; 6] = [
+        Metric::Lines,
+        Metric::Length,
+        Metric::Coverage,
+        Metric::Symmetry,
+        Metric::Width,
+        Metric::Height,
+    ];
+
+    /// The value of the metric for a glyph with these stats.
+    pub fn measure(&self, stats: &GlyphStats) -> f64 {
+        match self {
+            Metric::Lines => stats.segments as f64,
+            Metric::Length => stats.length,
+            Metric::Coverage => stats.coverage,
+            Metric::Symmetry => stats.symmetry,
+            Metric::Width => stats.extent().0,
+            Metric::Height => stats.extent().1,
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Metric::Lines => "lines",
+            Metric::Length => "length",
+            Metric::Coverage => "coverage",
+            Metric::Symmetry => "symmetry",
+            Metric::Width => "width",
+            Metric::Height => "height",
+        })
+    }
+}
+
+impl FromStr for Metric {
+    type Err = Error;
+    fn from_str(metric: &str) -> Result<Self> {
+        Metric::ALL
+            .iter()
+            .copied()
+            .find(|known| known.to_string() == metric)
+            .ok_or_else(|| Error::parse("metric", metric))
+    }
+}
+
+/// How a metric is compared with a value.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Operators, longest first so they can be matched in turn.
+    const OPERATORS: [(&'static str, Comparison); 5] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ];
+
+    pub fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Less => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::Equal => value == threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+            Comparison::Greater => value > threshold,
+        }
+    }
+
+    fn operator(&self) -> &'static str {
+        Self::OPERATORS
+            .iter()
+            .find(|(_, comparison)| comparison == self)
+            .map_or("=", |(operator, _)| operator)
+    }
+}
+
+/// Something true or false of a glyph.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// A metric compared with a value, like `lines>=6`
+    Compare(Metric, Comparison, f64),
+    /// Every segment is joined to the others at their ends, as `connected`
+    Connected,
+    /// The segments reach every edge of the cell, as `spans-grid`
+    SpansGrid,
+}
+
+impl Condition {
+    /// Whether the glyph, with the given stats, meets the condition.
+    fn holds(&self, glyph: &Glyph, stats: &GlyphStats) -> bool {
+        match self {
+            Condition::Compare(metric, comparison, value) => {
+                comparison.holds(metric.measure(stats), *value)
+            }
+            Condition::Connected => glyph.components().len() == 1,
+            Condition::SpansGrid => stats.bounds.is_some_and(|(min, max)| {
+                min.x <= EDGE_TOLERANCE
+                    && min.y <= EDGE_TOLERANCE
+                    && max.x >= 1.0 - EDGE_TOLERANCE
+                    && max.y >= 1.0 - EDGE_TOLERANCE
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Condition::Compare(metric, comparison, value) => {
+                write!(f, "{}{}{}", metric, comparison.operator(), value)
+            }
+            Condition::Connected => f.write_str("connected"),
+            Condition::SpansGrid => f.write_str("spans-grid"),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = Error;
+    fn from_str(condition: &str) -> Result<Self> {
+        let invalid = || Error::parse("condition", condition);
+        match condition {
+            "connected" => return Ok(Condition::Connected),
+            "spans-grid" => return Ok(Condition::SpansGrid),
+            _ => {}
+        }
+        let start = condition.find(['<', '>', '=']).ok_or_else(invalid)?;
+        let (metric, rest) = condition.split_at(start);
+        let (operator, comparison) = Comparison::OPERATORS
+            .iter()
+            .find(|(operator, _)| rest.starts_with(operator))
+            .ok_or_else(invalid)?;
+        let value = rest[operator.len()..]
+            .trim()
+            .parse()
+            .map_err(|_| invalid())?;
+        Ok(Condition::Compare(
+            metric.trim().parse()?,
+            *comparison,
+            value,
+        ))
+    }
+}
+
+/// Conditions a glyph must all meet to be kept.
+///
+/// Written as conditions separated by commas, like `lines>=6,connected,spans-grid`. Metrics
+/// are measured as by `Glyph::stats`, so are best checked on glyphs as generated, in a unit
+/// cell.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct GlyphFilter {
+    pub conditions: Vec<Condition>,
+}
+
+impl GlyphFilter {
+    pub fn new(conditions: Vec<Condition>) -> Self {
+        Self { conditions }
+    }
+
+    /// Whether the glyph meets every condition.
+    pub fn matches(&self, glyph: &Glyph) -> bool {
+        let stats = glyph.stats();
+        self.conditions
+            .iter()
+            .all(|condition| condition.holds(glyph, &stats))
+    }
+}
+
+impl fmt::Display for GlyphFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let conditions: Vec<String> = self.conditions.iter().map(ToString::to_string).collect();
+        f.write_str(&conditions.join(","))
+    }
+}
+
+impl FromStr for GlyphFilter {
+    type Err = Error;
+    fn from_str(filter: &str) -> Result<Self> {
+        filter
+            .split(',')
+            .map(str::trim)
+            .filter(|condition| !condition.is_empty())
+            .map(str::parse)
+            .collect::<Result<_>>()
+            .map(GlyphFilter::new)
+    }
+}
+
+impl TryFrom<String> for GlyphFilter {
+    type Error = Error;
+    fn try_from(filter: String) -> Result<Self> {
+        filter.parse()
+    }
+}
+
+impl From<GlyphFilter> for String {
+    fn from(filter: GlyphFilter) -> Self {
+        filter.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Line, Point};
+
+    #[test]
+    fn parse_filters() {
+        let filter: GlyphFilter = "lines>=6, connected,length<2.5".parse().unwrap();
+        assert_eq!(
+            filter.conditions,
+            [
+                Condition::Compare(Metric::Lines, Comparison::GreaterOrEqual, 6.0),
+                Condition::Connected,
+                Condition::Compare(Metric::Length, Comparison::Less, 2.5),
+            ]
+        );
+        assert_eq!(filter.to_string(), "lines>=6,connected,length<2.5");
+        assert!("lines=>6".parse::<GlyphFilter>().is_err());
+        assert!("colour>1".parse::<GlyphFilter>().is_err());
+        assert!("pretty".parse::<GlyphFilter>().is_err());
+    }
+
+    #[test]
+    fn glyphs_must_meet_every_condition() {
+        let line = |start: (f64, f64), end: (f64, f64)| {
+            Line::new(Point::new(start.0, start.1), Point::new(end.0, end.1)).into()
+        };
+        let corner = Glyph::new(
+            0,
+            vec![line((0.0, 0.0), (1.0, 0.0)), line((1.0, 0.0), (1.0, 1.0))],
+        );
+        let apart = Glyph::new(
+            0,
+            vec![line((0.0, 0.0), (0.5, 0.0)), line((0.0, 1.0), (1.0, 1.0))],
+        );
+
+        let filter: GlyphFilter = "connected,spans-grid".parse().unwrap();
+        assert!(filter.matches(&corner));
+        assert!(!filter.matches(&apart));
+        assert!("lines=2".parse::<GlyphFilter>().unwrap().matches(&apart));
+        assert!(!"lines>2".parse::<GlyphFilter>().unwrap().matches(&corner));
+        assert!(GlyphFilter::default().matches(&Glyph::new(0, Vec::new())));
+    }
+}
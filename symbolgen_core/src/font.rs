@@ -0,0 +1,148 @@
+//! Glyphs made into fonts, with each stroke expanded to an outline, to type in an alphabet or
+//! refine in a font editor.
+
+use font_kurbo::{BezPath, Cap, Join, Stroke, StrokeOpts};
+
+use crate::{Glyph, MetricsOptions, Point, Segment};
+
+/// Greatest distance, in font units, outlines may stray from the strokes they are made from.
+pub(crate) const TOLERANCE: f64 = 0.5;
+
+/// How glyphs are made into a font.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct FontOptions {
+    /// Name the font is installed and picked under
+    pub family: String,
+    /// Width of strokes, as a fraction of a glyph's cell
+    pub weight: f64,
+    /// Font units along each side of a glyph's cell
+    pub units_per_em: u16,
+    /// Width of a space, as a fraction of a glyph's cell
+    pub word_spacing: f64,
+    /// How each glyph's width is measured, before its strokes are widened
+    pub metrics: MetricsOptions,
+}
+
+impl Default for FontOptions {
+    fn default() -> Self {
+        Self {
+            family: "Symbolgen".to_owned(),
+            weight: 0.08,
+            units_per_em: 1000,
+            word_spacing: 0.75,
+            metrics: MetricsOptions::default(),
+        }
+    }
+}
+
+/// A glyph as it goes in a font, in font units with y upwards.
+pub(crate) struct FontGlyph {
+    pub name: String,
+    /// Character typed to get the glyph, if any
+    pub character: Option<char>,
+    /// Seed of the glyph it was made from, if any
+    pub seed: Option<u64>,
+    /// Outline of the widened strokes, of lines and cubic curves
+    pub outline: BezPath,
+    pub advance: u16,
+}
+
+/// Heights of the top and bottom of a glyph's cell, with room for the strokes, from the
+/// baseline.
+pub(crate) fn vertical_metrics(options: &FontOptions) -> (i16, i16) {
+    let em = f64::from(options.units_per_em);
+    let half_weight = options.weight / 2.0;
+    (
+        ((options.metrics.baseline + half_weight) * em).round() as i16,
+        ((options.metrics.baseline - 1.0 - half_weight) * em).round() as i16,
+    )
+}
+
+/// Every glyph of a font: an empty `.notdef` and a space, then each of the glyphs with its
+/// strokes widened. The space isn't typed if a glyph is typed as one.
+pub(crate) fn font_glyphs(glyphs: &[(char, Glyph)], options: &FontOptions) -> Vec<FontGlyph> {
+    let em = f64::from(options.units_per_em);
+    let style = Stroke::new(options.weight * em)
+        .with_caps(Cap::Round)
+        .with_join(Join::Round);
+    let typed_space = glyphs.iter().any(|(character, _)| *character == ' ');
+    let mut font_glyphs = vec![
+        FontGlyph {
+            name: ".notdef".to_owned(),
+            character: None,
+            seed: None,
+            outline: BezPath::new(),
+            advance: options.units_per_em,
+        },
+        FontGlyph {
+            name: "space".to_owned(),
+            character: Some(' ').filter(|_| !typed_space),
+            seed: None,
+            outline: BezPath::new(),
+            advance: (options.word_spacing * em).round() as u16,
+        },
+    ];
+    font_glyphs.extend(glyphs.iter().map(|(character, glyph)| {
+        let metrics = glyph.metrics(&options.metrics);
+        let shift = (metrics.offset + options.weight / 2.0) * em;
+        let path = outline(glyph, |point| {
+            font_kurbo::Point::new(
+                shift + point.x * em,
+                (options.metrics.baseline - point.y) * em,
+            )
+        });
+        FontGlyph {
+            name: glyph_name(*character),
+            character: Some(*character),
+            seed: Some(glyph.seed()),
+            outline: font_kurbo::stroke(path, &style, &StrokeOpts::default(), TOLERANCE),
+            advance: ((metrics.advance + options.weight) * em).round() as u16,
+        }
+    }));
+    font_glyphs
+}
+
+/// The glyph as a path, with each point moved into place by `place`.
+fn outline(glyph: &Glyph, place: impl Fn(Point) -> font_kurbo::Point) -> BezPath {
+    let mut path = BezPath::new();
+    let mut position = None;
+    for segment in glyph.segments() {
+        if position != Some(segment.start()) {
+            path.move_to(place(segment.start()));
+        }
+        match segment {
+            Segment::Line(line) => path.line_to(place(line.end())),
+            Segment::Arc(arc) => {
+                for curve in arc.to_curves() {
+                    let [_, control_start, control_end, end] = curve.points();
+                    path.curve_to(place(control_start), place(control_end), place(end));
+                }
+            }
+            Segment::Curve(curve) => {
+                let [_, control_start, control_end, end] = curve.points();
+                path.curve_to(place(control_start), place(control_end), place(end));
+            }
+        }
+        position = Some(segment.end());
+    }
+    path
+}
+
+/// The glyph's name for a character, as font tools expect.
+fn glyph_name(character: char) -> String {
+    match character as u32 {
+        code @ 0..=0xFFFF => format!("uni{:04X}", code),
+        code => format!("u{:X}", code),
+    }
+}
+
+/// The PostScript name of the family's only style, of the characters such names allow.
+pub(crate) fn postscript_name(family: &str) -> String {
+    let family: String = family
+        .chars()
+        .filter(|character| character.is_ascii_alphanumeric() || *character == '-')
+        .collect();
+    format!("{}-Regular", family)
+}
@@ -0,0 +1,107 @@
+//! Glyphs as geographic lines, to place on maps as markers.
+
+use std::io::{self, Write};
+
+use geo_types::{Coord, LineString, MultiLineString};
+use geojson::{Feature, FeatureCollection, Geometry, JsonObject};
+
+use crate::Glyph;
+
+/// A glyph placed on a map.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapMarker<'a> {
+    pub glyph: &'a Glyph,
+    /// Where the middle of the glyph goes, such as a longitude and latitude
+    pub centre: Coord<f64>,
+    /// Width and height of the glyph, in the same units as the centre
+    pub size: f64,
+}
+
+impl From<&Glyph> for MultiLineString<f64> {
+    /// Each run of joined segments as a line string, in the glyph's own coordinates.
+    fn from(glyph: &Glyph) -> Self {
+        glyph
+            .to_polylines()
+            .into_iter()
+            .map(|polyline| {
+                polyline
+                    .iter()
+                    .map(|point| Coord {
+                        x: point.x,
+                        y: point.y,
+                    })
+                    .collect::<LineString<f64>>()
+            })
+            .collect()
+    }
+}
+
+impl MapMarker<'_> {
+    /// The glyph's lines in place on the map, turned the right way up, as map coordinates
+    /// increase northwards.
+    pub fn to_multi_line_string(&self) -> MultiLineString<f64> {
+        let mut lines = MultiLineString::from(self.glyph);
+        for line in lines.0.iter_mut() {
+            for coord in line.0.iter_mut() {
+                *coord = Coord {
+                    x: self.centre.x + (coord.x - 0.5) * self.size,
+                    y: self.centre.y - (coord.y - 0.5) * self.size,
+                };
+            }
+        }
+        lines
+    }
+
+    /// A GeoJSON feature of the marker's lines, with the glyph's seed as a property.
+    pub fn to_feature(&self) -> Feature {
+        let mut properties = JsonObject::new();
+        properties.insert("seed".to_owned(), self.glyph.seed().into());
+        Feature {
+            geometry: Some(Geometry::new((&self.to_multi_line_string()).into())),
+            properties: Some(properties),
+            ..Feature::default()
+        }
+    }
+}
+
+/// A GeoJSON feature collection of markers.
+pub fn to_geojson(markers: &[MapMarker]) -> String {
+    FeatureCollection {
+        bbox: None,
+        features: markers.iter().map(MapMarker::to_feature).collect(),
+        foreign_members: None,
+    }
+    .to_string()
+}
+
+/// Write a GeoJSON feature collection of markers, as for `to_geojson`.
+pub fn write_geojson<W: Write>(mut writer: W, markers: &[MapMarker]) -> io::Result<()> {
+    writer.write_all(to_geojson(markers).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Line, Point};
+
+    #[test]
+    fn markers_placed_upright() {
+        let glyph = Glyph::new(
+            7,
+            vec![Line::new(Point::new(0.5, 0.0), Point::new(0.5, 1.0)).into()],
+        );
+        let marker = MapMarker {
+            glyph: &glyph,
+            centre: Coord { x: -0.1, y: 51.5 },
+            size: 0.2,
+        };
+        let lines = marker.to_multi_line_string();
+        assert_eq!(lines.0.len(), 1);
+        let ends: Vec<(f64, f64)> = lines.0[0].points().map(|point| point.x_y()).collect();
+        assert!(ends.contains(&(-0.1, 51.6)) && ends.contains(&(-0.1, 51.4)));
+
+        let geojson = to_geojson(&[marker]);
+        assert!(geojson.contains(r#""type":"FeatureCollection""#));
+        assert!(geojson.contains(r#""seed":7"#));
+    }
+}
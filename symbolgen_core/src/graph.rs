@@ -0,0 +1,398 @@
+//! Connectivity of the segments in a glyph.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::segment::FixedPoint;
+use crate::{Line, Point, Segment};
+
+/// Largest sine of the angle between lines that still count as straight on.
+const COLLINEAR_TOLERANCE: f64 = 1e-5;
+
+/// Segments joined by their end points into a graph.
+struct Graph {
+    /// The end points of each segment, as node indices.
+    ends: Vec<(usize, usize)>,
+    /// The position of each end point.
+    points: Vec<FixedPoint>,
+    /// The number of distinct end points.
+    nodes: usize,
+}
+
+impl Graph {
+    fn new(segments: &[Segment]) -> Self {
+        let mut indices: HashMap<FixedPoint, usize> = HashMap::new();
+        let mut points = Vec::new();
+        let mut index = |point: FixedPoint| {
+            let next = indices.len();
+            *indices.entry(point).or_insert_with(|| {
+                points.push(point);
+                next
+            })
+        };
+        let ends = segments
+            .iter()
+            .map(|segment| {
+                let (start, end) = segment.fixed_ends();
+                (index(start), index(end))
+            })
+            .collect();
+        Self {
+            ends,
+            nodes: points.len(),
+            points,
+        }
+    }
+
+    fn position(&self, node: usize) -> Point {
+        self.points[node].into()
+    }
+}
+
+/// Find the root of a node in a union-find forest, flattening the path to it.
+fn find(parents: &mut [usize], node: usize) -> usize {
+    let mut root = node;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut node = node;
+    while parents[node] != root {
+        let parent = parents[node];
+        parents[node] = root;
+        node = parent;
+    }
+    root
+}
+
+/// Group segments that are connected through shared end points.
+///
+/// Groups are ordered largest first, then by their first segment.
+pub(crate) fn components(segments: &[Segment]) -> Vec<Vec<Segment>> {
+    let graph = Graph::new(segments);
+    let mut parents: Vec<usize> = (0..graph.nodes).collect();
+    for (start, end) in graph.ends.iter() {
+        let (start, end) = (find(&mut parents, *start), find(&mut parents, *end));
+        parents[start] = end;
+    }
+
+    let mut groups: Vec<Vec<Segment>> = Vec::new();
+    let mut group_of_root = HashMap::new();
+    for (segment, (start, _)) in segments.iter().zip(graph.ends.iter()) {
+        let root = find(&mut parents, *start);
+        let group = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(segment.clone());
+    }
+    groups.sort_by_key(|group| Reverse(group.len()));
+    groups
+}
+
+/// One step along a path through a glyph.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Stroke {
+    /// Draw a segment, from its end to its start if reversed.
+    Draw { segment: Segment, reversed: bool },
+    /// Move to the start of the next segment without drawing.
+    Travel(Line),
+}
+
+impl Stroke {
+    pub fn start(&self) -> Point {
+        match self {
+            Stroke::Draw { segment, reversed } if *reversed => segment.end(),
+            Stroke::Draw { segment, .. } => segment.start(),
+            Stroke::Travel(line) => line.start(),
+        }
+    }
+
+    pub fn end(&self) -> Point {
+        match self {
+            Stroke::Draw { segment, reversed } if *reversed => segment.start(),
+            Stroke::Draw { segment, .. } => segment.end(),
+            Stroke::Travel(line) => line.end(),
+        }
+    }
+}
+
+/// Order the segments into a single path, drawing each once.
+///
+/// Each connected group is drawn in as few continuous runs as possible, joined by travel
+/// moves between the nearest loose ends.
+pub(crate) fn single_stroke(segments: &[Segment]) -> Vec<Stroke> {
+    let mut strokes: Vec<Stroke> = Vec::new();
+    for component in components(segments) {
+        for stroke in eulerian_path(&component) {
+            if let Some(previous) = strokes.last() {
+                if previous.end() != stroke.start() {
+                    strokes.push(Stroke::Travel(Line::new(previous.end(), stroke.start())));
+                }
+            }
+            strokes.push(stroke);
+        }
+    }
+    strokes
+}
+
+/// A path through a connected group of segments, adding travel moves to pair up
+/// all but two of the nodes where an odd number of segments meet.
+fn eulerian_path(segments: &[Segment]) -> Vec<Stroke> {
+    let graph = Graph::new(segments);
+    let mut ends = graph.ends.clone();
+
+    // Pair each odd node with the nearest unpaired one
+    let mut degrees = vec![0; graph.nodes];
+    for (start, end) in ends.iter() {
+        degrees[*start] += 1;
+        degrees[*end] += 1;
+    }
+    let mut odd: Vec<usize> = (0..graph.nodes)
+        .filter(|node| degrees[*node] % 2 == 1)
+        .collect();
+    let mut pairs = Vec::new();
+    while let Some(node) = odd.pop() {
+        let nearest = (0..odd.len()).min_by(|a, b| {
+            let distance =
+                |other: usize| (graph.position(node) - graph.position(odd[other])).norm();
+            distance(*a)
+                .partial_cmp(&distance(*b))
+                .expect("distances are finite")
+        });
+        if let Some(nearest) = nearest {
+            pairs.push((node, odd.remove(nearest)));
+        }
+    }
+    // The path may start and end on the furthest apart pair
+    pairs.sort_by(|a, b| {
+        let distance =
+            |(from, to): (usize, usize)| (graph.position(from) - graph.position(to)).norm();
+        distance(*a)
+            .partial_cmp(&distance(*b))
+            .expect("distances are finite")
+    });
+    let start = pairs
+        .pop()
+        .map(|(start, _)| start)
+        .or_else(|| ends.first().map(|(start, _)| *start));
+    let start = match start {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+    ends.extend(pairs);
+
+    // Hierholzer's algorithm, following unused edges until stuck then backtracking
+    let mut adjacent = vec![Vec::new(); graph.nodes];
+    for (edge, (from, to)) in ends.iter().enumerate() {
+        adjacent[*from].push((edge, *to));
+        adjacent[*to].push((edge, *from));
+    }
+    let mut used = vec![false; ends.len()];
+    let mut stack: Vec<(usize, Option<usize>)> = vec![(start, None)];
+    let mut path = Vec::new();
+    while let Some((node, arrived_by)) = stack.last().copied() {
+        match adjacent[node].pop() {
+            Some((edge, next)) if !used[edge] => {
+                used[edge] = true;
+                stack.push((next, Some(edge)));
+            }
+            Some(_) => {}
+            None => {
+                stack.pop();
+                if let Some(edge) = arrived_by {
+                    path.push((edge, node));
+                }
+            }
+        }
+    }
+    path.reverse();
+
+    path.into_iter()
+        .map(|(edge, arrival)| match segments.get(edge) {
+            Some(segment) => Stroke::Draw {
+                segment: segment.clone(),
+                reversed: ends[edge].0 == arrival && ends[edge].1 != arrival,
+            },
+            None => {
+                let (from, to) = ends[edge];
+                let (from, to) = if to == arrival {
+                    (from, to)
+                } else {
+                    (to, from)
+                };
+                Stroke::Travel(Line::new(graph.position(from), graph.position(to)))
+            }
+        })
+        .collect()
+}
+
+/// Join the segments into as few lines through consecutive points as possible.
+///
+/// Arcs and curves are flattened into `samples` points each.
+pub(crate) fn polylines(segments: &[Segment], samples: usize) -> Vec<Vec<Point>> {
+    let mut polylines: Vec<Vec<Point>> = Vec::new();
+    let mut continuing = false;
+    for stroke in single_stroke(segments) {
+        let (segment, reversed) = match stroke {
+            Stroke::Draw { segment, reversed } => (segment, reversed),
+            Stroke::Travel(_) => {
+                continuing = false;
+                continue;
+            }
+        };
+        let mut points = match segment {
+            Segment::Line(line) => vec![line.start(), line.end()],
+            segment => segment.sample(samples),
+        };
+        if reversed {
+            points.reverse();
+        }
+        match polylines.last_mut() {
+            Some(polyline) if continuing => polyline.extend(points.into_iter().skip(1)),
+            _ => polylines.push(points),
+        }
+        continuing = true;
+    }
+    polylines
+}
+
+/// Merge lines that continue straight on from one another into single longer lines.
+///
+/// Other segments are left as they are.
+pub(crate) fn simplify(segments: &[Segment]) -> Vec<Segment> {
+    let mut segments = segments.to_vec();
+    'merging: loop {
+        for first in 0..segments.len() {
+            for second in first + 1..segments.len() {
+                if let (Segment::Line(a), Segment::Line(b)) = (&segments[first], &segments[second])
+                {
+                    if let Some(joined) = join(a, b) {
+                        segments[first] = joined.into();
+                        segments.remove(second);
+                        continue 'merging;
+                    }
+                }
+            }
+        }
+        return segments;
+    }
+}
+
+/// The line made by two lines meeting end to end in a straight line, in the direction of
+/// the first.
+fn join(a: &Line, b: &Line) -> Option<Line> {
+    let fixed = |point: Point| FixedPoint::from(point);
+    for (shared, a_other, a_ends_there) in
+        [(a.end(), a.start(), true), (a.start(), a.end(), false)].iter()
+    {
+        for (b_shared, b_other) in [(b.start(), b.end()), (b.end(), b.start())].iter() {
+            if fixed(*shared) != fixed(*b_shared) {
+                continue;
+            }
+            let (towards_a, towards_b) = (a_other - shared, b_other - shared);
+            let cross = towards_a.x * towards_b.y - towards_a.y * towards_b.x;
+            // Allow for rounding on lattices without exact coordinates
+            let straight = cross.abs() <= COLLINEAR_TOLERANCE * towards_a.norm() * towards_b.norm();
+            if straight && towards_a.dot(&towards_b) < 0.0 {
+                return Some(if *a_ends_there {
+                    Line::new(*a_other, *b_other)
+                } else {
+                    Line::new(*b_other, *a_other)
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(from: (f64, f64), to: (f64, f64)) -> Segment {
+        Line::new(Point::new(from.0, from.1), Point::new(to.0, to.1)).into()
+    }
+
+    /// The segments drawn, and the number of travel moves, checking each stroke starts where
+    /// the last ended.
+    fn follow(strokes: &[Stroke]) -> (Vec<Segment>, usize) {
+        for pair in strokes.windows(2) {
+            assert_eq!(pair[0].end(), pair[1].start());
+        }
+        let drawn = strokes
+            .iter()
+            .filter_map(|stroke| match stroke {
+                Stroke::Draw { segment, .. } => Some(segment.clone()),
+                Stroke::Travel(_) => None,
+            })
+            .collect();
+        let travels = strokes
+            .iter()
+            .filter(|stroke| matches!(stroke, Stroke::Travel(_)))
+            .count();
+        (drawn, travels)
+    }
+
+    #[test]
+    fn paths_draw_each_segment_once() {
+        // A cross, whose four loose ends need one travel move between two of them
+        let cross = vec![
+            line((0.5, 0.5), (0.0, 0.5)),
+            line((0.5, 0.5), (1.0, 0.5)),
+            line((0.5, 0.5), (0.5, 0.0)),
+            line((0.5, 0.5), (0.5, 1.0)),
+        ];
+        let (mut drawn, travels) = follow(&eulerian_path(&cross));
+        assert_eq!(travels, 1);
+        drawn.sort_by_key(Segment::key);
+        let mut expected = cross.clone();
+        expected.sort_by_key(Segment::key);
+        assert_eq!(drawn, expected);
+    }
+
+    #[test]
+    fn even_glyphs_are_one_run() {
+        let square = vec![
+            line((0.0, 0.0), (1.0, 0.0)),
+            line((1.0, 0.0), (1.0, 1.0)),
+            line((0.0, 1.0), (1.0, 1.0)),
+            line((0.0, 0.0), (0.0, 1.0)),
+        ];
+        let strokes = eulerian_path(&square);
+        let (drawn, travels) = follow(&strokes);
+        assert_eq!((drawn.len(), travels), (4, 0));
+        assert_eq!(strokes[0].start(), strokes[3].end());
+    }
+
+    #[test]
+    fn segments_entered_from_their_end_are_reversed() {
+        // Both lines end at the same point, so one is drawn backwards
+        let strokes = eulerian_path(&[line((0.0, 0.0), (1.0, 0.0)), line((0.0, 1.0), (1.0, 0.0))]);
+        follow(&strokes);
+        let reversed: Vec<&Stroke> = strokes
+            .iter()
+            .filter(|stroke| matches!(stroke, Stroke::Draw { reversed: true, .. }))
+            .collect();
+        assert_eq!(reversed.len(), 1);
+        match reversed[0] {
+            Stroke::Draw { segment, .. } => {
+                assert_eq!(reversed[0].start(), segment.end());
+                assert_eq!(reversed[0].end(), segment.start());
+            }
+            Stroke::Travel(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn components_are_joined_by_one_travel() {
+        let strokes = single_stroke(&[
+            line((0.0, 0.0), (0.0, 1.0)),
+            line((0.0, 1.0), (0.25, 1.0)),
+            line((1.0, 0.0), (1.0, 1.0)),
+        ]);
+        let (drawn, travels) = follow(&strokes);
+        assert_eq!((drawn.len(), travels), (3, 1));
+    }
+}
@@ -0,0 +1,723 @@
+//! The lattice glyphs are generated on.
+
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{Error, Line, Point, Result, Vector};
+
+/// Behaviour when a line would leave the unit cell.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Edge {
+    /// Clamp end points to the cell boundary.
+    Clamp,
+    /// Wrap around to the opposite edge, as on a torus.
+    Wrap,
+}
+
+impl fmt::Display for Edge {
+    /// The edge behaviour's name, which parses back to the same behaviour.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Edge::Clamp => f.write_str("clamp"),
+            Edge::Wrap => f.write_str("wrap"),
+        }
+    }
+}
+
+impl FromStr for Edge {
+    type Err = Error;
+    fn from_str(edge: &str) -> Result<Self> {
+        match edge {
+            "clamp" => Ok(Edge::Clamp),
+            "wrap" => Ok(Edge::Wrap),
+            _ => Err(Error::parse("edge", edge)),
+        }
+    }
+}
+
+/// The arrangement of points that lines are drawn between.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Lattice {
+    /// A square grid, with points along each edge of the cell.
+    Square,
+    /// Vertices and centres of a honeycomb of hexagons, with a centre in the middle of the cell.
+    ///
+    /// Orthogonal lines follow the edges of the hexagons, while diagonal lines may also join
+    /// a vertex to a centre.
+    Hexagonal,
+    /// Vertices of a tiling of equilateral triangles, in vertical columns with a vertex in the
+    /// middle of the cell.
+    ///
+    /// Orthogonal lines follow the edges of the triangles, vertically and at 30° either side
+    /// of horizontal, giving an isometric look. Diagonal lines may also join vertices two
+    /// triangles apart.
+    Triangular,
+    /// Intersections of concentric rings and the spokes between them, inside a circle
+    /// touching the edges of the cell.
+    ///
+    /// Orthogonal lines run around a ring or along a spoke, while diagonal lines may cut
+    /// across both.
+    Polar { rings: i32, spokes: i32 },
+}
+
+impl fmt::Display for Lattice {
+    /// The lattice's name, which parses back to the same lattice.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lattice::Square => f.write_str("square"),
+            Lattice::Hexagonal => f.write_str("hexagonal"),
+            Lattice::Triangular => f.write_str("triangular"),
+            Lattice::Polar { rings, spokes } => write!(f, "polar{}x{}", rings, spokes),
+        }
+    }
+}
+
+impl FromStr for Lattice {
+    type Err = Error;
+    fn from_str(lattice: &str) -> Result<Self> {
+        match lattice {
+            "square" => Ok(Lattice::Square),
+            "hexagonal" => Ok(Lattice::Hexagonal),
+            "triangular" => Ok(Lattice::Triangular),
+            // Rings and spokes, as in `polar4x12`
+            _ => lattice
+                .strip_prefix("polar")
+                .and_then(|counts| {
+                    let mut counts = counts.splitn(2, 'x').map(str::parse);
+                    match (counts.next(), counts.next()) {
+                        (Some(Ok(rings)), Some(Ok(spokes))) if rings > 0 && spokes > 0 => {
+                            Some(Lattice::Polar { rings, spokes })
+                        }
+                        _ => None,
+                    }
+                })
+                .ok_or_else(|| Error::parse("lattice", lattice)),
+        }
+    }
+}
+
+/// Index offsets to the six neighbours of a point on a hexagonal or triangular lattice.
+const HEXAGONAL_NEIGHBOURS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// Index offsets to the six next nearest neighbours of a point on a triangular lattice.
+const TRIANGULAR_SECOND_NEIGHBOURS: [(i32, i32); 6] =
+    [(1, 1), (-1, 2), (-2, 1), (-1, -1), (1, -2), (2, -1)];
+
+/// Regions of the cell that lines may not start or end in.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Mask {
+    /// Every point may be used.
+    None,
+    /// Only points within this distance of the centre of the cell may be used.
+    Circle(f64),
+    /// Only points between an inner and outer distance from the centre of the cell may be used.
+    Ring { inner: f64, outer: f64 },
+    /// Whether each point may be used, indexed by column then row.
+    ///
+    /// Points beyond the table may be used.
+    Points(Vec<Vec<bool>>),
+}
+
+/// Allowance for floating point error when comparing positions, in steps.
+const TOLERANCE: f64 = 1e-6;
+
+/// Distribution of the number of steps a line end is adjusted by.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Adjustment {
+    /// -1, 0 or 1 steps with equal probability.
+    Uniform,
+    /// Pairs of step count and relative weight.
+    Weighted(Vec<(i32, f64)>),
+}
+
+impl Adjustment {
+    /// Generate a number of steps from this distribution.
+    fn gen_steps<R: Rng>(&self, rng: &mut R) -> i32 {
+        match self {
+            Adjustment::Uniform => rng.gen_range(-1, 2),
+            Adjustment::Weighted(weights) => gen_weighted(weights, rng),
+        }
+    }
+}
+
+/// Distribution of the number of steps a line spans.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Span {
+    /// Every line spans a single step.
+    Unit,
+    /// Every length from one step up to the maximum is equally likely.
+    Uniform,
+    /// Pairs of span length and relative weight.
+    Weighted(Vec<(i32, f64)>),
+}
+
+impl Span {
+    /// Generate a span length from this distribution, no longer than `max_length`.
+    fn gen_length<R: Rng>(&self, max_length: i32, rng: &mut R) -> i32 {
+        let length = match self {
+            Span::Unit => 1,
+            Span::Uniform => rng.gen_range(1, max_length.max(1) + 1),
+            Span::Weighted(weights) => gen_weighted(weights, rng),
+        };
+        length.min(max_length)
+    }
+}
+
+/// Distribution of the points lines start from.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Start {
+    /// Every point is equally likely.
+    Uniform,
+    /// Points near the centre of the cell are more likely, falling off as a Gaussian with
+    /// this standard deviation.
+    Centre(f64),
+    /// Points near the edges of the cell are more likely, falling off as a Gaussian with
+    /// this standard deviation.
+    Edge(f64),
+}
+
+impl Start {
+    /// Relative likelihood of starting from a point.
+    fn weight(&self, point: Point) -> f64 {
+        let gaussian =
+            |distance: f64, deviation: f64| (-distance.powi(2) / (2.0 * deviation.powi(2))).exp();
+        match self {
+            Start::Uniform => 1.0,
+            Start::Centre(deviation) => gaussian((point - Point::new(0.5, 0.5)).norm(), *deviation),
+            Start::Edge(deviation) => {
+                let distance = point.x.min(1.0 - point.x).min(point.y).min(1.0 - point.y);
+                gaussian(distance.max(0.0), *deviation)
+            }
+        }
+    }
+}
+
+/// Pick a value from pairs of value and relative weight.
+///
+/// Negative weights are treated as zero. If there is no weight at all, zero is returned.
+fn gen_weighted<R: Rng>(weights: &[(i32, f64)], rng: &mut R) -> i32 {
+    let total: f64 = weights.iter().map(|(_, weight)| weight.max(0.0)).sum();
+    if total <= 0.0 {
+        return 0;
+    }
+    let mut target = rng.gen_range(0.0, total);
+    for (value, weight) in weights.iter() {
+        target -= weight.max(0.0);
+        if target < 0.0 {
+            return *value;
+        }
+    }
+    // Only reachable through floating point error
+    weights.last().map(|(value, _)| *value).unwrap_or(0)
+}
+
+/// A lattice over the unit cell, and the distributions of moves across it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grid {
+    /// The arrangement of points
+    pub lattice: Lattice,
+    /// The number of points along the horizontal axis.
+    ///
+    /// Lattices other than square use this in both directions.
+    pub resolution_x: i32,
+    /// The number of points along the vertical axis.
+    pub resolution_y: i32,
+    /// Horizontal distance between points, 1 / (resolution_x - 1)
+    pub step_x: f64,
+    /// Vertical distance between points, 1 / (resolution_y - 1)
+    pub step_y: f64,
+    /// How to handle lines leaving the cell
+    pub edge: Edge,
+    /// Distribution of horizontal adjustments
+    pub adjustment_x: Adjustment,
+    /// Distribution of vertical adjustments
+    pub adjustment_y: Adjustment,
+    /// Distribution of line lengths, in steps
+    pub span: Span,
+    /// The longest line allowed, in steps
+    pub max_length: i32,
+    /// Regions lines may not start or end in
+    pub mask: Mask,
+    /// Distribution of start points
+    pub start: Start,
+}
+
+impl Grid {
+    pub fn new(resolution: i32) -> Result<Self> {
+        Self::rectangular(resolution, resolution)
+    }
+
+    /// A grid with a different number of points along each axis.
+    ///
+    /// The cell is still the unit square, so should be drawn with the aspect ratio given by
+    /// `aspect_ratio` for the steps to be the same size.
+    pub fn rectangular(resolution_x: i32, resolution_y: i32) -> Result<Self> {
+        if resolution_x < 2 || resolution_y < 2 {
+            return Err(Error::Resolution(resolution_x, resolution_y));
+        }
+        Ok(Self {
+            lattice: Lattice::Square,
+            resolution_x,
+            resolution_y,
+            step_x: 1.0 / (resolution_x - 1) as f64,
+            step_y: 1.0 / (resolution_y - 1) as f64,
+            edge: Edge::Clamp,
+            adjustment_x: Adjustment::Uniform,
+            adjustment_y: Adjustment::Uniform,
+            span: Span::Unit,
+            max_length: resolution_x.max(resolution_y) - 1,
+            mask: Mask::None,
+            start: Start::Uniform,
+        })
+    }
+
+    /// Width of the cell divided by its height, when drawn with square steps.
+    pub fn aspect_ratio(&self) -> f64 {
+        match self.lattice {
+            Lattice::Square => self.step_y / self.step_x,
+            _ => 1.0,
+        }
+    }
+
+    /// Generate a random x coordinate
+    pub fn gen_coordinate_x<R: Rng>(&self, rng: &mut R) -> f64 {
+        gen_coordinate(self.resolution_x, rng)
+    }
+
+    /// Generate a random y coordinate
+    pub fn gen_coordinate_y<R: Rng>(&self, rng: &mut R) -> f64 {
+        gen_coordinate(self.resolution_y, rng)
+    }
+
+    /// Generate a random grid point.
+    ///
+    /// Points follow the start distribution, and are chosen from those the mask allows
+    /// unless it allows none.
+    pub fn gen_point<R: Rng>(&self, rng: &mut R) -> Point {
+        if self.mask != Mask::None || self.start != Start::Uniform {
+            if let Some(point) = self.choose_start(self.nodes(), |point| point, rng) {
+                return point;
+            }
+        }
+        Point::new(self.gen_coordinate_x(rng), self.gen_coordinate_y(rng))
+    }
+
+    /// Choose a start point from the candidates, following the mask and start distribution.
+    fn choose_start<T: Copy, F: Fn(T) -> Point, R: Rng>(
+        &self,
+        candidates: Vec<T>,
+        position: F,
+        rng: &mut R,
+    ) -> Option<T> {
+        let allowed: Vec<T> = candidates
+            .into_iter()
+            .filter(|candidate| self.allows(position(*candidate)))
+            .collect();
+        match self.start {
+            Start::Uniform => allowed.choose(rng).copied(),
+            _ => allowed
+                .choose_weighted(rng, |candidate| self.start.weight(position(*candidate)))
+                .ok()
+                .copied(),
+        }
+    }
+
+    /// Whether the mask allows lines to start or end at this point.
+    pub fn allows(&self, point: Point) -> bool {
+        let tolerance = TOLERANCE * self.step_x.min(self.step_y);
+        let distance = (point - Point::new(0.5, 0.5)).norm();
+        match &self.mask {
+            Mask::None => true,
+            Mask::Circle(radius) => distance <= radius + tolerance,
+            Mask::Ring { inner, outer } => {
+                distance >= inner - tolerance && distance <= outer + tolerance
+            }
+            Mask::Points(table) => {
+                let (x, y) = self.index(point);
+                if x < 0 || y < 0 {
+                    return true;
+                }
+                table
+                    .get(x as usize)
+                    .and_then(|column| column.get(y as usize))
+                    .copied()
+                    .unwrap_or(true)
+            }
+        }
+    }
+
+    /// Generate a horizontal adjustment, in steps.
+    pub fn gen_adjustment_x<R: Rng>(&self, rng: &mut R) -> f64 {
+        self.adjustment_x.gen_steps(rng) as f64
+    }
+
+    /// Generate a vertical adjustment, in steps.
+    pub fn gen_adjustment_y<R: Rng>(&self, rng: &mut R) -> f64 {
+        self.adjustment_y.gen_steps(rng) as f64
+    }
+
+    /// Generate a line length, in steps.
+    pub fn gen_span<R: Rng>(&self, rng: &mut R) -> f64 {
+        self.span.gen_length(self.max_length, rng) as f64
+    }
+
+    /// All lattice points inside the cell.
+    pub fn nodes(&self) -> Vec<Point> {
+        self.indices()
+            .into_iter()
+            .map(|index| self.position(index))
+            .collect()
+    }
+
+    /// Move a point onto the lattice if it is only off it through floating point error.
+    pub fn snap_point(&self, point: Point) -> Point {
+        let node = self.position(self.index(point));
+        if (node - point).norm() < TOLERANCE * self.step_x.min(self.step_y) {
+            node
+        } else {
+            point
+        }
+    }
+
+    /// Move a mirror axis, given as the point the axes pass through, so that reflecting
+    /// in it maps the lattice onto itself.
+    pub fn snap_axis(&self, axis: Point) -> Point {
+        // Mirror lines are half way between parallel rows of points
+        let (spacing_x, spacing_y) = match self.lattice {
+            Lattice::Square => (self.step_x / 2.0, self.step_y / 2.0),
+            Lattice::Hexagonal => (self.step_x / 2.0, self.row_height()),
+            Lattice::Triangular => (self.row_height(), self.step_x / 2.0),
+            // Only lines through the centre are mirror lines
+            Lattice::Polar { .. } => return Point::new(0.5, 0.5),
+        };
+        // Measure from the centre, which every lattice is symmetric about
+        let snap =
+            |position: f64, spacing: f64| 0.5 + ((position - 0.5) / spacing).round() * spacing;
+        Point::new(snap(axis.x, spacing_x), snap(axis.y, spacing_y))
+    }
+
+    /// Generate a line between points on a non-square lattice.
+    ///
+    /// The line starts from the given point, or a random one, and is shortened so as to
+    /// end on a lattice point inside the cell that the mask allows.
+    pub fn gen_move<R: Rng>(
+        &self,
+        from: Option<Point>,
+        diagonal: bool,
+        rng: &mut R,
+    ) -> Option<Line> {
+        let mut start = match from {
+            Some(point) => self.index(point),
+            None => self.choose_start(
+                self.indices()
+                    .into_iter()
+                    .filter(|index| diagonal || !self.is_hexagonal_centre(*index))
+                    .collect(),
+                |index| self.position(index),
+                rng,
+            )?,
+        };
+        if let Lattice::Polar { spokes, .. } = self.lattice {
+            // Leave the centre along any spoke
+            if start.0 == 0 {
+                start.1 = rng.gen_range(0, spokes.max(1));
+            }
+        }
+        let (x, y) = *self.directions(start, diagonal).choose(rng)?;
+
+        // Shorten the line until it ends inside the cell
+        let mut length = self.gen_span(rng) as i32;
+        let end = loop {
+            if length <= 0 {
+                return None;
+            }
+            if let Some(end) = self.normalise((start.0 + x * length, start.1 + y * length)) {
+                if self.allows(self.position(end)) {
+                    break end;
+                }
+            }
+            length -= 1;
+        };
+        Some(Line::new(self.position(start), self.position(end)))
+    }
+
+    /// Indices of all lattice points inside the cell.
+    fn indices(&self) -> Vec<(i32, i32)> {
+        match self.lattice {
+            Lattice::Square => (0..self.resolution_x)
+                .flat_map(|x| (0..self.resolution_y).map(move |y| (x, y)))
+                .collect(),
+            Lattice::Hexagonal => {
+                let rows = (0.5 / self.row_height()).floor() as i32;
+                let columns = (0.5 / self.step_x).ceil() as i32 + rows;
+                (-rows..=rows)
+                    .flat_map(|y| (-columns..=columns).map(move |x| (x, y)))
+                    .filter_map(|index| self.normalise(index))
+                    .collect()
+            }
+            Lattice::Triangular => {
+                let columns = (0.5 / self.row_height()).floor() as i32;
+                let rows = (0.5 / self.step_x).ceil() as i32 + columns;
+                (-columns..=columns)
+                    .flat_map(|x| (-rows..=rows).map(move |y| (x, y)))
+                    .filter_map(|index| self.normalise(index))
+                    .collect()
+            }
+            Lattice::Polar { rings, spokes } => std::iter::once((0, 0))
+                .chain((1..=rings).flat_map(|ring| (0..spokes).map(move |spoke| (ring, spoke))))
+                .collect(),
+        }
+    }
+
+    /// Position of a lattice point from its index.
+    fn position(&self, (x, y): (i32, i32)) -> Point {
+        match self.lattice {
+            Lattice::Square => Point::new(
+                x as f64 / (self.resolution_x - 1) as f64,
+                y as f64 / (self.resolution_y - 1) as f64,
+            ),
+            Lattice::Hexagonal => Point::new(
+                0.5 + (x as f64 + y as f64 / 2.0) * self.step_x,
+                0.5 + y as f64 * self.row_height(),
+            ),
+            Lattice::Triangular => Point::new(
+                0.5 + x as f64 * self.row_height(),
+                0.5 + (y as f64 + x as f64 / 2.0) * self.step_x,
+            ),
+            Lattice::Polar { rings, spokes } => {
+                let radius = 0.5 * x as f64 / rings as f64;
+                let angle = 2.0 * PI * y as f64 / spokes as f64;
+                Point::new(0.5, 0.5) + Vector::new(angle.cos(), angle.sin()) * radius
+            }
+        }
+    }
+
+    /// Index of the nearest lattice point.
+    fn index(&self, point: Point) -> (i32, i32) {
+        match self.lattice {
+            Lattice::Square => (
+                (point.x / self.step_x).round() as i32,
+                (point.y / self.step_y).round() as i32,
+            ),
+            Lattice::Hexagonal => {
+                let y = ((point.y - 0.5) / self.row_height()).round();
+                let x = ((point.x - 0.5) / self.step_x - y / 2.0).round();
+                (x as i32, y as i32)
+            }
+            Lattice::Triangular => {
+                let x = ((point.x - 0.5) / self.row_height()).round();
+                let y = ((point.y - 0.5) / self.step_x - x / 2.0).round();
+                (x as i32, y as i32)
+            }
+            Lattice::Polar { rings, spokes } => {
+                let offset = point - Point::new(0.5, 0.5);
+                let ring = (offset.norm() / (0.5 / rings as f64)).round() as i32;
+                let spoke = (offset.y.atan2(offset.x) / (2.0 * PI / spokes as f64)).round() as i32;
+                self.normalise((ring, spoke)).unwrap_or((ring, spoke))
+            }
+        }
+    }
+
+    /// The canonical index of a lattice point, if it is inside the cell.
+    fn normalise(&self, (x, y): (i32, i32)) -> Option<(i32, i32)> {
+        match self.lattice {
+            Lattice::Square => {
+                if (0..self.resolution_x).contains(&x) && (0..self.resolution_y).contains(&y) {
+                    Some((x, y))
+                } else {
+                    None
+                }
+            }
+            Lattice::Hexagonal | Lattice::Triangular => {
+                let point = self.position((x, y));
+                let range = -TOLERANCE * self.step_x..1.0 + TOLERANCE * self.step_x;
+                if range.contains(&point.x) && range.contains(&point.y) {
+                    Some((x, y))
+                } else {
+                    None
+                }
+            }
+            Lattice::Polar { rings, spokes } => match x {
+                0 => Some((0, 0)),
+                ring if ring > 0 && ring <= rings => Some((ring, y.rem_euclid(spokes.max(1)))),
+                _ => None,
+            },
+        }
+    }
+
+    /// Index offsets to the neighbours of a lattice point.
+    ///
+    /// Orthogonal neighbours lie along the lattice's own lines, and diagonal neighbours across them.
+    fn directions(&self, index: (i32, i32), diagonal: bool) -> Vec<(i32, i32)> {
+        match self.lattice {
+            Lattice::Square | Lattice::Polar { .. } => {
+                let orthogonal = vec![(1, 0), (0, 1), (-1, 0), (0, -1)];
+                if diagonal {
+                    orthogonal
+                        .into_iter()
+                        .chain(vec![(1, 1), (-1, 1), (-1, -1), (1, -1)])
+                        .collect()
+                } else {
+                    orthogonal
+                }
+            }
+            Lattice::Hexagonal => HEXAGONAL_NEIGHBOURS
+                .iter()
+                .copied()
+                .filter(|(x, y)| diagonal || !self.is_hexagonal_centre((index.0 + x, index.1 + y)))
+                .collect(),
+            Lattice::Triangular => {
+                let neighbours = HEXAGONAL_NEIGHBOURS.iter();
+                if diagonal {
+                    neighbours
+                        .chain(TRIANGULAR_SECOND_NEIGHBOURS.iter())
+                        .copied()
+                        .collect()
+                } else {
+                    neighbours.copied().collect()
+                }
+            }
+        }
+    }
+
+    /// Distance between rows of a hexagonal lattice, or columns of a triangular one.
+    fn row_height(&self) -> f64 {
+        self.step_x * 3.0f64.sqrt() / 2.0
+    }
+
+    /// Whether a hexagonal lattice index is at the centre of a hexagon, rather than a vertex.
+    fn is_hexagonal_centre(&self, (x, y): (i32, i32)) -> bool {
+        self.lattice == Lattice::Hexagonal && (x - y).rem_euclid(3) == 0
+    }
+
+    /// Shorten a line starting inside the cell so that it ends inside the cell.
+    pub fn clamp_line(&self, line: &Line) -> Line {
+        let start = line.start();
+        let mut end = line.end();
+        let delta = (end - start).abs();
+        if (delta.x / self.step_x).round() > 1.0 || (delta.y / self.step_y).round() > 1.0 {
+            // Clip long lines where they leave the cell, keeping their direction
+            end = clip_line(start, end);
+        }
+        // Clamp to valid adjustment range
+        end = Point::new(end.x.clamp(0.0, 1.0), end.y.clamp(0.0, 1.0));
+        Line::new(start, end)
+    }
+
+    /// Bring a line starting inside the cell inside it entirely, according to the edge behaviour.
+    ///
+    /// Degenerate lines are dropped.
+    pub fn fit_line(&self, line: &Line) -> Vec<Line> {
+        let lines = match self.edge {
+            Edge::Clamp => vec![self.clamp_line(line)],
+            Edge::Wrap => wrap_line(line.start(), line.end()),
+        };
+        lines
+            .into_iter()
+            .filter(|line| line.start() != line.end())
+            .collect()
+    }
+}
+
+/// Generate a random coordinate from one of `resolution` evenly spaced points.
+fn gen_coordinate<R: Rng>(resolution: i32, rng: &mut R) -> f64 {
+    let index = rng.gen_range::<f64, _, _>(0.0, resolution as f64).floor();
+    index / (resolution - 1) as f64
+}
+
+/// Shorten a line starting inside the unit cell so that it ends inside the cell.
+fn clip_line(start: Point, end: Point) -> Point {
+    let delta = end - start;
+    let mut t: f64 = 1.0;
+    for axis in 0..2 {
+        if end[axis] > 1.0 {
+            t = t.min((1.0 - start[axis]) / delta[axis]);
+        } else if end[axis] < 0.0 {
+            t = t.min(-start[axis] / delta[axis]);
+        }
+    }
+    start + delta * t
+}
+
+/// Split a line at each cell boundary it crosses, translating each piece
+/// back into the unit cell.
+fn wrap_line(start: Point, end: Point) -> Vec<Line> {
+    let delta = end - start;
+    // Parameters along the line at which a boundary is crossed
+    let mut crossings = vec![0.0, 1.0];
+    for axis in 0..2 {
+        if delta[axis] == 0.0 {
+            continue;
+        }
+        let low = start[axis].min(end[axis]).ceil() as i32;
+        let high = start[axis].max(end[axis]).floor() as i32;
+        for boundary in low..=high {
+            let t = (boundary as f64 - start[axis]) / delta[axis];
+            if t > 0.0 && t < 1.0 {
+                crossings.push(t);
+            }
+        }
+    }
+    crossings.sort_by(|a, b| a.partial_cmp(b).expect("crossings are finite"));
+
+    let mut lines = Vec::new();
+    for window in crossings.windows(2) {
+        let piece_start = start + delta * window[0];
+        let piece_end = start + delta * window[1];
+        if piece_start == piece_end {
+            continue;
+        }
+        // Translate by the cell the middle of this piece falls in
+        let middle = start + delta * ((window[0] + window[1]) / 2.0);
+        let offset = Vector::new(middle.x.floor(), middle.y.floor());
+        lines.push(Line::new(piece_start - offset, piece_end - offset));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_mask_excludes_corners() {
+        let mut grid = Grid::new(5).unwrap();
+        grid.mask = Mask::Circle(0.5);
+        assert!(grid.allows(Point::new(0.5, 0.0)));
+        assert!(!grid.allows(Point::new(0.0, 0.0)));
+    }
+
+    #[test]
+    fn wrap_line_splits_at_edge() {
+        let lines = wrap_line(Point::new(0.5, 0.5), Point::new(1.5, 0.5));
+        assert_eq!(
+            lines,
+            vec![
+                Line::new(Point::new(0.5, 0.5), Point::new(1.0, 0.5)),
+                Line::new(Point::new(0.0, 0.5), Point::new(0.5, 0.5)),
+            ]
+        );
+    }
+}
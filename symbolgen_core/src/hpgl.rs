@@ -0,0 +1,128 @@
+//! Glyphs as HPGL commands for pen plotters.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::Glyph;
+
+/// How drawings are placed on the plotter.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct HpglOptions {
+    /// Plotter units for each unit of the drawing. Plotters usually have 40 units to the
+    /// millimetre.
+    pub scale: f64,
+    /// Height of the drawing, to turn it the right way up, as plotters have y upwards
+    pub height: f64,
+    /// Pen to draw with
+    pub pen: u32,
+    /// Pen to draw each glyph in turn with, in place of `pen`. Glyphs past the end of the
+    /// list are drawn with `pen`.
+    pub glyph_pens: Vec<u32>,
+}
+
+impl Default for HpglOptions {
+    fn default() -> Self {
+        Self {
+            scale: 10.0,
+            height: 0.0,
+            pen: 1,
+            glyph_pens: Vec::new(),
+        }
+    }
+}
+
+/// HPGL commands drawing each glyph, lifting the pen between runs of joined segments, and
+/// changing pens only when the next glyph needs a different one.
+///
+/// Arcs and curves are flattened into short lines. Glyphs are drawn where they are, so
+/// should already be scaled and moved into place.
+pub fn to_hpgl(glyphs: &[Glyph], options: &HpglOptions) -> String {
+    let mut hpgl = format!("IN;SP{};PA;\n", options.pen);
+    let position = |x: f64, y: f64| {
+        format!(
+            "{},{}",
+            (x * options.scale).round() as i64,
+            ((options.height - y) * options.scale).round() as i64
+        )
+    };
+    let mut current = options.pen;
+    for (index, glyph) in glyphs.iter().enumerate() {
+        for polyline in glyph.to_polylines() {
+            let (first, rest) = match polyline.split_first() {
+                Some((first, rest)) if !rest.is_empty() => (first, rest),
+                _ => continue,
+            };
+            let pen = options
+                .glyph_pens
+                .get(index)
+                .copied()
+                .unwrap_or(options.pen);
+            if pen != current {
+                writeln!(hpgl, "PU;SP{};", pen).expect("writing to a string can't fail");
+                current = pen;
+            }
+            let rest: Vec<String> = rest
+                .iter()
+                .map(|point| position(point.x, point.y))
+                .collect();
+            writeln!(
+                hpgl,
+                "PU{};PD{};",
+                position(first.x, first.y),
+                rest.join(",")
+            )
+            .expect("writing to a string can't fail");
+        }
+    }
+    hpgl.push_str("PU;SP0;\n");
+    hpgl
+}
+
+/// Write HPGL commands drawing each glyph, as for `to_hpgl`.
+pub fn write_hpgl<W: Write>(
+    mut writer: W,
+    glyphs: &[Glyph],
+    options: &HpglOptions,
+) -> io::Result<()> {
+    writer.write_all(to_hpgl(glyphs, options).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Line, Point};
+
+    #[test]
+    fn joined_lines_drawn_without_lifting() {
+        let glyph = Glyph::new(
+            0,
+            vec![
+                Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0)).into(),
+                Line::new(Point::new(1.0, 0.0), Point::new(1.0, 1.0)).into(),
+            ],
+        );
+        let options = HpglOptions {
+            scale: 40.0,
+            height: 1.0,
+            pen: 2,
+            ..HpglOptions::default()
+        };
+        assert_eq!(
+            to_hpgl(std::slice::from_ref(&glyph), &options),
+            "IN;SP2;PA;\nPU40,0;PD40,40,0,40;\nPU;SP0;\n"
+        );
+
+        let options = HpglOptions {
+            glyph_pens: vec![2, 3, 3],
+            ..options
+        };
+        assert_eq!(
+            to_hpgl(&[glyph.clone(), glyph.clone(), glyph], &options)
+                .matches("SP")
+                .count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Glyphs standing in for arbitrary content, like avatars for email addresses.
+
+use crate::{Alphabet, Glyph, Result};
+
+/// Glyphs seeded by a hash of some content.
+///
+/// The same content always gives the same glyph in the same style.
+#[derive(Clone, Copy, Debug)]
+pub struct Identicon;
+
+impl Identicon {
+    /// The seed for some content, which is its 64-bit FNV-1a hash.
+    pub fn seed(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    /// The glyph for some content, drawn in the style of the given alphabet.
+    pub fn from_bytes(bytes: &[u8], style: &Alphabet) -> Result<Glyph> {
+        style.generate(Self::seed(bytes))
+    }
+
+    /// The glyph for an email address, ignoring case and surrounding whitespace.
+    pub fn from_email(email: &str, style: &Alphabet) -> Result<Glyph> {
+        Self::from_bytes(email.trim().to_lowercase().as_bytes(), style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Motif, Symmetry};
+
+    #[test]
+    fn same_content_same_glyph() {
+        let style = Alphabet::new(4, 3, Symmetry::Horizontal, Motif::Diagonal).unwrap();
+        assert_eq!(Identicon::seed(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(
+            Identicon::from_email(" Someone@Example.com\n", &style).unwrap(),
+            Identicon::from_bytes(b"someone@example.com", &style).unwrap()
+        );
+        assert_ne!(Identicon::seed(b"a"), Identicon::seed(b"b"));
+    }
+}
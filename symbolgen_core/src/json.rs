@@ -0,0 +1,46 @@
+//! Glyphs and the settings that generated them as JSON, for use outside Rust.
+
+use std::io::{self, Write};
+
+use crate::{Alphabet, Glyph};
+
+/// Glyphs generated by one alphabet, alongside its settings.
+#[derive(Debug, serde::Serialize)]
+pub struct GlyphSet<'a> {
+    pub alphabet: &'a Alphabet,
+    pub glyphs: &'a [Glyph],
+}
+
+/// A JSON array of glyph sets, each with its alphabet's settings and each glyph's seed and
+/// segments.
+///
+/// Glyphs are written as they are, so are usually best left unscaled in unit coordinates.
+pub fn to_json(sets: &[GlyphSet]) -> String {
+    serde_json::to_string_pretty(sets).expect("glyph sets always serialize")
+}
+
+/// Write glyph sets as JSON, as for `to_json`.
+pub fn write_json<W: Write>(writer: W, sets: &[GlyphSet]) -> io::Result<()> {
+    serde_json::to_writer_pretty(writer, sets)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Motif, Symmetry};
+
+    #[test]
+    fn sets_include_seeds_and_settings() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let glyphs = alphabet.generate_batch(vec![3, 5]).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&to_json(&[GlyphSet {
+            alphabet: &alphabet,
+            glyphs: &glyphs,
+        }]))
+        .unwrap();
+        assert_eq!(json[0]["alphabet"]["density"], 3);
+        assert_eq!(json[0]["glyphs"][1]["seed"], 5);
+        assert!(json[0]["glyphs"][0]["segments"].is_array());
+    }
+}
@@ -0,0 +1,100 @@
+//! Conversions to kurbo shapes, for stroking, flattening and measuring glyphs with the
+//! druid, piet and vello ecosystem.
+
+use ::kurbo::{BezPath, CubicBez, PathSeg};
+
+use crate::{Arc, Curve, Glyph, Line, Point, Segment};
+
+fn point(point: Point) -> ::kurbo::Point {
+    ::kurbo::Point::new(point.x, point.y)
+}
+
+impl From<&Line> for ::kurbo::Line {
+    fn from(line: &Line) -> Self {
+        ::kurbo::Line::new(point(line.start()), point(line.end()))
+    }
+}
+
+impl From<&Curve> for CubicBez {
+    fn from(curve: &Curve) -> Self {
+        let [start, control_start, control_end, end] = curve.points();
+        CubicBez::new(
+            point(start),
+            point(control_start),
+            point(control_end),
+            point(end),
+        )
+    }
+}
+
+impl From<&Arc> for ::kurbo::Arc {
+    fn from(arc: &Arc) -> Self {
+        ::kurbo::Arc::new(
+            point(arc.centre()),
+            (arc.radius(), arc.radius()),
+            arc.start_angle(),
+            arc.end_angle() - arc.start_angle(),
+            0.0,
+        )
+    }
+}
+
+impl From<&Segment> for Vec<PathSeg> {
+    /// The segment as kurbo path segments, with arcs as the same cubic curves used elsewhere.
+    fn from(segment: &Segment) -> Self {
+        match segment {
+            Segment::Line(line) => vec![PathSeg::Line(line.into())],
+            Segment::Arc(arc) => arc
+                .to_curves()
+                .iter()
+                .map(|curve| PathSeg::Cubic(curve.into()))
+                .collect(),
+            Segment::Curve(curve) => vec![PathSeg::Cubic(curve.into())],
+        }
+    }
+}
+
+impl From<&Glyph> for BezPath {
+    /// The glyph as a path, starting a new subpath wherever a segment doesn't continue from
+    /// the last.
+    fn from(glyph: &Glyph) -> Self {
+        let mut path = BezPath::new();
+        let mut position = None;
+        for segment in glyph.segments() {
+            let start = segment.start();
+            if position != Some(start) {
+                path.move_to(point(start));
+            }
+            for piece in Vec::<PathSeg>::from(segment) {
+                match piece {
+                    PathSeg::Line(line) => path.line_to(line.p1),
+                    PathSeg::Quad(quad) => path.quad_to(quad.p1, quad.p2),
+                    PathSeg::Cubic(cubic) => path.curve_to(cubic.p1, cubic.p2, cubic.p3),
+                }
+            }
+            position = Some(segment.end());
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::kurbo::Shape;
+
+    #[test]
+    fn joined_segments_share_a_subpath() {
+        let glyph = Glyph::new(
+            0,
+            vec![
+                Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0)).into(),
+                Line::new(Point::new(1.0, 0.0), Point::new(1.0, 1.0)).into(),
+                Line::new(Point::new(0.0, 1.0), Point::new(0.5, 1.0)).into(),
+            ],
+        );
+        let path = BezPath::from(&glyph);
+        assert_eq!(path.elements().len(), 5);
+        assert_eq!(path.bounding_box(), ::kurbo::Rect::new(0.0, 0.0, 1.0, 1.0));
+    }
+}
@@ -4,22 +4,121 @@
 //! https://github.com/v3ga/Workshop_Processing_Axidraw_Stereolux_2019/blob/cdf0a7fdec7ea5d4f6f2ee72694661aad6278bbf/axidraw_grid/GridCellRenderAntoine.pde#L1
 #![deny(clippy::all)]
 
+use std::collections::HashSet;
 use std::f64::consts::PI;
-use std::f64::EPSILON;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use nalgebra::{
-    base::{dimension::U2, Matrix3, Vector2},
+    base::{dimension::U2, Matrix2, Matrix3, Vector2},
     geometry::{Isometry, Point as PointN, Rotation2, Translation},
 };
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+mod animation;
+#[cfg(feature = "binary")]
+mod binary;
+mod builder;
+mod config;
+#[cfg(feature = "embedded-graphics")]
+mod embedded;
+mod error;
+mod filter;
+#[cfg(feature = "font-source")]
+mod font;
+#[cfg(feature = "geo")]
+mod geo;
+mod graph;
+mod grid;
+mod hpgl;
+mod identicon;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "kurbo")]
+mod kurbo;
+#[cfg(feature = "json")]
+mod manifest;
+#[cfg(feature = "mesh")]
+mod mesh;
+mod metrics;
+pub mod motif;
+mod planar;
+mod progress;
+mod seed;
+mod segment;
+#[cfg(feature = "config")]
+mod set;
+#[cfg(feature = "font-source")]
+mod sfd;
+mod stats;
+mod svg;
+mod sweep;
+mod text;
+mod theme;
+mod transform;
+#[cfg(feature = "font")]
+mod ttf;
+#[cfg(feature = "font-source")]
+mod ufo;
+mod units;
+
+#[cfg(feature = "svg")]
+pub use crate::svg::to_svg_document;
+pub use crate::svg::{to_svg, write_svg, LineCap, SvgOptions};
+pub use animation::{morph, Animator, Easing, Timing};
+pub use builder::AlphabetBuilder;
+pub use config::{AlphabetConfig, GenerationConfig, SheetLayout};
+#[cfg(feature = "embedded-graphics")]
+pub use embedded::GlyphDrawable;
+pub use error::{Error, Result};
+pub use filter::{Comparison, Condition, GlyphFilter, Metric};
+#[cfg(feature = "font-source")]
+pub use font::FontOptions;
+#[cfg(feature = "geo")]
+pub use geo::{to_geojson, write_geojson, MapMarker};
+pub use graph::Stroke;
+pub use grid::{Adjustment, Edge, Grid, Lattice, Mask, Span, Start};
+pub use hpgl::{to_hpgl, write_hpgl, HpglOptions};
+pub use identicon::Identicon;
+#[cfg(feature = "json")]
+pub use json::{to_json, write_json, GlyphSet};
+#[cfg(feature = "json")]
+pub use manifest::{Manifest, ManifestGlyph, Mismatch};
+#[cfg(feature = "mesh")]
+pub use mesh::{Mesh, MeshOptions};
+pub use metrics::{Metrics, MetricsOptions, Spacing};
+pub use motif::{Motif, MotifStrategy};
+pub use planar::{Hatch, PlanarGraph};
+pub use progress::Progress;
+pub use seed::SeedDerivation;
+pub use segment::{Arc, Curve, Line, Segment, UNITS};
+#[cfg(feature = "config")]
+pub use set::{NamedGlyph, NamedSet};
+#[cfg(feature = "font-source")]
+pub use sfd::to_sfd;
+pub use stats::GlyphStats;
+pub use sweep::{Sweep, SweepAxis};
+pub use text::{Align, CharacterMap, SetText, TextLayout, ALPHANUMERIC};
+pub use theme::{Color, Cycle, Theme};
+pub use transform::{Deduplicate, GlyphTransform, Simplify, Stage};
+#[cfg(feature = "font")]
+pub use ttf::to_font;
+#[cfg(feature = "font-source")]
+pub use ufo::to_ufo;
+pub use units::{Paper, Unit};
 
 pub type Point = PointN<f64, U2>;
 pub type Vector = Vector2<f64>;
 
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Symmetry {
     Asymmetric,
     Horizontal,
@@ -27,12 +126,186 @@ pub enum Symmetry {
     HorizontalVertical,
     Rotation90,
     Rotation180,
+    /// N-fold rotation about the centre of the cell.
+    Rotational(u32),
     Matrices(Vec<Matrix3<f64>>),
+    /// Several symmetries, applied in order.
+    Composite(Vec<Symmetry>),
+}
+
+impl Symmetry {
+    /// Add symmetric copies of the given segments.
+    fn apply(&self, alphabet: &Alphabet, segments: &mut Vec<Segment>) {
+        match self {
+            Symmetry::Asymmetric => {}
+            Symmetry::Horizontal => reflect_horizontal(alphabet, segments),
+            Symmetry::Vertical => reflect_vertical(alphabet, segments),
+            Symmetry::HorizontalVertical => {
+                reflect_horizontal(alphabet, segments);
+                reflect_vertical(alphabet, segments);
+            }
+            Symmetry::Rotation180 => rotate_half(segments),
+            Symmetry::Rotation90 => {
+                rotate_half(segments);
+                rotate_quarter(segments);
+            }
+            Symmetry::Rotational(order) => {
+                for segment in segments.clone().iter() {
+                    for turn in 1..*order {
+                        let matrix = centre_rotation(turn, *order).to_homogeneous();
+                        segments.push(segment.transformed(&matrix));
+                    }
+                }
+            }
+            Symmetry::Matrices(matrices) => {
+                for segment in segments.clone().iter() {
+                    for matrix in matrices.iter() {
+                        segments.push(segment.transformed(matrix));
+                    }
+                }
+            }
+            Symmetry::Composite(symmetries) => {
+                for symmetry in symmetries.iter() {
+                    symmetry.apply(alphabet, segments);
+                }
+            }
+        }
+    }
+}
+
+fn reflect_horizontal(alphabet: &Alphabet, segments: &mut Vec<Segment>) {
+    let axis = alphabet.grid.snap_axis(alphabet.axis);
+    for segment in segments.clone().iter() {
+        // Reflect in the y-axis, then translate x-axis to the mirror axis
+        let matrix = Matrix3::new(-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0)
+            .append_translation(&Vector2::new(2.0 * axis.x, 0.0));
+
+        let reflected = segment
+            .transformed(&matrix)
+            .map_points(|point| alphabet.grid.snap_point(point));
+        if let Some(reflected) = clip_inside(reflected) {
+            segments.push(reflected);
+        }
+    }
+}
+
+fn reflect_vertical(alphabet: &Alphabet, segments: &mut Vec<Segment>) {
+    let axis = alphabet.grid.snap_axis(alphabet.axis);
+    for segment in segments.clone().iter() {
+        // Reflect in the x-axis, then translate y-axis to the mirror axis
+        let matrix = Matrix3::new(1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0)
+            .append_translation(&Vector2::new(0.0, 2.0 * axis.y));
+
+        let reflected = segment
+            .transformed(&matrix)
+            .map_points(|point| alphabet.grid.snap_point(point));
+        if let Some(reflected) = clip_inside(reflected) {
+            segments.push(reflected);
+        }
+    }
+}
+
+/// How far outside the cell a reflected arc may reach through floating point error.
+const CELL_TOLERANCE: f64 = 1e-9;
+
+/// The part of a reflected segment inside the cell, if any, as reflecting in an axis off the
+/// centre can move it partly or wholly outside.
+///
+/// Lines are shortened along their own direction, so they stay mirror images of the originals.
+/// Arcs and curves are kept only if they are wholly inside.
+fn clip_inside(segment: Segment) -> Option<Segment> {
+    let inside = |point: Point| {
+        (-CELL_TOLERANCE..=1.0 + CELL_TOLERANCE).contains(&point.x)
+            && (-CELL_TOLERANCE..=1.0 + CELL_TOLERANCE).contains(&point.y)
+    };
+    let clipped = match &segment {
+        Segment::Line(line) => {
+            let (start, end) = clip_to_cell(line.start(), line.end())?;
+            Segment::Line(Line::new(start, end))
+        }
+        Segment::Arc(arc) => {
+            let (low, high) = arc.bounds();
+            if !(inside(low) && inside(high)) {
+                return None;
+            }
+            segment
+        }
+        // Curves lie within the hull of their control points
+        Segment::Curve(curve) => {
+            if !curve.points().iter().all(|point| inside(*point)) {
+                return None;
+            }
+            segment
+        }
+    };
+    (!clipped.is_degenerate()).then_some(clipped)
+}
+
+/// The ends of the part of a line inside the unit cell, if any of it is.
+fn clip_to_cell(start: Point, end: Point) -> Option<(Point, Point)> {
+    let delta = end - start;
+    let (mut enter, mut exit): (f64, f64) = (0.0, 1.0);
+    for axis in 0..2 {
+        if delta[axis] == 0.0 {
+            if !(0.0..=1.0).contains(&start[axis]) {
+                return None;
+            }
+            continue;
+        }
+        let low = -start[axis] / delta[axis];
+        let high = (1.0 - start[axis]) / delta[axis];
+        enter = enter.max(low.min(high));
+        exit = exit.min(low.max(high));
+    }
+    (enter < exit).then(|| (start + delta * enter, start + delta * exit))
+}
+
+fn rotate_half(segments: &mut Vec<Segment>) {
+    for segment in segments.clone().iter() {
+        // Rotate by half a turn, then translate both axes by 1.0
+        let matrix = Isometry::from_parts(
+            Translation::from(Vector2::new(1.0, 1.0)),
+            Rotation2::new(PI),
+        );
+
+        segments.push(segment.transformed(&matrix.to_homogeneous()));
+    }
+}
+
+fn rotate_quarter(segments: &mut Vec<Segment>) {
+    for segment in segments.clone().iter() {
+        let matrix = Isometry::from_parts(
+            Translation::from(Vector2::new(1.0, 0.0)),
+            Rotation2::new(PI / 2.0),
+        );
+
+        segments.push(segment.transformed(&matrix.to_homogeneous()));
+    }
+}
+
+impl fmt::Display for Symmetry {
+    /// The symmetry's name, which parses back to the same symmetry, except for matrices.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Symmetry::Asymmetric => f.write_str("asymmetric"),
+            Symmetry::Horizontal => f.write_str("horizontal"),
+            Symmetry::Vertical => f.write_str("vertical"),
+            Symmetry::HorizontalVertical => f.write_str("horizontalvertical"),
+            Symmetry::Rotation90 => f.write_str("rotation90"),
+            Symmetry::Rotation180 => f.write_str("rotation180"),
+            Symmetry::Rotational(order) => write!(f, "rotational{}", order),
+            Symmetry::Matrices(_) => f.write_str("matrices"),
+            Symmetry::Composite(symmetries) => {
+                let names: Vec<String> = symmetries.iter().map(Symmetry::to_string).collect();
+                f.write_str(&names.join(","))
+            }
+        }
+    }
 }
 
 impl FromStr for Symmetry {
-    type Err = String;
-    fn from_str(symmetry: &str) -> Result<Self, Self::Err> {
+    type Err = Error;
+    fn from_str(symmetry: &str) -> Result<Self> {
         match symmetry {
             "asymmetric" => Ok(Symmetry::Asymmetric),
             "horizontal" => Ok(Symmetry::Horizontal),
@@ -40,226 +313,1073 @@ impl FromStr for Symmetry {
             "horizontalvertical" => Ok(Symmetry::HorizontalVertical),
             "rotation90" => Ok(Symmetry::Rotation90),
             "rotation180" => Ok(Symmetry::Rotation180),
-            _ => Err(format!("Could not parse symmetry '{}'", symmetry)),
+            _ if symmetry.contains(',') => symmetry
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<_>>()
+                .map(Symmetry::Composite),
+            _ => symmetry
+                .strip_prefix("rotational")
+                .and_then(|order| order.parse().ok())
+                .filter(|order| *order > 0)
+                .map(Symmetry::Rotational)
+                .ok_or_else(|| Error::parse("symmetry", symmetry)),
         }
     }
 }
 
-#[non_exhaustive]
-#[derive(Debug, PartialEq)]
-pub enum Motif {
-    Orthogonal,
-    Diagonal,
-}
-
-#[derive(Debug)]
+/// The segments of a symbol, and the seed they were generated from.
+///
+/// Glyphs are equal if they draw the same segments, in any order or direction, whatever
+/// their seeds.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Glyph {
     /// Original seed
     seed: u64,
-    /// Generated lines
-    lines: Vec<Line>,
+    /// Generated segments
+    segments: Vec<Segment>,
 }
 
 impl Glyph {
-    pub fn new(seed: u64, lines: Vec<Line>) -> Self {
-        Self { seed, lines }
+    pub fn new(seed: u64, segments: Vec<Segment>) -> Self {
+        Self { seed, segments }
     }
 
     pub fn seed(&self) -> u64 {
         self.seed
     }
 
-    pub fn lines(&self) -> &[Line] {
-        &self.lines
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn segments_mut(&mut self) -> &mut Vec<Segment> {
+        &mut self.segments
+    }
+
+    /// A 64-bit FNV-1a hash of the exact coordinates of each segment, in order.
+    ///
+    /// Unlike equality, this tells apart glyphs whose segments differ in the slightest, or are
+    /// in a different order, so it is the same only for geometry that is byte for byte the
+    /// same. It is stable across platforms and releases.
+    pub fn geometry_hash(&self) -> u64 {
+        let mut values = Vec::new();
+        for segment in &self.segments {
+            let (kind, points, extra) = match segment {
+                Segment::Line(line) => (0, vec![line.start(), line.end()], Vec::new()),
+                Segment::Arc(arc) => (1, vec![arc.centre(), arc.start(), arc.end()], Vec::new()),
+                Segment::Curve(curve) => (2, curve.points().to_vec(), Vec::new()),
+            };
+            values.push(kind);
+            values.extend(
+                points
+                    .iter()
+                    .flat_map(|point| [point.x, point.y])
+                    .chain(extra)
+                    .map(f64::to_bits),
+            );
+        }
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// Apply an affine transformation, given as a homogeneous matrix.
+    ///
+    /// Arcs are replaced by curves if the transformation would not keep them circular.
+    pub fn transformed(&self, matrix: &Matrix3<f64>) -> Glyph {
+        // Only rotations, reflections and uniform scaling keep circles circular
+        let linear = matrix.fixed_slice::<U2, U2>(0, 0);
+        let product = linear.transpose() * linear;
+        let tolerance = 1e-9 * product.norm();
+        let circular = product[(0, 1)].abs() <= tolerance
+            && (product[(0, 0)] - product[(1, 1)]).abs() <= tolerance;
+        let segments = self
+            .segments
+            .iter()
+            .flat_map(|segment| match segment {
+                Segment::Arc(arc) if !circular => {
+                    arc.to_curves().into_iter().map(Segment::from).collect()
+                }
+                segment => vec![segment.clone()],
+            })
+            .map(|segment| segment.transformed(matrix))
+            .collect();
+        Glyph::new(self.seed, segments)
+    }
+
+    /// Scale about the origin.
+    pub fn scaled(&self, factor: f64) -> Glyph {
+        self.transformed(&Matrix3::new_scaling(factor))
+    }
+
+    /// Rotate about the origin, by an angle in radians.
+    pub fn rotated(&self, angle: f64) -> Glyph {
+        self.transformed(&Rotation2::new(angle).to_homogeneous())
+    }
+
+    /// Move by the given offset.
+    pub fn translated(&self, offset: Vector) -> Glyph {
+        self.transformed(&Matrix3::new_translation(&offset))
+    }
+
+    /// Shear horizontally, moving each point across by `factor` times its height.
+    pub fn sheared(&self, factor: f64) -> Glyph {
+        self.transformed(&Matrix3::new(
+            1.0, factor, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+        ))
+    }
+
+    /// Draw both glyphs together, skipping segments of the other already in this one.
+    ///
+    /// The result keeps this glyph's seed.
+    pub fn merge(&self, other: &Glyph) -> Glyph {
+        let mut seen: HashSet<_> = self.segments.iter().map(Segment::key).collect();
+        let mut segments = self.segments.clone();
+        segments.extend(
+            other
+                .segments
+                .iter()
+                .filter(|segment| seen.insert(segment.key()))
+                .cloned(),
+        );
+        Glyph::new(self.seed, segments)
+    }
+
+    /// Draw another glyph over this one, moved by the given offset.
+    pub fn overlay_offset(&self, other: &Glyph, offset: Vector) -> Glyph {
+        self.merge(&other.translated(offset))
+    }
+
+    /// Remove segments that are also in the other glyph, in either direction.
+    pub fn subtract(&self, other: &Glyph) -> Glyph {
+        let theirs: HashSet<_> = other.segments.iter().map(Segment::key).collect();
+        let segments = self
+            .segments
+            .iter()
+            .filter(|segment| !theirs.contains(&segment.key()))
+            .cloned()
+            .collect();
+        Glyph::new(self.seed, segments)
+    }
+
+    /// Split into groups of segments connected through shared end points, largest first.
+    pub fn components(&self) -> Vec<Glyph> {
+        graph::components(&self.segments)
+            .into_iter()
+            .map(|segments| Glyph::new(self.seed, segments))
+            .collect()
+    }
+
+    /// The glyph part way through being drawn, from nothing at 0 to all of it at 1.
+    pub fn frame(&self, t: f64) -> Vec<Segment> {
+        Animator::default().frame(self, t)
+    }
+
+    /// Order the segments into one continuous path where possible, with as few travel
+    /// moves between them as needed otherwise.
+    pub fn to_single_stroke(&self) -> Vec<Stroke> {
+        graph::single_stroke(&self.segments)
+    }
+
+    /// Join segments sharing end points into ordered lists of points.
+    ///
+    /// Arcs and curves are flattened into short lines.
+    pub fn to_polylines(&self) -> Vec<Vec<Point>> {
+        graph::polylines(&self.segments, FLATTEN_SAMPLES)
+    }
+
+    /// Merge lines that continue straight on from one another, such as consecutive steps
+    /// along a row, into single longer lines.
+    pub fn simplify(&mut self) {
+        self.segments = graph::simplify(&self.segments);
+    }
+
+    /// Split segments wherever they cross, and find the regions they enclose.
+    ///
+    /// Arcs and curves are flattened into short lines.
+    pub fn planar_graph(&self) -> PlanarGraph {
+        PlanarGraph::new(&self.segments, FLATTEN_SAMPLES)
+    }
+
+    /// The outline of each region enclosed by the glyph's segments.
+    pub fn regions(&self) -> Vec<Vec<Point>> {
+        self.planar_graph().regions()
+    }
+
+    /// Fill every enclosed region with hatching lines.
+    pub fn hatch(&mut self, hatch: &Hatch) {
+        let lines: Vec<Line> = self
+            .regions()
+            .iter()
+            .flat_map(|region| hatch.fill(region))
+            .collect();
+        self.segments.extend(lines.into_iter().map(Segment::from));
+    }
+
+    /// Draw each segment in a consistent direction, sort them, and remove duplicates.
+    pub fn canonicalize(&mut self) {
+        let mut segments: Vec<Segment> = self.segments.iter().map(Segment::canonical).collect();
+        segments.sort_by_cached_key(Segment::key);
+        segments.dedup_by_key(|segment| segment.key());
+        self.segments = segments;
+    }
+
+    /// How alike two glyphs look, from 0 for nothing in common to 1 for the same drawing.
+    ///
+    /// Based on the Hausdorff distance between points sampled along each glyph's segments,
+    /// relative to the diagonal of the cell.
+    pub fn similarity(&self, other: &Glyph) -> f64 {
+        let sample = |glyph: &Glyph| -> Vec<Point> {
+            glyph
+                .segments
+                .iter()
+                .flat_map(|segment| segment.sample(SIMILARITY_SAMPLES))
+                .collect()
+        };
+        let (ours, theirs) = (sample(self), sample(other));
+        if ours.is_empty() || theirs.is_empty() {
+            return if ours.is_empty() && theirs.is_empty() {
+                1.0
+            } else {
+                0.0
+            };
+        }
+
+        // Furthest any point in one set is from the nearest point in the other
+        let directed = |from: &[Point], to: &[Point]| {
+            from.iter()
+                .map(|a| {
+                    to.iter()
+                        .map(|b| (a - b).norm())
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .fold(0.0, f64::max)
+        };
+        let distance = directed(&ours, &theirs).max(directed(&theirs, &ours));
+        (1.0 - distance / 2.0f64.sqrt()).max(0.0)
+    }
+
+    /// Sorted, distinct keys of the segments, for comparison.
+    fn keys(&self) -> Vec<Vec<(i64, i64)>> {
+        let mut keys: Vec<_> = self.segments.iter().map(Segment::key).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+}
+
+impl PartialEq for Glyph {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys() == other.keys()
+    }
+}
+
+impl Eq for Glyph {}
+
+impl Hash for Glyph {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.keys().hash(state);
+    }
+}
+
+/// How the number of lines limits the segments generated for a glyph.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum LineCount {
+    /// Make one attempt per line, skipping any that are invalid or degenerate.
+    Attempts,
+    /// Keep trying until there are exactly that many distinct segments, giving up after
+    /// this many attempts.
+    Exact(i32),
+}
+
+/// Which groups of connected segments to keep in a glyph.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Components {
+    /// Keep every segment.
+    All,
+    /// Drop segments not connected to any other, unless there is nothing else.
+    DropStrays,
+    /// Keep only the largest group of connected segments.
+    Largest,
+}
+
+/// When two glyphs count as the same symbol.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Distinct {
+    /// Glyphs drawing the same segments are the same.
+    Identical,
+    /// Glyphs that are the same after reflecting or rotating by quarter turns are the same.
+    UnderSymmetry,
+}
+
+/// How heavily a glyph is drawn, relative to the alphabet's number of lines.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Weight {
+    /// Half the lines.
+    Light,
+    Regular,
+    /// Half as many lines again.
+    Bold,
+    /// Any multiple of the number of lines.
+    Factor(f64),
+}
+
+impl Weight {
+    /// The multiple of the alphabet's number of lines drawn.
+    pub fn factor(&self) -> f64 {
+        match self {
+            Weight::Light => 0.5,
+            Weight::Regular => 1.0,
+            Weight::Bold => 1.5,
+            Weight::Factor(factor) => *factor,
+        }
+    }
+}
+
+/// Versions of the generation algorithm.
+///
+/// Improvements to generation come as new versions, and old versions are kept, so the glyph
+/// for a seed never changes once an alphabet has settled on a version.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Algorithm {
+    V1,
+}
+
+impl Algorithm {
+    /// The newest version, used by default.
+    pub const LATEST: Algorithm = Algorithm::V1;
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::V1 => f.write_str("v1"),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+    fn from_str(algorithm: &str) -> Result<Self> {
+        match algorithm {
+            "v1" => Ok(Algorithm::V1),
+            _ => Err(Error::parse("algorithm", algorithm)),
+        }
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Alphabet {
-    /// The numer steps visible along one grid axis.
-    pub resolution: i32,
+    /// The lattice to generate on.
+    pub grid: Grid,
     /// The number of lines to draw per resolution
     pub density: i32,
-    /// Whether to mirror in the y-axis
-    pub symmetry: Symmetry,
     /// Enable diagonal lines
     pub motif: Motif,
+    /// Point through which the mirror axes pass
+    pub axis: Point,
+    /// Version of the generation algorithm
+    pub algorithm: Algorithm,
 
     /// The number of lines generated.
     pub num_lines: i32,
-    /// 1 / resolution
-    pub step: f64,
+    /// Whether the number of lines is a limit on attempts or on segments
+    pub line_count: LineCount,
+    /// The fewest segments a glyph may have, after the pipeline
+    pub min_lines: Option<usize>,
+    /// The most segments a glyph may have, after the pipeline
+    pub max_lines: Option<usize>,
+    /// Skip proposed segments that have already been drawn, in either direction. Symmetric
+    /// copies are removed by the pipeline's `Deduplicate` stage.
+    pub deduplicate: bool,
+    /// Stages applied in order to each glyph, starting with symmetry
+    #[cfg_attr(feature = "serde", serde(with = "transform::pipeline"))]
+    pub pipeline: Vec<Box<dyn GlyphTransform>>,
 }
 
 impl Alphabet {
-    pub fn new(resolution: i32, density: i32, symmetry: Symmetry, motif: Motif) -> Self {
-        Self {
-            resolution,
-            step: 1.0 / (resolution - 1) as f64,
+    /// An alphabet whose pipeline applies the symmetry, then a `Deduplicate` stage.
+    pub fn new(resolution: i32, density: i32, symmetry: Symmetry, motif: Motif) -> Result<Self> {
+        if density < 1 {
+            return Err(Error::Density(density));
+        }
+        Ok(Self {
+            grid: Grid::new(resolution)?,
             density,
-            symmetry,
             motif,
+            axis: Point::new(0.5, 0.5),
+            algorithm: Algorithm::LATEST,
 
             num_lines: density * resolution,
-        }
+            line_count: LineCount::Attempts,
+            min_lines: None,
+            max_lines: None,
+            deduplicate: true,
+            pipeline: vec![Box::new(symmetry), Box::new(Deduplicate)],
+        })
     }
 
-    /// Generate a random x coordinate
-    fn gen_coordinate<R: Rng>(&self, rng: &mut R) -> f64 {
-        let index = rng
-            .gen_range::<f64, _, _>(0.0, self.resolution as f64)
-            .floor();
-        index / (self.resolution - 1) as f64
+    /// Start building an alphabet from the defaults.
+    pub fn builder() -> AlphabetBuilder {
+        AlphabetBuilder::new()
     }
 
-    fn gen_point<R: Rng>(&self, rng: &mut R) -> Point {
-        Point::new(self.gen_coordinate(rng), self.gen_coordinate(rng))
+    /// An alphabet on a grid with a different number of points along each axis.
+    ///
+    /// The number of lines is taken from the larger resolution.
+    pub fn rectangular(
+        resolution_x: i32,
+        resolution_y: i32,
+        density: i32,
+        symmetry: Symmetry,
+        motif: Motif,
+    ) -> Result<Self> {
+        Ok(Self {
+            grid: Grid::rectangular(resolution_x, resolution_y)?,
+            num_lines: density * resolution_x.max(resolution_y),
+            ..Self::new(resolution_x, density, symmetry, motif)?
+        })
     }
 
-    /// Generate -1, 0, 1 with equal probability.
-    fn gen_adjustment<R: Rng>(&self, rng: &mut R) -> f64 {
-        rng.gen_range(-1, 2) as f64
+    pub fn generate(&self, seed: u64) -> Result<Glyph> {
+        self.generate_with(&self.motif, seed)
     }
 
-    pub fn generate(&self, seed: u64) -> Glyph {
-        let mut rng = ChaCha8Rng::seed_from_u64(seed);
-        let mut lines = Vec::new();
-
-        for _i in 0..self.num_lines {
-            let coin_flip: bool = rng.gen();
-            let coin_fliend_point: bool = rng.gen();
-
-            // Generate a random point to start the line
-            let start_point = self.gen_point(&mut rng);
-            // Start with no change at all
-            let mut additive = Vector::new(0.0, 0.0);
-
-            if self.motif == Motif::Orthogonal {
-                // Either adjust x, or y, orthogonally
-                if coin_flip {
-                    if start_point.x == 0.0 {
-                        // If no x addition, add half
-                        additive += Vector::new(self.step, 0.0);
-                    } else if (start_point.x - 1.0).abs() < EPSILON {
-                        // If full width, subtract half
-                        additive += Vector::new(-self.step, 0.0);
-                    } else {
-                        // If neighther, randomly adjust by up to one resolution
-                        additive += Vector::new(self.gen_adjustment(&mut rng) * self.step, 0.0);
-                    }
-                } else {
-                    // If no x addition, add half
-                    if start_point.y == 0.0 {
-                        additive += Vector::new(0.0, self.step);
-                    } else if (start_point.y - 1.0).abs() < EPSILON {
-                        additive += Vector::new(0.0, -self.step);
-                    } else {
-                        // If neighther, randomly adjust by up to one resolution
-                        additive += Vector::new(0.0, self.gen_adjustment(&mut rng) * self.step);
-                    }
-                }
+    /// Generate a glyph, proposing segments with a custom motif.
+    ///
+    /// Glyphs with too few or too many segments are regenerated from a seed derived from
+    /// the original, which the glyph keeps.
+    pub fn generate_with<M: MotifStrategy>(&self, motif: &M, seed: u64) -> Result<Glyph> {
+        self.generate_using::<ChaCha8Rng, M>(motif, seed)
+    }
+
+    /// Generate a glyph, seeding a different random number generator than the default
+    /// `ChaCha8Rng`.
+    pub fn generate_with_rng<R: Rng + SeedableRng>(&self, seed: u64) -> Result<Glyph> {
+        self.generate_using::<R, Motif>(&self.motif, seed)
+    }
+
+    /// Generate a glyph from a seed drawn from an existing random number generator.
+    ///
+    /// The glyph keeps the seed, so `generate` reproduces it without the generator.
+    pub fn generate_from<R: Rng>(&self, rng: &mut R) -> Result<Glyph> {
+        self.generate(rng.gen())
+    }
+
+    fn generate_using<R: Rng + SeedableRng, M: MotifStrategy>(
+        &self,
+        motif: &M,
+        seed: u64,
+    ) -> Result<Glyph> {
+        self.validate()?;
+        match self.algorithm {
+            Algorithm::V1 => self.generate_v1::<R, M>(motif, seed),
+        }
+    }
+
+    /// Propose segments from the seed, retrying with derived seeds until the glyph is
+    /// within the line limits.
+    fn generate_v1<R: Rng + SeedableRng, M: MotifStrategy>(
+        &self,
+        motif: &M,
+        seed: u64,
+    ) -> Result<Glyph> {
+        for attempt in 0..=MAX_REJECTIONS {
+            let derived = if attempt == 0 {
+                seed
             } else {
-                // If we have diagonals, adjust x and y independently
-
-                if coin_flip {
-                    additive += Vector::new(self.gen_adjustment(&mut rng) * self.step, 0.0);
-                };
-                if coin_fliend_point {
-                    additive += Vector::new(0.0, self.gen_adjustment(&mut rng) * self.step);
-                };
+                SeedDerivation::new(seed).retry(attempt).seed()
+            };
+            let mut rng = R::seed_from_u64(derived);
+            let glyph = self.finish(seed, self.propose(motif, &mut rng));
+            if self.accepts(glyph.segments()) {
+                return Ok(glyph);
             }
+        }
+        Err(Error::Unsatisfiable(MAX_REJECTIONS + 1))
+    }
 
-            let mut end_point = start_point + additive;
-            // Clamp to valid adjustment range
-            end_point = Point::new(end_point.x.max(0.0).min(1.0), end_point.y.max(0.0).min(1.0));
-
-            // Check the line is valid, continue if not
-            if start_point == end_point {
-                continue;
+    /// Check settings that may have been changed since construction.
+    fn validate(&self) -> Result<()> {
+        if self.grid.resolution_x < 2 || self.grid.resolution_y < 2 {
+            return Err(Error::Resolution(
+                self.grid.resolution_x,
+                self.grid.resolution_y,
+            ));
+        }
+        if let (Some(min), Some(max)) = (self.min_lines, self.max_lines) {
+            if min > max {
+                return Err(Error::LineLimits { min, max });
             }
+        }
+        Ok(())
+    }
 
-            lines.push(Line::new(start_point, end_point));
+    /// Run the pipeline over proposed segments.
+    fn finish(&self, seed: u64, segments: Vec<Segment>) -> Glyph {
+        let mut glyph = Glyph::new(seed, segments);
+        for stage in self.pipeline.iter() {
+            stage.transform(self, &mut glyph);
         }
+        glyph
+    }
 
-        if self.symmetry == Symmetry::Horizontal || self.symmetry == Symmetry::HorizontalVertical {
-            for line in lines.clone().iter() {
-                // Reflect in the y-axis, then translate x-axis by 1.0
-                let matrix = Matrix3::new(-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0)
-                    .append_translation(&Vector2::new(1.0, 0.0));
+    /// Lazily generate glyphs from consecutive seeds, starting at `start_seed`.
+    ///
+    /// Each glyph is only generated when the iterator reaches it, so adapters like `filter`
+    /// and `take` do no more work than they need.
+    pub fn glyphs(&self, start_seed: u64) -> impl Iterator<Item = Result<Glyph>> + '_ {
+        (start_seed..=u64::MAX).map(move |seed| self.generate(seed))
+    }
 
-                let start = matrix.transform_point(&line.start());
-                let end = matrix.transform_point(&line.end());
-                lines.push(Line::new(start, end));
-            }
-        };
+    /// Generate a glyph from each seed, in order.
+    ///
+    /// With the `rayon` feature, glyphs are generated in parallel.
+    pub fn generate_batch<I: IntoIterator<Item = u64>>(&self, seeds: I) -> Result<Vec<Glyph>> {
+        self.generate_batch_with_progress(seeds, &|_| {})
+    }
 
-        if self.symmetry == Symmetry::Vertical || self.symmetry == Symmetry::HorizontalVertical {
-            for line in lines.clone().iter() {
-                // Reflect in the x-axis, then translate y-axis by 1.0
-                let matrix = Matrix3::new(1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0)
-                    .append_translation(&Vector2::new(0.0, 1.0));
+    /// Generate a glyph from each seed, in order, as for `generate_batch`, calling `progress`
+    /// as each glyph is done.
+    ///
+    /// With the `rayon` feature, `progress` may be called from several threads at once, so
+    /// not always in order of how much is done.
+    pub fn generate_batch_with_progress<I: IntoIterator<Item = u64>>(
+        &self,
+        seeds: I,
+        progress: &(dyn Fn(Progress) + Sync),
+    ) -> Result<Vec<Glyph>> {
+        let seeds: Vec<u64> = seeds.into_iter().collect();
+        let total = seeds.len();
+        let done = AtomicUsize::new(0);
+        #[cfg(feature = "rayon")]
+        let seeds = seeds.into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let seeds = seeds.into_iter();
+        seeds
+            .map(|seed| {
+                let glyph = self.generate(seed);
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                progress(Progress { done, total });
+                glyph
+            })
+            .collect()
+    }
 
-                let start = matrix.transform_point(&line.start());
-                let end = matrix.transform_point(&line.end());
-                lines.push(Line::new(start, end));
+    /// Generate `count` glyphs that are all different symbols, from consecutive seeds
+    /// starting at `base_seed`.
+    ///
+    /// Seeds producing a symbol already generated are skipped. If the alphabet cannot
+    /// produce enough different symbols, fewer glyphs are returned.
+    pub fn generate_distinct(
+        &self,
+        count: usize,
+        base_seed: u64,
+        distinct: Distinct,
+    ) -> Result<Vec<Glyph>> {
+        let mut glyphs = Vec::new();
+        let mut seen = HashSet::new();
+        let max_attempts = count as u64 * MAX_REJECTIONS;
+        for attempt in 0..max_attempts {
+            if glyphs.len() >= count {
+                break;
             }
-        };
-
-        if self.symmetry == Symmetry::Rotation180 || self.symmetry == Symmetry::Rotation90 {
-            for line in lines.clone().iter() {
-                // Reflect in the y-axis, then translate x-axis by 1.0
-                let matrix = Isometry::from_parts(
-                    Translation::from(Vector2::new(1.0, 1.0)),
-                    Rotation2::new(PI),
-                );
-
-                let start = matrix.transform_point(&line.start());
-                let end = matrix.transform_point(&line.end());
-                lines.push(Line::new(start, end));
+            let glyph = self.generate(base_seed.wrapping_add(attempt))?;
+            let key = match distinct {
+                Distinct::Identical => glyph.keys(),
+                Distinct::UnderSymmetry => dihedral_matrices()
+                    .iter()
+                    .map(|matrix| {
+                        let segments = glyph
+                            .segments
+                            .iter()
+                            .map(|segment| segment.transformed(matrix));
+                        Glyph::new(glyph.seed, segments.collect()).keys()
+                    })
+                    .min()
+                    .expect("there is always an identity matrix"),
+            };
+            if seen.insert(key) {
+                glyphs.push(glyph);
             }
-        };
+        }
+        Ok(glyphs)
+    }
 
-        if self.symmetry == Symmetry::Rotation90 {
-            for line in lines.clone().iter() {
-                let matrix = Isometry::from_parts(
-                    Translation::from(Vector2::new(1.0, 0.0)),
-                    Rotation2::new(PI / 2.0),
-                );
+    /// Generate a lighter or heavier version of the glyph for a seed.
+    ///
+    /// Heavier weights draw more lines from the same random stream, so they only add
+    /// segments to lighter ones, as long as no pipeline stage removes segments. Line limits
+    /// are not applied, and the regular weight is the glyph from `generate`.
+    pub fn generate_weight(&self, seed: u64, weight: Weight) -> Result<Glyph> {
+        self.validate()?;
+        let num_lines = (f64::from(self.num_lines) * weight.factor()).ceil() as i32;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Ok(self.finish(seed, self.propose_lines(&self.motif, &mut rng, num_lines)))
+    }
 
-                let start = matrix.transform_point(&line.start());
-                let end = matrix.transform_point(&line.end());
-                lines.push(Line::new(start, end));
-            }
-        };
+    /// Generate `count` related glyphs from one seed.
+    ///
+    /// Every glyph shares the first half of the segments proposed from `base_seed`, and adds
+    /// the rest from its own variant seed, which it keeps.
+    pub fn family(&self, base_seed: u64, count: usize) -> Result<Vec<Glyph>> {
+        self.validate()?;
+        let base = SeedDerivation::new(base_seed);
+        let core = self.propose(&self.motif, &mut ChaCha8Rng::seed_from_u64(base_seed));
+        let shared = core.len().div_ceil(2);
+        Ok((0..count as u64)
+            .map(|index| {
+                let seed = base.variant(index).seed();
+                let extra = self.propose(&self.motif, &mut ChaCha8Rng::seed_from_u64(seed));
+                let mut segments = core[..shared].to_vec();
+                segments.extend(extra.into_iter().take(core.len() - shared));
+                self.finish(seed, segments)
+            })
+            .collect())
+    }
 
-        Glyph::new(seed, lines)
+    /// Breed a glyph from two parents, keeping each of their segments with even odds.
+    ///
+    /// The kept segments are run through the pipeline again, so the child has the
+    /// alphabet's symmetry. The child takes the given seed.
+    pub fn crossover(&self, a: &Glyph, b: &Glyph, seed: u64) -> Glyph {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut seen = HashSet::new();
+        let segments = a
+            .segments
+            .iter()
+            .chain(b.segments.iter())
+            .filter(|segment| seen.insert(segment.key()))
+            .filter(|_| rng.gen())
+            .cloned()
+            .collect();
+        self.finish(seed, segments)
     }
-}
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Line {
-    start: Point,
-    end: Point,
-}
+    /// Change a glyph a little, dropping each of its segments with the odds given by `rate`,
+    /// and proposing as many new ones in their place, or one if none were dropped.
+    ///
+    /// Like `crossover`, the result is run through the pipeline again, and takes the given
+    /// seed.
+    pub fn mutate(&self, glyph: &Glyph, seed: u64, rate: f64) -> Glyph {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let rate = rate.clamp(0.0, 1.0);
+        let mut segments: Vec<Segment> = glyph
+            .segments
+            .iter()
+            .filter(|_| !rng.gen_bool(rate))
+            .cloned()
+            .collect();
+        let dropped = (glyph.segments.len() - segments.len()).max(1);
+        let mut seen: HashSet<_> = glyph.segments.iter().map(Segment::key).collect();
+        let proposed = self.propose(&self.motif, &mut rng);
+        segments.extend(
+            proposed
+                .into_iter()
+                .filter(|segment| seen.insert(segment.key()))
+                .take(dropped),
+        );
+        self.finish(seed, segments)
+    }
 
-impl Line {
-    pub fn new(start: Point, end: Point) -> Self {
-        Self { start, end }
+    /// Whether a glyph's segments are within the line count constraints.
+    fn accepts(&self, segments: &[Segment]) -> bool {
+        self.min_lines.is_none_or(|min| segments.len() >= min)
+            && self.max_lines.is_none_or(|max| segments.len() <= max)
     }
 
-    pub fn start(&self) -> Point {
-        self.start
+    /// Propose segments for a glyph, before the pipeline.
+    fn propose<M: MotifStrategy, R: Rng>(&self, motif: &M, rng: &mut R) -> Vec<Segment> {
+        self.propose_lines(motif, rng, self.num_lines)
     }
 
-    pub fn end(&self) -> Point {
-        self.end
+    /// Propose segments for a glyph with the given number of lines.
+    ///
+    /// Proposals are drawn from the stream in order, so more lines only add segments.
+    fn propose_lines<M: MotifStrategy, R: Rng>(
+        &self,
+        motif: &M,
+        rng: &mut R,
+        num_lines: i32,
+    ) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let mut seen = HashSet::new();
+
+        // Masks apply before symmetry, so should be symmetric themselves
+        let allowed = |segment: &Segment| {
+            self.grid.allows(segment.start()) && self.grid.allows(segment.end())
+        };
+        let target = num_lines.max(0) as usize;
+        let mut attempts = 0;
+        loop {
+            let finished = match self.line_count {
+                LineCount::Attempts => attempts >= num_lines,
+                LineCount::Exact(max_attempts) => {
+                    segments.len() >= target || attempts >= max_attempts
+                }
+            };
+            if finished {
+                break;
+            }
+            attempts += 1;
+
+            let previous = segments.last().map(Segment::end);
+            let proposed = match motif.propose_from(&self.grid, previous, rng) {
+                Some(Segment::Line(line)) => self
+                    .grid
+                    .fit_line(&line)
+                    .into_iter()
+                    .map(Segment::from)
+                    .filter(allowed)
+                    .collect(),
+                Some(segment) if allowed(&segment) => vec![segment],
+                _ => vec![],
+            };
+            let exact = self.line_count != LineCount::Attempts;
+            for segment in proposed {
+                if exact && segments.len() >= target {
+                    continue;
+                }
+                if (self.deduplicate || exact) && !seen.insert(segment.key()) {
+                    continue;
+                }
+                segments.push(segment);
+            }
+        }
+        segments
     }
 }
 
+/// Points arcs and curves are flattened into.
+const FLATTEN_SAMPLES: usize = 16;
+
+/// Points sampled along each segment when comparing glyphs.
+const SIMILARITY_SAMPLES: usize = 8;
+
+/// Most times a glyph is regenerated to meet the line count constraints.
+const MAX_REJECTIONS: u64 = 100;
+
+/// Rotations by quarter turns about the centre of the cell, with and without reflection.
+fn dihedral_matrices() -> Vec<Matrix3<f64>> {
+    let reflection = Matrix3::new(-1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+    (0..4)
+        .map(|turn| centre_rotation(turn, 4).to_homogeneous())
+        .flat_map(|rotation| vec![rotation, rotation * reflection])
+        .collect()
+}
+
+/// Rotation by `numerator / denominator` of a full turn about the centre of the cell.
+///
+/// Quarter turns are constructed exactly, so grid points stay on the grid.
+fn centre_rotation(numerator: u32, denominator: u32) -> Isometry<f64, U2, Rotation2<f64>> {
+    let rotation = if (4 * numerator).is_multiple_of(denominator) {
+        let (cos, sin) = match (4 * numerator / denominator) % 4 {
+            0 => (1.0, 0.0),
+            1 => (0.0, 1.0),
+            2 => (-1.0, 0.0),
+            _ => (0.0, -1.0),
+        };
+        Rotation2::from_matrix_unchecked(Matrix2::new(cos, -sin, sin, cos))
+    } else {
+        Rotation2::new(2.0 * PI * numerator as f64 / denominator as f64)
+    };
+    // Translate so that the centre is fixed
+    let centre = Vector::new(0.5, 0.5);
+    Isometry::from_parts(Translation::from(centre - rotation * centre), rotation)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn glyphs_equal_in_any_order_or_direction() {
+        let a = Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+        let b = Line::new(Point::new(0.5, 0.5), Point::new(0.5, 1.0));
+        let reversed = Line::new(b.end(), b.start());
+        let mut glyph = Glyph::new(0, vec![b.into(), a.clone().into()]);
+        assert_eq!(glyph, Glyph::new(1, vec![a.into(), reversed.into()]));
+
+        glyph.canonicalize();
+        assert_eq!(glyph.segments()[1].start(), Point::new(0.5, 0.5));
+    }
+
+    #[test]
+    fn dense_glyphs_draw_each_segment_once() {
+        let alphabet = Alphabet::new(3, 20, Symmetry::Asymmetric, Motif::Orthogonal).unwrap();
+        let repeats = |glyph: &Glyph| {
+            let keys: HashSet<_> = glyph.segments().iter().map(Segment::key).collect();
+            glyph.segments().len() - keys.len()
+        };
+        for seed in 0..20 {
+            assert_eq!(repeats(&alphabet.generate(seed).unwrap()), 0);
+        }
+        let repeating = Alphabet::builder()
+            .resolution(3)
+            .density(20)
+            .motif(Motif::Orthogonal)
+            .deduplicate(false)
+            .build()
+            .unwrap();
+        assert!(repeats(&repeating.generate(0).unwrap()) > 0);
+    }
+
+    #[test]
+    fn mirror_axis_lines_drawn_once() {
+        let alphabet = Alphabet::new(5, 3, Symmetry::Horizontal, Motif::Diagonal).unwrap();
+        let finished = |lines: Vec<Line>| {
+            let segments = lines.into_iter().map(Segment::from).collect();
+            alphabet.finish(0, segments).segments().len()
+        };
+        // Reflected onto itself in the same direction
+        let along = Line::new(Point::new(0.5, 0.0), Point::new(0.5, 1.0));
+        assert_eq!(finished(vec![along.clone()]), 1);
+        assert_eq!(
+            finished(vec![along.clone(), Line::new(along.end(), along.start())]),
+            1
+        );
+        // Reflected onto itself in the reverse direction
+        let across = Line::new(Point::new(0.25, 0.5), Point::new(0.75, 0.5));
+        assert_eq!(finished(vec![across]), 1);
+        let off = Line::new(Point::new(0.0, 0.0), Point::new(0.5, 0.5));
+        assert_eq!(finished(vec![off]), 2);
+    }
+
+    #[test]
+    fn off_centre_reflections_stay_mirror_images() {
+        let mut alphabet = Alphabet::new(4, 3, Symmetry::Horizontal, Motif::Diagonal).unwrap();
+        let third = 1.0 / 3.0;
+        alphabet.axis = Point::new(third, third);
+        let originals = [
+            Line::new(Point::new(third, 0.0), Point::new(1.0, third)),
+            Line::new(Point::new(2.0 * third, 0.0), Point::new(2.0 * third, 1.0)),
+            Line::new(Point::new(1.0, 0.0), Point::new(1.0, 1.0)),
+            Line::new(Point::new(0.0, 2.0 * third), Point::new(1.0, 1.0)),
+        ];
+        for (symmetry, axis) in [(Symmetry::Horizontal, 0), (Symmetry::Vertical, 1)] {
+            let mirror = |mut point: Point| {
+                point[axis] = 2.0 * third - point[axis];
+                point
+            };
+            let mut segments: Vec<Segment> = originals.iter().cloned().map(Segment::from).collect();
+            symmetry.apply(&alphabet, &mut segments);
+            for segment in &segments[originals.len()..] {
+                // Mirrored back, each end lies on one of the original lines
+                let (start, end) = (mirror(segment.start()), mirror(segment.end()));
+                assert!(originals.iter().any(|line| {
+                    let on_line = |point: Point| {
+                        let along = (point - line.start()).dot(&(line.end() - line.start()))
+                            / (line.end() - line.start()).norm_squared();
+                        (line.start() + (line.end() - line.start()) * along - point).norm() < 1e-9
+                            && (-1e-9..=1.0 + 1e-9).contains(&along)
+                    };
+                    on_line(start) && on_line(end)
+                }));
+                for point in [segment.start(), segment.end()] {
+                    assert!((0.0..=1.0).contains(&point.x) && (0.0..=1.0).contains(&point.y));
+                }
+            }
+        }
+
+        // Shortened along its own direction, rather than clamped a coordinate at a time
+        let mut segments = vec![Segment::from(originals[0].clone())];
+        Symmetry::Horizontal.apply(&alphabet, &mut segments);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].start(), Point::new(third, 0.0));
+        assert!((segments[1].end() - Point::new(0.0, third / 2.0)).norm() < 1e-9);
+        // Reflected wholly outside the cell, a line is dropped
+        let mut segments = vec![Segment::from(originals[2].clone())];
+        Symmetry::Horizontal.apply(&alphabet, &mut segments);
+        assert_eq!(segments.len(), 1);
+    }
+
+    #[test]
+    fn parse_composite_symmetry() {
+        assert_eq!(
+            "horizontal,rotational2".parse(),
+            Ok(Symmetry::Composite(vec![
+                Symmetry::Horizontal,
+                Symmetry::Rotational(2)
+            ]))
+        );
+    }
+
+    #[test]
+    fn batch_matches_serial_generation() {
+        let alphabet = Alphabet::new(3, 3, Symmetry::Horizontal, Motif::Diagonal).unwrap();
+        let glyphs = alphabet.generate_batch(10..20).unwrap();
+        assert_eq!(glyphs.len(), 10);
+        for (seed, glyph) in (10..20).zip(glyphs) {
+            assert_eq!(glyph.seed(), seed);
+            assert_eq!(glyph, alphabet.generate(seed).unwrap());
+        }
+    }
+
+    #[test]
+    fn generate_with_other_rngs() {
+        let alphabet = Alphabet::new(3, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let glyph = alphabet.generate(5).unwrap();
+        assert_eq!(alphabet.generate_with_rng::<ChaCha8Rng>(5).unwrap(), glyph);
+
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(1);
+        let drawn = alphabet.generate_from(&mut rng).unwrap();
+        assert_eq!(alphabet.generate(drawn.seed()).unwrap(), drawn);
+    }
+
+    #[test]
+    fn glyph_iterator_filters_by_seed_order() {
+        let alphabet = Alphabet::new(3, 3, Symmetry::Horizontal, Motif::Diagonal).unwrap();
+        let busy: Vec<u64> = alphabet
+            .glyphs(10)
+            .map(Result::unwrap)
+            .filter(|glyph| glyph.segments().len() > 4)
+            .take(3)
+            .map(|glyph| glyph.seed())
+            .collect();
+        assert_eq!(busy.len(), 3);
+        assert!(busy[0] >= 10 && busy[0] < busy[1] && busy[1] < busy[2]);
+    }
+
+    #[test]
+    fn crossover_keeps_symmetry() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Horizontal, Motif::Diagonal).unwrap();
+        let (a, b) = (alphabet.generate(1).unwrap(), alphabet.generate(2).unwrap());
+        let reflection = Matrix3::new(-1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        for seed in 0..10 {
+            let child = alphabet.crossover(&a, &b, seed);
+            assert_eq!(child.seed(), seed);
+            assert_eq!(child.transformed(&reflection), child);
+            assert_eq!(child.subtract(&a.merge(&b)).segments().len(), 0);
+        }
+    }
+
+    #[test]
+    fn mutation_changes_a_little() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Vertical, Motif::Orthogonal).unwrap();
+        let glyph = alphabet.generate(5).unwrap();
+        let reflection = Matrix3::new(1.0, 0.0, 0.0, 0.0, -1.0, 1.0, 0.0, 0.0, 1.0);
+        let keeping = alphabet.mutate(&glyph, 1, 0.0);
+        assert_eq!(keeping.seed(), 1);
+        assert_eq!(glyph.subtract(&keeping).segments().len(), 0);
+        assert_ne!(keeping, glyph);
+        for seed in 0..10 {
+            let child = alphabet.mutate(&glyph, seed, 0.5);
+            assert_eq!(child, alphabet.mutate(&glyph, seed, 0.5));
+            assert_eq!(child.transformed(&reflection), child);
+        }
+    }
+
+    #[test]
+    fn batches_report_progress() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let reports = std::sync::Mutex::new(Vec::new());
+        let glyphs = alphabet
+            .generate_batch_with_progress(10..15, &|progress| {
+                reports.lock().unwrap().push(progress)
+            })
+            .unwrap();
+        assert_eq!(glyphs, alphabet.generate_batch(10..15).unwrap());
+        let mut reports = reports.into_inner().unwrap();
+        reports.sort_by_key(|progress| progress.done);
+        assert_eq!(reports.len(), 5);
+        assert_eq!(reports[4], Progress { done: 5, total: 5 });
+        assert_eq!(reports[1].fraction(), 0.4);
+    }
+
+    #[test]
+    fn family_shares_core_segments() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Vertical, Motif::Diagonal).unwrap();
+        let family = alphabet.family(3, 4).unwrap();
+        assert_eq!(family.len(), 4);
+        let core = alphabet.propose(&alphabet.motif, &mut ChaCha8Rng::seed_from_u64(3));
+        for glyph in family.iter() {
+            assert!(glyph.keys().contains(&core[0].key()));
+        }
+        assert_ne!(family[0], family[1]);
+    }
+
+    #[test]
+    fn heavier_weights_add_segments() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Rotation90, Motif::Diagonal).unwrap();
+        for seed in 0..10 {
+            let light = alphabet.generate_weight(seed, Weight::Light).unwrap();
+            let regular = alphabet.generate_weight(seed, Weight::Regular).unwrap();
+            let bold = alphabet.generate_weight(seed, Weight::Bold).unwrap();
+            assert_eq!(regular, alphabet.generate(seed).unwrap());
+            assert!(light.subtract(&regular).segments().is_empty());
+            assert!(regular.subtract(&bold).segments().is_empty());
+        }
+    }
+
+    #[test]
+    fn parse_algorithm() {
+        assert_eq!("v1".parse(), Ok(Algorithm::V1));
+        assert!("v0".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn display_parses_back() {
+        let symmetry = Symmetry::Composite(vec![Symmetry::Horizontal, Symmetry::Rotational(3)]);
+        assert_eq!(symmetry.to_string().parse(), Ok(symmetry));
+        let lattice = Lattice::Polar {
+            rings: 4,
+            spokes: 12,
+        };
+        assert_eq!(lattice.to_string(), "polar4x12");
+        assert_eq!(Motif::Walk.to_string().parse(), Ok(Motif::Walk));
+        assert_eq!(Algorithm::V1.to_string(), "v1");
+    }
+
+    #[test]
+    fn parse_motif() {
+        assert_eq!("arcs".parse(), Ok(Motif::Arcs));
+        assert_eq!("walk".parse(), Ok(Motif::Walk));
+        assert!("Diagonal".parse::<Motif>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn alphabet_round_trips_through_json() {
+        let mut alphabet = Alphabet::new(4, 3, Symmetry::Rotational(4), Motif::Arcs).unwrap();
+        alphabet.pipeline.push(Box::new(Components::Largest));
+        let json = serde_json::to_string(&alphabet).unwrap();
+        let loaded: Alphabet = serde_json::from_str(&json).unwrap();
+
+        let glyph = alphabet.generate(7).unwrap();
+        assert_eq!(loaded.generate(7).unwrap(), glyph);
+        let json = serde_json::to_string(&glyph).unwrap();
+        assert_eq!(serde_json::from_str::<Glyph>(&json).unwrap(), glyph);
+    }
 }
@@ -0,0 +1,158 @@
+//! Records of how a sheet of glyphs was generated, to check it can be generated again.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use crate::{Algorithm, Alphabet, Error, GenerationConfig, Glyph, GlyphFilter, Result};
+
+/// Everything needed to generate a sheet's glyphs again, and a hash of each glyph's geometry
+/// to check them against.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    /// Version of symbolgen_core the glyphs were generated with
+    pub version: String,
+    /// Version of the generation algorithm, as in the settings
+    pub algorithm: Algorithm,
+    pub settings: GenerationConfig,
+    pub glyphs: Vec<ManifestGlyph>,
+    /// Filter the glyphs had to pass to be kept, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<GlyphFilter>,
+    /// Seeds passed over because their glyphs failed the filter
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<u64>,
+}
+
+/// A glyph in a manifest.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ManifestGlyph {
+    pub seed: u64,
+    /// Row of the sheet, whose alphabet generated the glyph
+    pub row: i32,
+    /// The glyph's geometry hash, as 16 hex digits
+    pub hash: String,
+}
+
+/// A glyph that generated differently from its entry in a manifest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    /// Position of the glyph in the manifest
+    pub index: usize,
+    pub seed: u64,
+    pub row: i32,
+    /// Hash of the glyph generated now, or none if it couldn't be generated
+    pub hash: Option<String>,
+}
+
+impl Manifest {
+    /// Version of symbolgen_core, recorded in manifests written now.
+    pub const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    /// A manifest of rows of glyphs, each row generated by the alphabet the settings build for
+    /// it, as on a sheet.
+    pub fn new(settings: &GenerationConfig, rows: &[Vec<Glyph>]) -> Self {
+        let glyphs = (0..)
+            .zip(rows)
+            .flat_map(|(row, glyphs)| {
+                glyphs.iter().map(move |glyph| ManifestGlyph {
+                    seed: glyph.seed(),
+                    row,
+                    hash: hex(glyph.geometry_hash()),
+                })
+            })
+            .collect();
+        Self {
+            version: Self::VERSION.to_owned(),
+            algorithm: settings.alphabet.algorithm,
+            settings: settings.clone(),
+            glyphs,
+            filter: None,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Generate every glyph again, returning those whose geometry isn't exactly the same.
+    pub fn verify(&self) -> Result<Vec<Mismatch>> {
+        let mut alphabets: BTreeMap<i32, Alphabet> = BTreeMap::new();
+        let mut mismatches = Vec::new();
+        for (index, entry) in self.glyphs.iter().enumerate() {
+            let alphabet = match alphabets.get(&entry.row) {
+                Some(alphabet) => alphabet,
+                None => {
+                    let alphabet = self.settings.alphabet.build(entry.row)?;
+                    alphabets.entry(entry.row).or_insert(alphabet)
+                }
+            };
+            let hash = alphabet
+                .generate(entry.seed)
+                .ok()
+                .map(|glyph| hex(glyph.geometry_hash()));
+            if hash.as_ref() != Some(&entry.hash) {
+                mismatches.push(Mismatch {
+                    index,
+                    seed: entry.seed,
+                    row: entry.row,
+                    hash,
+                });
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Read a manifest from JSON.
+    pub fn read<R: Read>(reader: R) -> Result<Self> {
+        serde_json::from_reader(reader).map_err(|error| Error::Manifest(error.to_string()))
+    }
+
+    /// Write the manifest as JSON.
+    pub fn write<W: Write>(&self, writer: W) -> Result<()> {
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(|error| Error::Manifest(error.to_string()))
+    }
+}
+
+/// A hash as 16 hex digits.
+fn hex(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_glyphs_verify() {
+        let settings = GenerationConfig::default();
+        let rows: Vec<Vec<Glyph>> = (0..2)
+            .map(|row| {
+                let alphabet = settings.alphabet.build(row).unwrap();
+                alphabet.generate_batch(vec![1, 2, 3]).unwrap()
+            })
+            .collect();
+        let manifest = Manifest::new(&settings, &rows);
+        assert_eq!(manifest.glyphs.len(), 6);
+        assert_eq!(manifest.glyphs[4].row, 1);
+
+        let mut json = Vec::new();
+        manifest.write(&mut json).unwrap();
+        assert!(!String::from_utf8(json.clone()).unwrap().contains("skipped"));
+        let mut manifest = Manifest::read(json.as_slice()).unwrap();
+        assert_eq!(manifest.verify(), Ok(Vec::new()));
+
+        manifest.glyphs[4].seed = 7;
+        let mismatches = manifest.verify().unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].index, 4);
+    }
+
+    #[test]
+    fn hash_tells_apart_order() {
+        use crate::{Line, Point};
+        let line = |x: f64| Line::new(Point::new(x, 0.0), Point::new(x, 1.0)).into();
+        let glyph = Glyph::new(0, vec![line(0.0), line(1.0)]);
+        let reversed = Glyph::new(0, vec![line(1.0), line(0.0)]);
+        assert_eq!(glyph, reversed);
+        assert_ne!(glyph.geometry_hash(), reversed.geometry_hash());
+        assert_eq!(glyph.geometry_hash(), glyph.clone().geometry_hash());
+    }
+}
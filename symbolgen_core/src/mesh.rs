@@ -0,0 +1,133 @@
+//! Glyphs stroked into triangle meshes, for drawing on the GPU.
+
+use lyon_tessellation::math::point;
+use lyon_tessellation::path::Path;
+use lyon_tessellation::{
+    BuffersBuilder, LineCap, LineJoin, StrokeOptions, StrokeTessellator, StrokeVertex,
+    VertexBuffers,
+};
+
+use crate::{Error, Glyph, Point, Result, Segment};
+
+/// How glyphs are stroked into meshes.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MeshOptions {
+    pub line_width: f64,
+    /// Furthest the triangles may stray from the true outline of each stroke
+    pub tolerance: f64,
+}
+
+impl Default for MeshOptions {
+    fn default() -> Self {
+        Self {
+            line_width: 0.1,
+            tolerance: 0.001,
+        }
+    }
+}
+
+/// Triangles covering a stroked glyph, as every three indices into the vertices.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mesh {
+    pub vertices: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl Glyph {
+    /// The glyph's strokes as triangles, with round caps and joins.
+    pub fn to_mesh(&self, options: &MeshOptions) -> Result<Mesh> {
+        let vertex = |at: Point| point(at.x as f32, at.y as f32);
+        let mut builder = Path::builder();
+        let mut position = None;
+        for segment in self.segments() {
+            let start = segment.start();
+            if position != Some(start) {
+                if position.is_some() {
+                    builder.end(false);
+                }
+                builder.begin(vertex(start));
+            }
+            match segment {
+                Segment::Line(line) => {
+                    builder.line_to(vertex(line.end()));
+                }
+                Segment::Arc(arc) => {
+                    for curve in arc.to_curves() {
+                        let [_, control_start, control_end, end] = curve.points();
+                        builder.cubic_bezier_to(
+                            vertex(control_start),
+                            vertex(control_end),
+                            vertex(end),
+                        );
+                    }
+                }
+                Segment::Curve(curve) => {
+                    let [_, control_start, control_end, end] = curve.points();
+                    builder.cubic_bezier_to(
+                        vertex(control_start),
+                        vertex(control_end),
+                        vertex(end),
+                    );
+                }
+            }
+            position = Some(segment.end());
+        }
+        if position.is_some() {
+            builder.end(false);
+        }
+
+        let stroke = StrokeOptions::tolerance(options.tolerance as f32)
+            .with_line_width(options.line_width as f32)
+            .with_line_cap(LineCap::Round)
+            .with_line_join(LineJoin::Round);
+        let mut buffers: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &builder.build(),
+                &stroke,
+                &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| {
+                    vertex.position().to_array()
+                }),
+            )
+            .map_err(|error| Error::Tessellation(format!("{:?}", error)))?;
+        Ok(Mesh {
+            vertices: buffers.vertices,
+            indices: buffers.indices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Line;
+
+    #[test]
+    fn mesh_covers_strokes() {
+        let glyph = Glyph::new(
+            0,
+            vec![Line::new(Point::new(0.0, 0.5), Point::new(1.0, 0.5)).into()],
+        );
+        let mesh = glyph.to_mesh(&MeshOptions::default()).unwrap();
+        assert_eq!(mesh.indices.len() % 3, 0);
+        assert!(mesh
+            .indices
+            .iter()
+            .all(|index| (*index as usize) < mesh.vertices.len()));
+        let (min, max) = mesh
+            .vertices
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), [_, y]| {
+                (min.min(*y), max.max(*y))
+            });
+        assert!((min - 0.45).abs() < 1e-3 && (max - 0.55).abs() < 1e-3);
+        assert!(Glyph::new(0, Vec::new())
+            .to_mesh(&MeshOptions::default())
+            .unwrap()
+            .indices
+            .is_empty());
+    }
+}
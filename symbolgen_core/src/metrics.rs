@@ -0,0 +1,155 @@
+//! Widths and bearings of glyphs, for setting them side by side as text or in a font.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, Glyph, Result};
+
+/// Where the widths of glyphs come from.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Spacing {
+    /// Every glyph as wide as its cell, like a typewriter's letters.
+    Monospace,
+    /// Each glyph as wide as its ink, with side bearings either side.
+    Proportional,
+}
+
+impl fmt::Display for Spacing {
+    /// The spacing's name, which parses back to the same spacing.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Spacing::Monospace => f.write_str("monospace"),
+            Spacing::Proportional => f.write_str("proportional"),
+        }
+    }
+}
+
+impl FromStr for Spacing {
+    type Err = Error;
+    fn from_str(spacing: &str) -> Result<Self> {
+        match spacing {
+            "monospace" => Ok(Spacing::Monospace),
+            "proportional" => Ok(Spacing::Proportional),
+            _ => Err(Error::parse("spacing", spacing)),
+        }
+    }
+}
+
+/// How glyphs are measured, as fractions of their cell.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct MetricsOptions {
+    pub spacing: Spacing,
+    /// Gap either side of the ink of proportionally spaced glyphs
+    pub side_bearing: f64,
+    /// Depth of the baseline glyphs sit on, down from the top of the cell
+    pub baseline: f64,
+}
+
+impl Default for MetricsOptions {
+    fn default() -> Self {
+        Self {
+            spacing: Spacing::Monospace,
+            side_bearing: 0.1,
+            baseline: 1.0,
+        }
+    }
+}
+
+/// Horizontal measurements of a glyph, and the baseline it sits on, in the units of its
+/// coordinates.
+///
+/// The glyph's origin is where the previous glyph's advance ends. Moving the glyph right by
+/// `offset` puts the left of its ink `left_side_bearing` from the origin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metrics {
+    /// Distance from the glyph's origin to the next glyph's
+    pub advance: f64,
+    /// Gap from the origin to the left of the ink
+    pub left_side_bearing: f64,
+    /// Gap from the right of the ink to the advance
+    pub right_side_bearing: f64,
+    /// Depth of the baseline, down from the top of the cell
+    pub baseline: f64,
+    /// Distance to move the glyph right, to place its ink after the origin
+    pub offset: f64,
+}
+
+impl Glyph {
+    /// Measure the glyph to set it beside others. A blank glyph's bearings split its
+    /// advance evenly.
+    pub fn metrics(&self, options: &MetricsOptions) -> Metrics {
+        let ink = self.stats().bounds.map(|(min, max)| (min.x, max.x));
+        match (options.spacing, ink) {
+            (Spacing::Monospace, Some((left, right))) => Metrics {
+                advance: 1.0,
+                left_side_bearing: left,
+                right_side_bearing: 1.0 - right,
+                baseline: options.baseline,
+                offset: 0.0,
+            },
+            (Spacing::Proportional, Some((left, right))) => Metrics {
+                advance: right - left + 2.0 * options.side_bearing,
+                left_side_bearing: options.side_bearing,
+                right_side_bearing: options.side_bearing,
+                baseline: options.baseline,
+                offset: options.side_bearing - left,
+            },
+            (spacing, _) => {
+                let advance = match spacing {
+                    Spacing::Monospace => 1.0,
+                    _ => 2.0 * options.side_bearing,
+                };
+                Metrics {
+                    advance,
+                    left_side_bearing: advance / 2.0,
+                    right_side_bearing: advance / 2.0,
+                    baseline: options.baseline,
+                    offset: 0.0,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Line, Point, Segment};
+
+    #[test]
+    fn measure_ink_or_cells() {
+        let glyph = Glyph::new(
+            0,
+            vec![Segment::Line(Line::new(
+                Point::new(0.25, 0.0),
+                Point::new(0.5, 1.0),
+            ))],
+        );
+        let monospace = glyph.metrics(&MetricsOptions::default());
+        assert_eq!(monospace.advance, 1.0);
+        assert_eq!(monospace.left_side_bearing, 0.25);
+        assert_eq!(monospace.right_side_bearing, 0.5);
+        assert_eq!(monospace.offset, 0.0);
+
+        let proportional = glyph.metrics(&MetricsOptions {
+            spacing: Spacing::Proportional,
+            ..MetricsOptions::default()
+        });
+        assert!((proportional.advance - 0.45).abs() < 1e-9);
+        assert_eq!(proportional.left_side_bearing, 0.1);
+        assert!((proportional.offset + 0.15).abs() < 1e-9);
+
+        let blank = Glyph::new(0, Vec::new()).metrics(&MetricsOptions::default());
+        assert_eq!(blank.left_side_bearing, 0.5);
+        assert_eq!(
+            "proportional".parse::<Spacing>().unwrap(),
+            Spacing::Proportional
+        );
+    }
+}
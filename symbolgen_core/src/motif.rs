@@ -0,0 +1,286 @@
+//! Strategies for proposing the segments a glyph is built from.
+
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{Arc, Curve, Edge, Error, Grid, Lattice, Line, Point, Result, Segment, Vector};
+
+/// Proposes one segment at a time for a glyph.
+///
+/// Proposed lines may leave the cell, and are fitted back inside it according to the grid's
+/// edge behaviour. Other segments are used as proposed.
+pub trait MotifStrategy {
+    /// Propose a segment on the grid, or `None` to skip this attempt.
+    fn propose<R: Rng>(&self, grid: &Grid, rng: &mut R) -> Option<Segment>;
+
+    /// Propose a segment, given where the previous segment ended.
+    ///
+    /// By default the previous segment is ignored.
+    fn propose_from<R: Rng>(
+        &self,
+        grid: &Grid,
+        _previous: Option<Point>,
+        rng: &mut R,
+    ) -> Option<Segment> {
+        self.propose(grid, rng)
+    }
+}
+
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Motif {
+    Orthogonal,
+    Diagonal,
+    /// Quarter and half circle arcs between grid points.
+    Arcs,
+    /// Diagonal lines, randomly bowed into curves.
+    Curved,
+    /// A single meandering stroke of diagonal lines.
+    Walk,
+}
+
+impl fmt::Display for Motif {
+    /// The motif's name, which parses back to the same motif.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Motif::Orthogonal => "orthogonal",
+            Motif::Diagonal => "diagonal",
+            Motif::Arcs => "arcs",
+            Motif::Curved => "curved",
+            Motif::Walk => "walk",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Motif {
+    type Err = Error;
+    fn from_str(motif: &str) -> Result<Self> {
+        match motif {
+            "orthogonal" => Ok(Motif::Orthogonal),
+            "diagonal" => Ok(Motif::Diagonal),
+            "arcs" => Ok(Motif::Arcs),
+            "curved" => Ok(Motif::Curved),
+            "walk" => Ok(Motif::Walk),
+            _ => Err(Error::parse("motif", motif)),
+        }
+    }
+}
+
+impl MotifStrategy for Motif {
+    fn propose<R: Rng>(&self, grid: &Grid, rng: &mut R) -> Option<Segment> {
+        self.propose_from(grid, None, rng)
+    }
+
+    fn propose_from<R: Rng>(
+        &self,
+        grid: &Grid,
+        previous: Option<Point>,
+        rng: &mut R,
+    ) -> Option<Segment> {
+        match self {
+            Motif::Orthogonal => Orthogonal.propose_from(grid, previous, rng),
+            Motif::Diagonal => Diagonal.propose_from(grid, previous, rng),
+            Motif::Arcs => Arcs.propose_from(grid, previous, rng),
+            Motif::Curved => Curved.propose_from(grid, previous, rng),
+            Motif::Walk => Walk.propose_from(grid, previous, rng),
+        }
+    }
+}
+
+/// Horizontal and vertical lines.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Orthogonal;
+
+impl MotifStrategy for Orthogonal {
+    fn propose<R: Rng>(&self, grid: &Grid, rng: &mut R) -> Option<Segment> {
+        if grid.lattice != Lattice::Square {
+            return grid.gen_move(None, false, rng).map(Segment::from);
+        }
+
+        let coin_flip: bool = rng.gen();
+        // Unused, but keeps the random stream in step with other motifs
+        let _coin_flip_end_point: bool = rng.gen();
+
+        // Generate a random point to start the line
+        let start_point = grid.gen_point(rng);
+        // Start with no change at all
+        let mut additive = Vector::new(0.0, 0.0);
+
+        // Either adjust x, or y, orthogonally
+        // When wrapping, edges are no different to any other point
+        let clamp = grid.edge == Edge::Clamp;
+        if coin_flip {
+            if clamp && start_point.x == 0.0 {
+                // If no x addition, add half
+                additive += Vector::new(grid.step_x, 0.0);
+            } else if clamp && (start_point.x - 1.0).abs() < f64::EPSILON {
+                // If full width, subtract half
+                additive += Vector::new(-grid.step_x, 0.0);
+            } else {
+                // If neighther, randomly adjust by up to one resolution
+                additive += Vector::new(grid.gen_adjustment_x(rng) * grid.step_x, 0.0);
+            }
+        } else {
+            // If no x addition, add half
+            if clamp && start_point.y == 0.0 {
+                additive += Vector::new(0.0, grid.step_y);
+            } else if clamp && (start_point.y - 1.0).abs() < f64::EPSILON {
+                additive += Vector::new(0.0, -grid.step_y);
+            } else {
+                // If neighther, randomly adjust by up to one resolution
+                additive += Vector::new(0.0, grid.gen_adjustment_y(rng) * grid.step_y);
+            }
+        }
+
+        // Extend the line over multiple steps in the same direction
+        let end_point = start_point + additive * grid.gen_span(rng);
+        Some(Line::new(start_point, end_point).into())
+    }
+}
+
+/// Lines in any of the eight grid directions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Diagonal;
+
+impl MotifStrategy for Diagonal {
+    fn propose<R: Rng>(&self, grid: &Grid, rng: &mut R) -> Option<Segment> {
+        if grid.lattice != Lattice::Square {
+            return grid.gen_move(None, true, rng).map(Segment::from);
+        }
+
+        let coin_flip: bool = rng.gen();
+        let coin_flip_end_point: bool = rng.gen();
+
+        // Generate a random point to start the line
+        let start_point = grid.gen_point(rng);
+        Some(diagonal_from(grid, start_point, coin_flip, coin_flip_end_point, rng).into())
+    }
+}
+
+/// Move from the start point in any of the eight grid directions.
+fn diagonal_from<R: Rng>(
+    grid: &Grid,
+    start_point: Point,
+    coin_flip: bool,
+    coin_flip_end_point: bool,
+    rng: &mut R,
+) -> Line {
+    // Start with no change at all
+    let mut additive = Vector::new(0.0, 0.0);
+
+    // Adjust x and y independently
+    if coin_flip {
+        additive += Vector::new(grid.gen_adjustment_x(rng) * grid.step_x, 0.0);
+    };
+    if coin_flip_end_point {
+        additive += Vector::new(0.0, grid.gen_adjustment_y(rng) * grid.step_y);
+    };
+
+    // Extend the line over multiple steps in the same direction
+    let end_point = start_point + additive * grid.gen_span(rng);
+    Line::new(start_point, end_point)
+}
+
+/// Quarter and half circle arcs of one step radius around a grid point.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Arcs;
+
+impl MotifStrategy for Arcs {
+    fn propose<R: Rng>(&self, grid: &Grid, rng: &mut R) -> Option<Segment> {
+        let centre = match grid.lattice {
+            Lattice::Square => grid.gen_point(rng),
+            _ => *grid.nodes().choose(rng)?,
+        };
+        let start_angle = rng.gen_range(0, 4) as f64 * PI / 2.0;
+        let sweep = if rng.gen() { PI / 2.0 } else { PI };
+        // Arcs are circles in the unit cell, so only meet grid points when the steps are equal
+        let radius = grid.step_x.min(grid.step_y);
+        let arc = Arc::new(centre, radius, start_angle, start_angle + sweep);
+
+        let (min, max) = arc.bounds();
+        // Allow for floating point error in the extreme points
+        let tolerance = radius / 2.0;
+        if min.x < -tolerance || min.y < -tolerance {
+            return None;
+        }
+        if max.x > 1.0 + tolerance || max.y > 1.0 + tolerance {
+            return None;
+        }
+        Some(arc.into())
+    }
+}
+
+/// Diagonal lines, bowed to one side by half a step.
+///
+/// Curves are always clamped to the cell, whatever the edge behaviour.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Curved;
+
+impl MotifStrategy for Curved {
+    fn propose<R: Rng>(&self, grid: &Grid, rng: &mut R) -> Option<Segment> {
+        let line = match Diagonal.propose(grid, rng)? {
+            Segment::Line(line) => grid.clamp_line(&line),
+            segment => return Some(segment),
+        };
+        if line.start() == line.end() {
+            return None;
+        }
+
+        let direction = line.end() - line.start();
+        let side = if rng.gen() { 1.0 } else { -1.0 };
+        let bow = Vector::new(-direction.y, direction.x).normalize()
+            * side
+            * grid.step_x.min(grid.step_y)
+            / 2.0;
+        let control = |t: f64| {
+            let point = line.start() + direction * t + bow;
+            Point::new(point.x.clamp(0.0, 1.0), point.y.clamp(0.0, 1.0))
+        };
+        Some(
+            Curve::new(
+                line.start(),
+                control(1.0 / 3.0),
+                control(2.0 / 3.0),
+                line.end(),
+            )
+            .into(),
+        )
+    }
+}
+
+/// Diagonal lines, each starting where the last one ended.
+///
+/// Glyphs are drawn as a single stroke, apart from any symmetric copies.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Walk;
+
+impl MotifStrategy for Walk {
+    fn propose<R: Rng>(&self, grid: &Grid, rng: &mut R) -> Option<Segment> {
+        self.propose_from(grid, None, rng)
+    }
+
+    fn propose_from<R: Rng>(
+        &self,
+        grid: &Grid,
+        previous: Option<Point>,
+        rng: &mut R,
+    ) -> Option<Segment> {
+        if grid.lattice != Lattice::Square {
+            return grid.gen_move(previous, true, rng).map(Segment::from);
+        }
+
+        let start_point = match previous {
+            Some(point) => point,
+            None => grid.gen_point(rng),
+        };
+        let coin_flip: bool = rng.gen();
+        let coin_flip_end_point: bool = rng.gen();
+        Some(diagonal_from(grid, start_point, coin_flip, coin_flip_end_point, rng).into())
+    }
+}
@@ -0,0 +1,350 @@
+//! Glyphs as planar graphs, split wherever segments cross.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::segment::FixedPoint;
+use crate::{Line, Point, Segment, Vector};
+
+/// Allowance for floating point error in line parameters.
+const TOLERANCE: f64 = 1e-9;
+
+/// Parallel lines filling enclosed regions.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hatch {
+    /// Direction of the lines, in radians from the x-axis
+    pub angle: f64,
+    /// Distance between lines, as a fraction of the cell
+    pub spacing: f64,
+}
+
+impl Hatch {
+    pub fn new(angle: f64, spacing: f64) -> Self {
+        Self { angle, spacing }
+    }
+
+    /// Lines filling a polygon, using the even-odd rule.
+    ///
+    /// Lines are spaced from the origin, so neighbouring regions hatch continuously.
+    pub fn fill(&self, polygon: &[Point]) -> Vec<Line> {
+        if polygon.len() < 3 || self.spacing <= 0.0 {
+            return Vec::new();
+        }
+        let (along, across) = (
+            Vector::new(self.angle.cos(), self.angle.sin()),
+            Vector::new(-self.angle.sin(), self.angle.cos()),
+        );
+        let offset = |point: &Point| point.coords.dot(&across);
+        let edges: Vec<(Point, Point)> = polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+            .collect();
+        let lowest = polygon.iter().map(offset).fold(f64::INFINITY, f64::min);
+        let highest = polygon.iter().map(offset).fold(f64::NEG_INFINITY, f64::max);
+
+        let mut lines = Vec::new();
+        // Skip lines touching the extremes, which would only retrace the outline
+        let first = (lowest / self.spacing).floor() as i64 + 1;
+        let last = (highest / self.spacing).ceil() as i64 - 1;
+        for index in first..=last {
+            let height = index as f64 * self.spacing;
+            // Where this hatch line crosses each edge, counting each vertex once
+            let mut crossings: Vec<Point> = edges
+                .iter()
+                .filter_map(|(a, b)| {
+                    let (low, high) = (offset(a), offset(b));
+                    if (low <= height) == (high <= height) {
+                        return None;
+                    }
+                    Some(a + (b - a) * ((height - low) / (high - low)))
+                })
+                .collect();
+            crossings.sort_by(|a, b| {
+                a.coords
+                    .dot(&along)
+                    .partial_cmp(&b.coords.dot(&along))
+                    .unwrap_or(Ordering::Equal)
+            });
+            for pair in crossings.chunks(2) {
+                if let [start, end] = pair {
+                    if start != end {
+                        lines.push(Line::new(*start, *end));
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// The drawing of a glyph as straight edges meeting only at their ends.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlanarGraph {
+    /// Points where edges meet or end
+    pub vertices: Vec<Point>,
+    /// Pairs of vertex indices joined by a straight edge
+    pub edges: Vec<(usize, usize)>,
+    /// Enclosed regions, as loops of vertex indices around their outer boundary
+    pub faces: Vec<Vec<usize>>,
+}
+
+impl PlanarGraph {
+    /// Split segments wherever they cross or touch, flattening arcs and curves into
+    /// `samples` points each.
+    pub(crate) fn new(segments: &[Segment], samples: usize) -> Self {
+        let pieces: Vec<(Point, Point)> = segments
+            .iter()
+            .flat_map(|segment| {
+                let points = match segment {
+                    Segment::Line(line) => vec![line.start(), line.end()],
+                    segment => segment.sample(samples),
+                };
+                points
+                    .windows(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        // Parameters along each piece at which it meets another
+        let mut splits: Vec<Vec<f64>> = vec![vec![0.0, 1.0]; pieces.len()];
+        for first in 0..pieces.len() {
+            for second in first + 1..pieces.len() {
+                for (t, u) in crossings(pieces[first], pieces[second]) {
+                    splits[first].push(t);
+                    splits[second].push(u);
+                }
+            }
+        }
+
+        let mut graph = PlanarGraph::default();
+        let mut indices: HashMap<FixedPoint, usize> = HashMap::new();
+        let mut seen = HashSet::new();
+        for ((start, end), mut splits) in pieces.into_iter().zip(splits) {
+            splits.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            let mut vertex = |point: Point| {
+                let next = indices.len();
+                *indices.entry(point.into()).or_insert_with(|| {
+                    graph.vertices.push(point);
+                    next
+                })
+            };
+            let vertices: Vec<usize> = splits
+                .iter()
+                .map(|t| vertex(start + (end - start) * *t))
+                .collect();
+            for pair in vertices.windows(2) {
+                let edge = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                if edge.0 != edge.1 && seen.insert(edge) {
+                    graph.edges.push(edge);
+                }
+            }
+        }
+        graph.faces = graph.find_faces();
+        graph
+    }
+
+    /// Each edge as a line between its vertices.
+    pub fn lines(&self) -> Vec<Line> {
+        self.edges
+            .iter()
+            .map(|(start, end)| Line::new(self.vertices[*start], self.vertices[*end]))
+            .collect()
+    }
+
+    /// The outline of each enclosed region.
+    pub fn regions(&self) -> Vec<Vec<Point>> {
+        self.faces
+            .iter()
+            .map(|face| face.iter().map(|vertex| self.vertices[*vertex]).collect())
+            .collect()
+    }
+
+    /// Area enclosed by a loop of vertices, positive if anticlockwise with y upwards.
+    pub fn area(&self, face: &[usize]) -> f64 {
+        let points: Vec<Point> = face.iter().map(|vertex| self.vertices[*vertex]).collect();
+        let twice_area: f64 = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum();
+        twice_area / 2.0
+    }
+
+    /// Trace the boundary of every region, keeping to the right-most turn at each vertex.
+    ///
+    /// Bounded regions come out with positive area, and the unbounded region around each
+    /// connected group with negative area.
+    fn find_faces(&self) -> Vec<Vec<usize>> {
+        // Neighbours of each vertex, in order of angle
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); self.vertices.len()];
+        for (start, end) in self.edges.iter() {
+            neighbours[*start].push(*end);
+            neighbours[*end].push(*start);
+        }
+        let angle = |from: usize, to: usize| {
+            let direction: Vector = self.vertices[to] - self.vertices[from];
+            direction.y.atan2(direction.x)
+        };
+        for (vertex, around) in neighbours.iter_mut().enumerate() {
+            around.sort_by(|a, b| {
+                angle(vertex, *a)
+                    .partial_cmp(&angle(vertex, *b))
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        let mut visited = HashSet::new();
+        let mut faces = Vec::new();
+        for (start, end) in self
+            .edges
+            .iter()
+            .flat_map(|(start, end)| vec![(*start, *end), (*end, *start)])
+        {
+            let mut face = Vec::new();
+            let (mut from, mut to) = (start, end);
+            while visited.insert((from, to)) {
+                face.push(from);
+                // Turn to the neighbour just before the one we came from
+                let around = &neighbours[to];
+                let back = around
+                    .iter()
+                    .position(|vertex| *vertex == from)
+                    .expect("edges are in both neighbour lists");
+                let next = around[(back + around.len() - 1) % around.len()];
+                from = to;
+                to = next;
+            }
+            if !face.is_empty() && self.area(&face) > TOLERANCE {
+                faces.push(face);
+            }
+        }
+        faces
+    }
+}
+
+/// Parameters along each of two straight pieces at which they meet.
+fn crossings((a, b): (Point, Point), (c, d): (Point, Point)) -> Vec<(f64, f64)> {
+    let cross = |v: Vector, w: Vector| v.x * w.y - v.y * w.x;
+    let (r, s) = (b - a, d - c);
+    let denominator = cross(r, s);
+    let within = |t: f64| (-TOLERANCE..=1.0 + TOLERANCE).contains(&t);
+    if denominator.abs() > TOLERANCE * r.norm() * s.norm() {
+        let t = cross(c - a, s) / denominator;
+        let u = cross(c - a, r) / denominator;
+        return if within(t) && within(u) {
+            vec![(t.clamp(0.0, 1.0), u.clamp(0.0, 1.0))]
+        } else {
+            vec![]
+        };
+    }
+    if cross(c - a, r).abs() > TOLERANCE * r.norm().max(TOLERANCE) {
+        // Parallel, but not on the same line
+        return vec![];
+    }
+    // Overlapping pieces meet at each end lying on the other
+    let along = |point: Point, start: Point, direction: Vector| {
+        (point - start).dot(&direction) / direction.norm_squared().max(TOLERANCE)
+    };
+    let mut meetings = Vec::new();
+    for (point, u) in [(c, 0.0), (d, 1.0)].iter() {
+        let t = along(*point, a, r);
+        if within(t) {
+            meetings.push((t.clamp(0.0, 1.0), *u));
+        }
+    }
+    for (point, t) in [(a, 0.0), (b, 1.0)].iter() {
+        let u = along(*point, c, s);
+        if within(u) {
+            meetings.push((*t, u.clamp(0.0, 1.0)));
+        }
+    }
+    meetings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(from: (f64, f64), to: (f64, f64)) -> Segment {
+        Line::new(Point::new(from.0, from.1), Point::new(to.0, to.1)).into()
+    }
+
+    #[test]
+    fn crossing_lines_split_where_they_meet() {
+        let graph = PlanarGraph::new(
+            &[line((0.0, 0.0), (1.0, 1.0)), line((0.0, 1.0), (1.0, 0.0))],
+            8,
+        );
+        assert_eq!(graph.vertices.len(), 5);
+        assert!(graph.vertices.contains(&Point::new(0.5, 0.5)));
+        assert_eq!(graph.edges.len(), 4);
+        assert!(graph.faces.is_empty());
+    }
+
+    #[test]
+    fn squares_enclose_one_face() {
+        let square = [
+            line((0.0, 0.0), (1.0, 0.0)),
+            line((1.0, 1.0), (1.0, 0.0)),
+            line((1.0, 1.0), (0.0, 1.0)),
+            line((0.0, 0.0), (0.0, 1.0)),
+        ];
+        let graph = PlanarGraph::new(&square, 8);
+        assert_eq!(graph.faces.len(), 1);
+        assert!((graph.area(&graph.faces[0]) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn overlapping_lines_split_at_each_others_ends() {
+        let graph = PlanarGraph::new(
+            &[line((0.0, 0.0), (0.75, 0.0)), line((0.25, 0.0), (1.0, 0.0))],
+            8,
+        );
+        let mut xs: Vec<f64> = graph.vertices.iter().map(|vertex| vertex.x).collect();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(xs, [0.0, 0.25, 0.75, 1.0]);
+        let mut lengths: Vec<f64> = graph
+            .lines()
+            .iter()
+            .map(|line| (line.end() - line.start()).norm())
+            .collect();
+        lengths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(lengths, [0.25, 0.25, 0.5]);
+    }
+
+    fn polygon(corners: &[(f64, f64)]) -> Vec<Point> {
+        corners.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    #[test]
+    fn hatches_fill_squares() {
+        let square = polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        let lines = Hatch::new(0.0, 0.25).fill(&square);
+        assert_eq!(lines.len(), 3);
+        for line in &lines {
+            assert!(((line.end() - line.start()).norm() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn hatches_cross_vertices_on_their_lines_once() {
+        let diamond = polygon(&[(0.5, 0.0), (1.0, 0.5), (0.5, 1.0), (0.0, 0.5)]);
+        let lines = Hatch::new(0.0, 0.5).fill(&diamond);
+        assert_eq!(lines.len(), 1);
+        let ends = [lines[0].start(), lines[0].end()];
+        assert!(ends.contains(&Point::new(0.0, 0.5)));
+        assert!(ends.contains(&Point::new(1.0, 0.5)));
+
+        // A notch touching the line from above splits it in two
+        let notched = polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.5, 0.5), (0.0, 1.0)]);
+        let lines = Hatch::new(0.0, 0.5).fill(&notched);
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            assert!(((line.end() - line.start()).norm() - 0.5).abs() < 1e-12);
+        }
+    }
+}
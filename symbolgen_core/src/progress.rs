@@ -0,0 +1,21 @@
+//! Reports of how far through long batches of work things are, to show progress bars with.
+
+/// How much of a batch of work is done, as passed to progress callbacks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Progress {
+    /// Items finished so far
+    pub done: usize,
+    /// Items in the whole batch
+    pub total: usize,
+}
+
+impl Progress {
+    /// Fraction of the batch done, from 0 to 1. An empty batch is all done.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.done as f64 / self.total as f64
+        }
+    }
+}
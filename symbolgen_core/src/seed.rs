@@ -0,0 +1,88 @@
+//! Deterministic seeds for every part of a project, from a single seed.
+
+/// Keeps the seeds derived for different purposes apart.
+const GLYPH: u64 = 1;
+const VARIANT: u64 = 2;
+const RETRY: u64 = 3;
+
+/// A seed, and the seeds derived from it.
+///
+/// Derived seeds are a hash of the parent seed, the kind of derivation and an index, so
+/// neighbouring indices and different kinds never collide in practice. Derivations are the
+/// same on every platform and in every release.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeedDerivation {
+    seed: u64,
+}
+
+impl SeedDerivation {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The seed for the glyph at `index` in a project.
+    pub fn glyph(&self, index: u64) -> Self {
+        self.derive(GLYPH, index)
+    }
+
+    /// The seed for a variant of this glyph.
+    pub fn variant(&self, index: u64) -> Self {
+        self.derive(VARIANT, index)
+    }
+
+    /// The seed for another attempt at generating this glyph.
+    pub fn retry(&self, attempt: u64) -> Self {
+        self.derive(RETRY, attempt)
+    }
+
+    /// A seed for some other purpose, kept apart from other purposes by `kind`.
+    pub fn derive(&self, kind: u64, index: u64) -> Self {
+        Self::new(mix(mix(self.seed ^ mix(kind)).wrapping_add(index)))
+    }
+}
+
+impl From<u64> for SeedDerivation {
+    fn from(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
+
+impl From<SeedDerivation> for u64 {
+    fn from(derivation: SeedDerivation) -> Self {
+        derivation.seed
+    }
+}
+
+/// The SplitMix64 finaliser, spreading every bit of the input across the output.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn derived_seeds_do_not_collide() {
+        let project = SeedDerivation::new(42);
+        let mut seen = HashSet::new();
+        for glyph in 0..100 {
+            let glyph = project.glyph(glyph);
+            assert!(seen.insert(glyph.seed()));
+            for index in 0..10 {
+                assert!(seen.insert(glyph.variant(index).seed()));
+                assert!(seen.insert(glyph.retry(index).seed()));
+            }
+        }
+        assert_eq!(project.glyph(7), SeedDerivation::new(42).glyph(7));
+    }
+}
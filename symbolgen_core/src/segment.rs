@@ -0,0 +1,567 @@
+//! Primitives that glyphs are drawn with.
+
+use std::f64::consts::PI;
+
+use nalgebra::base::Matrix3;
+
+use crate::{Point, Vector};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Segment {
+    Line(Line),
+    Arc(Arc),
+    Curve(Curve),
+}
+
+impl Segment {
+    pub fn start(&self) -> Point {
+        match self {
+            Segment::Line(line) => line.start(),
+            Segment::Arc(arc) => arc.start(),
+            Segment::Curve(curve) => curve.start(),
+        }
+    }
+
+    pub fn end(&self) -> Point {
+        match self {
+            Segment::Line(line) => line.end(),
+            Segment::Arc(arc) => arc.end(),
+            Segment::Curve(curve) => curve.end(),
+        }
+    }
+
+    /// The point a fraction `t` of the way along the segment, from 0 at the start to 1 at the end.
+    pub fn point_at(&self, t: f64) -> Point {
+        match self {
+            Segment::Line(line) => line.start() + (line.end() - line.start()) * t,
+            Segment::Arc(arc) => {
+                arc.point_at(arc.start_angle() + (arc.end_angle() - arc.start_angle()) * t)
+            }
+            Segment::Curve(curve) => {
+                let [p0, p1, p2, p3] = curve.points();
+                let u = 1.0 - t;
+                Point::from(
+                    p0.coords * u.powi(3)
+                        + p1.coords * 3.0 * u.powi(2) * t
+                        + p2.coords * 3.0 * u * t.powi(2)
+                        + p3.coords * t.powi(3),
+                )
+            }
+        }
+    }
+
+    /// The part of the segment between two fractions of the way along it, `from` before `to`.
+    pub fn between(&self, from: f64, to: f64) -> Segment {
+        match self {
+            Segment::Line(_) => Line::new(self.point_at(from), self.point_at(to)).into(),
+            Segment::Arc(arc) => {
+                let sweep = arc.end_angle() - arc.start_angle();
+                Arc::new(
+                    arc.centre(),
+                    arc.radius(),
+                    arc.start_angle() + sweep * from,
+                    arc.start_angle() + sweep * to,
+                )
+                .into()
+            }
+            Segment::Curve(curve) => {
+                let [p0, p1, p2, p3] = curve.points();
+                let tangent = |t: f64| {
+                    let u = 1.0 - t;
+                    ((p1 - p0) * u.powi(2) + (p2 - p1) * 2.0 * u * t + (p3 - p2) * t.powi(2)) * 3.0
+                };
+                let (start, end) = (self.point_at(from), self.point_at(to));
+                let handle = (to - from) / 3.0;
+                Curve::new(
+                    start,
+                    start + tangent(from) * handle,
+                    end - tangent(to) * handle,
+                    end,
+                )
+                .into()
+            }
+        }
+    }
+
+    /// Length along the segment, measured between the given number of sample points.
+    pub fn length(&self, samples: usize) -> f64 {
+        self.sample(samples)
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).norm())
+            .sum()
+    }
+
+    /// Points evenly spaced along the segment, including both ends.
+    pub fn sample(&self, points: usize) -> Vec<Point> {
+        let intervals = points.max(2) - 1;
+        (0..=intervals)
+            .map(|index| self.point_at(index as f64 / intervals as f64))
+            .collect()
+    }
+
+    /// Whether this segment has no extent.
+    pub fn is_degenerate(&self) -> bool {
+        match self {
+            Segment::Line(line) => line.start() == line.end(),
+            Segment::Arc(arc) => arc.centre == arc.start,
+            Segment::Curve(curve) => curve.points().iter().all(|point| *point == curve.start()),
+        }
+    }
+
+    /// Apply an isometry or uniform scaling, given as a homogeneous matrix.
+    pub fn transformed(&self, matrix: &Matrix3<f64>) -> Segment {
+        match self {
+            Segment::Line(line) => Segment::Line(Line::new(
+                matrix.transform_point(&line.start()),
+                matrix.transform_point(&line.end()),
+            )),
+            Segment::Arc(arc) => {
+                let centre = matrix.transform_point(&arc.centre());
+                let start = matrix.transform_point(&arc.start());
+                let end = matrix.transform_point(&arc.end());
+                // Reflections reverse the direction of travel
+                let determinant = matrix[(0, 0)] * matrix[(1, 1)] - matrix[(0, 1)] * matrix[(1, 0)];
+                if determinant < 0.0 {
+                    Segment::Arc(Arc::through(centre, end, start))
+                } else {
+                    Segment::Arc(Arc::through(centre, start, end))
+                }
+            }
+            Segment::Curve(curve) => {
+                Segment::Curve(curve.map_points(|point| matrix.transform_point(&point)))
+            }
+        }
+    }
+
+    /// The start and end points, rounded to whole units of the cell.
+    pub(crate) fn fixed_ends(&self) -> (FixedPoint, FixedPoint) {
+        match self {
+            Segment::Line(line) => (line.start, line.end),
+            Segment::Arc(arc) => (arc.start, arc.end),
+            Segment::Curve(curve) => (curve.start, curve.end),
+        }
+    }
+
+    /// Quantised defining points, the same for identical segments whichever way they are drawn.
+    ///
+    /// Lines, arcs and curves have different numbers of points, so never share a key.
+    pub(crate) fn key(&self) -> Vec<(i64, i64)> {
+        match self {
+            Segment::Line(line) => {
+                let mut key = vec![line.start.into(), line.end.into()];
+                key.sort_unstable();
+                key
+            }
+            // Arcs are always drawn in the same direction
+            Segment::Arc(arc) => arc
+                .fixed_points()
+                .iter()
+                .map(|point| (*point).into())
+                .collect(),
+            Segment::Curve(curve) => {
+                let forward: Vec<_> = curve
+                    .fixed_points()
+                    .iter()
+                    .map(|point| (*point).into())
+                    .collect();
+                let reversed: Vec<_> = forward.iter().rev().copied().collect();
+                forward.min(reversed)
+            }
+        }
+    }
+
+    /// The same segment, drawn in a consistent direction.
+    ///
+    /// Lines and curves start from whichever end has the smaller coordinates.
+    pub fn canonical(&self) -> Segment {
+        match self {
+            Segment::Line(line) if line.end < line.start => Segment::Line(Line {
+                start: line.end,
+                end: line.start,
+            }),
+            Segment::Curve(curve)
+                if curve
+                    .fixed_points()
+                    .iter()
+                    .rev()
+                    .lt(curve.fixed_points().iter()) =>
+            {
+                Segment::Curve(Curve {
+                    start: curve.end,
+                    control_start: curve.control_end,
+                    control_end: curve.control_start,
+                    end: curve.start,
+                })
+            }
+            segment => segment.clone(),
+        }
+    }
+
+    /// Move each defining point of this segment, keeping its direction of travel.
+    pub fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Segment {
+        match self {
+            Segment::Line(line) => Segment::Line(Line::new(f(line.start()), f(line.end()))),
+            Segment::Arc(arc) => {
+                Segment::Arc(Arc::through(f(arc.centre()), f(arc.start()), f(arc.end())))
+            }
+            Segment::Curve(curve) => Segment::Curve(curve.map_points(f)),
+        }
+    }
+}
+
+/// Divisions of the cell that the points of segments are stored in.
+///
+/// Divisible by every whole number up to 16, so the points of square grids with up to 17
+/// points a side, and their reflections and rotations, are represented exactly.
+pub const UNITS: i64 = 720_720;
+
+/// A point stored as whole units of the cell, so that comparison and hashing are exact.
+///
+/// Converting to and from `Point` rounds to the nearest unit, and is the same on every
+/// platform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Point", into = "Point"))]
+pub(crate) struct FixedPoint {
+    x: i64,
+    y: i64,
+}
+
+impl From<Point> for FixedPoint {
+    fn from(point: Point) -> Self {
+        let units = UNITS as f64;
+        Self {
+            x: (point.x * units).round() as i64,
+            y: (point.y * units).round() as i64,
+        }
+    }
+}
+
+impl From<FixedPoint> for Point {
+    fn from(point: FixedPoint) -> Self {
+        let units = UNITS as f64;
+        Point::new(point.x as f64 / units, point.y as f64 / units)
+    }
+}
+
+impl From<FixedPoint> for (i64, i64) {
+    fn from(point: FixedPoint) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl From<Line> for Segment {
+    fn from(line: Line) -> Self {
+        Segment::Line(line)
+    }
+}
+
+impl From<Arc> for Segment {
+    fn from(arc: Arc) -> Self {
+        Segment::Arc(arc)
+    }
+}
+
+impl From<Curve> for Segment {
+    fn from(curve: Curve) -> Self {
+        Segment::Curve(curve)
+    }
+}
+
+/// A straight line, with end points stored exactly in units of the cell.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line {
+    start: FixedPoint,
+    end: FixedPoint,
+}
+
+impl Line {
+    pub fn new(start: Point, end: Point) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    pub fn start(&self) -> Point {
+        self.start.into()
+    }
+
+    pub fn end(&self) -> Point {
+        self.end.into()
+    }
+}
+
+/// A circular arc, drawn in the direction of increasing angle, with its centre and end points
+/// stored exactly in units of the cell.
+///
+/// The radius is the distance from the centre to the start point, and the angles are found
+/// from the points when drawn. An arc ending where it starts is a full circle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arc {
+    centre: FixedPoint,
+    start: FixedPoint,
+    end: FixedPoint,
+}
+
+impl Arc {
+    /// Construct the arc around `centre` from `start_angle` round to `end_angle`.
+    ///
+    /// An end at or before the start is reached by carrying on round, so arcs sweep a full turn
+    /// at most.
+    pub fn new(centre: Point, radius: f64, start_angle: f64, end_angle: f64) -> Self {
+        let point_at = |angle: f64| centre + Vector::new(angle.cos(), angle.sin()) * radius;
+        Self {
+            centre: centre.into(),
+            start: point_at(start_angle).into(),
+            end: point_at(end_angle).into(),
+        }
+    }
+
+    /// Construct the arc around `centre` from `start` to `end`.
+    ///
+    /// The radius is taken from the start point.
+    pub fn through(centre: Point, start: Point, end: Point) -> Self {
+        let radius = (start - centre).norm();
+        // An end at the centre is taken to be at angle zero
+        let direction = (end - centre).try_normalize(0.0).unwrap_or_else(Vector::x);
+        Self {
+            centre: centre.into(),
+            start: start.into(),
+            end: (centre + direction * radius).into(),
+        }
+    }
+
+    pub fn centre(&self) -> Point {
+        self.centre.into()
+    }
+
+    pub fn radius(&self) -> f64 {
+        (self.start() - self.centre()).norm()
+    }
+
+    /// Angle of the start point, in radians.
+    pub fn start_angle(&self) -> f64 {
+        self.angle_of(self.start())
+    }
+
+    /// Angle of the end point, in radians, greater than the start angle by up to a full turn.
+    pub fn end_angle(&self) -> f64 {
+        let start_angle = self.start_angle();
+        let sweep = (self.angle_of(self.end()) - start_angle).rem_euclid(2.0 * PI);
+        start_angle + if sweep > 0.0 { sweep } else { 2.0 * PI }
+    }
+
+    fn angle_of(&self, point: Point) -> f64 {
+        let offset = point - self.centre();
+        offset.y.atan2(offset.x)
+    }
+
+    /// The point on the arc at the given angle.
+    pub fn point_at(&self, angle: f64) -> Point {
+        self.centre() + Vector::new(angle.cos(), angle.sin()) * self.radius()
+    }
+
+    pub fn start(&self) -> Point {
+        self.start.into()
+    }
+
+    pub fn end(&self) -> Point {
+        self.end.into()
+    }
+
+    fn fixed_points(&self) -> [FixedPoint; 3] {
+        [self.centre, self.start, self.end]
+    }
+
+    /// Cubic Bézier curves closely following the arc, one for each quarter turn or part of one.
+    pub fn to_curves(&self) -> Vec<Curve> {
+        let (start_angle, end_angle) = (self.start_angle(), self.end_angle());
+        let sweep = end_angle - start_angle;
+        let pieces = (sweep / (PI / 2.0) - 1e-9).ceil().max(1.0) as usize;
+        let angle = sweep / pieces as f64;
+        // Distance from each end to its control point
+        let handle = 4.0 / 3.0 * (angle / 4.0).tan() * self.radius();
+        let tangent = |angle: f64| Vector::new(-angle.sin(), angle.cos());
+        // The arc's own end points, so the curves join what it joins exactly
+        let point_at = |piece: usize| match piece {
+            0 => self.start(),
+            piece if piece == pieces => self.end(),
+            piece => self.point_at(start_angle + angle * piece as f64),
+        };
+        (0..pieces)
+            .map(|piece| {
+                let piece_start_angle = start_angle + angle * piece as f64;
+                let piece_end_angle = piece_start_angle + angle;
+                let (start, end) = (point_at(piece), point_at(piece + 1));
+                Curve::new(
+                    start,
+                    start + tangent(piece_start_angle) * handle,
+                    end - tangent(piece_end_angle) * handle,
+                    end,
+                )
+            })
+            .collect()
+    }
+
+    /// The smallest and largest coordinates the arc reaches.
+    pub fn bounds(&self) -> (Point, Point) {
+        let mut points = vec![self.start(), self.end()];
+        // Include any extreme points the arc passes through
+        let first = (self.start_angle() / (PI / 2.0)).ceil() as i32;
+        let last = (self.end_angle() / (PI / 2.0)).floor() as i32;
+        for quarter in first..=last {
+            points.push(self.point_at(quarter as f64 * PI / 2.0));
+        }
+        let min = points
+            .iter()
+            .fold(Point::new(f64::INFINITY, f64::INFINITY), |min, point| {
+                Point::new(min.x.min(point.x), min.y.min(point.y))
+            });
+        let max = points.iter().fold(
+            Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            |max, point| Point::new(max.x.max(point.x), max.y.max(point.y)),
+        );
+        (min, max)
+    }
+}
+
+/// A cubic Bézier curve, with points stored exactly in units of the cell.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Curve {
+    start: FixedPoint,
+    control_start: FixedPoint,
+    control_end: FixedPoint,
+    end: FixedPoint,
+}
+
+impl Curve {
+    pub fn new(start: Point, control_start: Point, control_end: Point, end: Point) -> Self {
+        Self {
+            start: start.into(),
+            control_start: control_start.into(),
+            control_end: control_end.into(),
+            end: end.into(),
+        }
+    }
+
+    pub fn start(&self) -> Point {
+        self.start.into()
+    }
+
+    /// Control point for the start of the curve.
+    pub fn control_start(&self) -> Point {
+        self.control_start.into()
+    }
+
+    /// Control point for the end of the curve.
+    pub fn control_end(&self) -> Point {
+        self.control_end.into()
+    }
+
+    pub fn end(&self) -> Point {
+        self.end.into()
+    }
+
+    /// All four points, in order.
+    pub fn points(&self) -> [Point; 4] {
+        [
+            self.start(),
+            self.control_start(),
+            self.control_end(),
+            self.end(),
+        ]
+    }
+
+    fn fixed_points(&self) -> [FixedPoint; 4] {
+        [self.start, self.control_start, self.control_end, self.end]
+    }
+
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self {
+        let [start, control_start, control_end, end] = self.points();
+        Self::new(f(start), f(control_start), f(control_end), f(end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Point, expected: Point) {
+        assert!(
+            (actual - expected).norm() < 1e-9,
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn reflecting_twice_is_exact() {
+        let reflections = [
+            Matrix3::new(-1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0),
+            Matrix3::new(0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0),
+        ];
+        let segments: Vec<Segment> = vec![
+            Line::new(Point::new(1.0 / 3.0, 2.0 / 7.0), Point::new(5.0 / 6.0, 0.1)).into(),
+            Arc::new(Point::new(1.0 / 3.0, 0.5), 1.0 / 6.0, 0.3, 2.0).into(),
+        ];
+        for reflection in reflections.iter() {
+            for segment in segments.iter() {
+                let back = segment.transformed(reflection).transformed(reflection);
+                assert_eq!(back.key(), segment.key());
+                assert_eq!(&back, segment);
+                if let (Segment::Arc(back), Segment::Arc(arc)) = (&back, segment) {
+                    // Stored the same, so drawn the same
+                    assert_eq!(
+                        (back.radius(), back.start_angle(), back.end_angle()),
+                        (arc.radius(), arc.start_angle(), arc.end_angle())
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn arcs_sweep_round_to_their_end() {
+        let centre = Point::new(0.5, 0.5);
+        let wrapped = Arc::new(centre, 0.5, 0.0, -PI / 2.0);
+        assert!((wrapped.end_angle() - 1.5 * PI).abs() < 1e-12);
+        assert_near(wrapped.end(), Point::new(0.5, 0.0));
+        let full = Arc::new(centre, 0.5, 1.0, 1.0);
+        assert_eq!(full.end(), full.start());
+        assert!((full.end_angle() - full.start_angle() - 2.0 * PI).abs() < 1e-12);
+        // Sweeps over a full turn go round once
+        let over = Arc::new(centre, 0.5, 0.0, 7.0);
+        assert!((over.end_angle() - (7.0 - 2.0 * PI)).abs() < 1e-5);
+
+        // Angles too large to step round a turn at a time still make arcs
+        let far = Arc::new(centre, 0.5, 1e20, 0.0);
+        assert!(far.end_angle() > far.start_angle());
+        let infinite = Arc::new(centre, 0.5, f64::INFINITY, 0.0);
+        assert!(infinite.end_angle() > infinite.start_angle());
+    }
+
+    #[test]
+    fn arc_bounds_reach_extreme_points() {
+        let centre = Point::new(0.5, 0.5);
+        let quarter = Arc::new(centre, 0.5, 0.0, PI / 2.0).bounds();
+        assert_near(quarter.0, Point::new(0.5, 0.5));
+        assert_near(quarter.1, Point::new(1.0, 1.0));
+
+        // The top of the circle is passed on the way round
+        let half = Arc::new(centre, 0.5, 0.0, PI).bounds();
+        assert_near(half.0, Point::new(0.0, 0.5));
+        assert_near(half.1, Point::new(1.0, 1.0));
+
+        // Crossing angle zero passes the right of the circle
+        let wrapped = Arc::new(centre, 0.5, 1.5 * PI, PI / 2.0).bounds();
+        assert_near(wrapped.0, Point::new(0.5, 0.0));
+        assert_near(wrapped.1, Point::new(1.0, 1.0));
+    }
+}
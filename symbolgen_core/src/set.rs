@@ -0,0 +1,198 @@
+//! Sets of glyphs picked out by name, each an alphabet's settings and a seed, kept in a TOML
+//! set file.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::{Alphabet, AlphabetConfig, Error, Glyph, Result};
+
+/// First character of Unicode's private use area, where glyphs without characters of their
+/// own are put.
+const PRIVATE_USE: u32 = 0xE000;
+
+/// Glyphs with names, like `ampersand` or `water-rune`.
+///
+/// Set files are laid out as:
+///
+/// ```toml
+/// # Settings for glyphs that don't name others, as a config file's [alphabet]
+/// [alphabet]
+/// motif = "arcs"
+///
+/// # Other settings, by name
+/// [alphabets.runes]
+/// motif = "walk"
+///
+/// [[glyphs]]
+/// name = "ampersand"
+/// seed = 17
+/// character = "&"
+///
+/// [[glyphs]]
+/// name = "water-rune"
+/// seed = 240
+/// alphabet = "runes"
+/// row = 3
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamedSet {
+    /// Settings for glyphs that don't name others
+    pub alphabet: AlphabetConfig,
+    /// Other settings, by name
+    pub alphabets: BTreeMap<String, AlphabetConfig>,
+    pub glyphs: Vec<NamedGlyph>,
+}
+
+/// A glyph in a named set.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamedGlyph {
+    pub name: String,
+    pub seed: u64,
+    /// Row of a sheet whose alphabet generates the glyph, as for a config file
+    #[serde(default = "NamedGlyph::default_row")]
+    pub row: i32,
+    /// Name of the settings in the set's `alphabets` to use, rather than its own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alphabet: Option<String>,
+    /// Character the glyph stands for, such as when typed in a font
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub character: Option<char>,
+}
+
+impl NamedGlyph {
+    fn default_row() -> i32 {
+        2
+    }
+}
+
+impl NamedSet {
+    /// Parse a set from TOML, checking every glyph's name is its own and any settings it
+    /// names are in the set.
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let set: Self = toml::from_str(toml).map_err(|error| Error::Config(error.to_string()))?;
+        let mut names = HashSet::new();
+        for glyph in &set.glyphs {
+            if !names.insert(&glyph.name) {
+                return Err(Error::Config(format!(
+                    "More than one glyph named '{}'",
+                    glyph.name
+                )));
+            }
+            set.settings(glyph)?;
+        }
+        Ok(set)
+    }
+
+    /// Read a set from a TOML file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .map_err(|error| Error::Config(format!("{}: {}", path.display(), error)))?;
+        Self::from_toml(&toml)
+    }
+
+    /// The set as TOML, which loads back to the same set.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|error| Error::Config(error.to_string()))
+    }
+
+    /// The glyph with the given name, if there is one.
+    pub fn get(&self, name: &str) -> Option<&NamedGlyph> {
+        self.glyphs.iter().find(|glyph| glyph.name == name)
+    }
+
+    /// The settings that generate a glyph.
+    pub fn settings(&self, glyph: &NamedGlyph) -> Result<&AlphabetConfig> {
+        match &glyph.alphabet {
+            Some(name) => self.alphabets.get(name).ok_or_else(|| {
+                Error::Config(format!(
+                    "Glyph '{}' uses settings '{}', which aren't in the set",
+                    glyph.name, name
+                ))
+            }),
+            None => Ok(&self.alphabet),
+        }
+    }
+
+    /// Generate every glyph, in the order they are listed, each with its alphabet.
+    pub fn generate(&self) -> Result<Vec<(Alphabet, Glyph)>> {
+        self.glyphs
+            .iter()
+            .map(|named| {
+                let alphabet = self.settings(named)?.build(named.row)?;
+                let glyph = alphabet.generate(named.seed)?;
+                Ok((alphabet, glyph))
+            })
+            .collect()
+    }
+
+    /// The character each glyph stands for, in the order they are listed. Glyphs without
+    /// characters of their own take the next unused ones in the private use area.
+    pub fn characters(&self) -> Vec<char> {
+        let taken: HashSet<char> = self.glyphs.iter().filter_map(|g| g.character).collect();
+        let mut spare = (PRIVATE_USE..)
+            .filter_map(char::from_u32)
+            .filter(|character| !taken.contains(character));
+        self.glyphs
+            .iter()
+            .map(|glyph| {
+                glyph
+                    .character
+                    .unwrap_or_else(|| spare.next().expect("Private use area has room"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Motif;
+
+    const SET: &str = r#"
+[alphabet]
+motif = "arcs"
+
+[alphabets.runes]
+motif = "walk"
+
+[[glyphs]]
+name = "ampersand"
+seed = 17
+character = "&"
+
+[[glyphs]]
+name = "water-rune"
+seed = 240
+alphabet = "runes"
+row = 3
+"#;
+
+    #[test]
+    fn parse_named_sets() {
+        let set = NamedSet::from_toml(SET).unwrap();
+        let rune = set.get("water-rune").unwrap();
+        assert_eq!(rune.row, 3);
+        assert_eq!(set.settings(rune).unwrap().motif, Motif::Walk);
+        assert_eq!(set.get("ampersand").unwrap().row, 2);
+        assert_eq!(set.characters(), ['&', '\u{E000}']);
+        assert_eq!(NamedSet::from_toml(&set.to_toml().unwrap()).unwrap(), set);
+
+        let glyphs = set.generate().unwrap();
+        assert_eq!(glyphs[1].1.seed(), 240);
+        assert_eq!(
+            glyphs[0].1,
+            set.alphabet.build(2).unwrap().generate(17).unwrap()
+        );
+    }
+
+    #[test]
+    fn reject_unknown_and_repeated_names() {
+        let unknown = SET.replace("alphabet = \"runes\"", "alphabet = \"fire\"");
+        assert!(NamedSet::from_toml(&unknown).is_err());
+        let repeated = SET.replace("water-rune", "ampersand");
+        assert!(NamedSet::from_toml(&repeated).is_err());
+    }
+}
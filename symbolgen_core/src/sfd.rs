@@ -0,0 +1,162 @@
+//! Glyphs as a FontForge spline font database, a single text file FontForge opens and
+//! generates fonts from, without compiling one here.
+
+use std::fmt::Write as _;
+
+use font_kurbo::{BezPath, PathEl, Point, QuadBez};
+
+use crate::font::{font_glyphs, postscript_name, FontGlyph};
+use crate::{FontOptions, Glyph};
+
+/// A FontForge SFD file of the glyphs, each typed as its character.
+///
+/// Glyphs have the same outlines and widths as in the UFO and TrueType fonts, with curves left
+/// cubic, and each has a comment of the seed it was generated from. Ascent and descent split
+/// the em at the baseline.
+pub fn to_sfd(glyphs: &[(char, Glyph)], options: &FontOptions) -> String {
+    let font_glyphs = font_glyphs(glyphs, options);
+    let em = i64::from(options.units_per_em);
+    let ascent = ((options.metrics.baseline * em as f64).round() as i64).clamp(0, em);
+
+    let mut sfd = String::new();
+    let mut line = |text: String| {
+        sfd.push_str(&text);
+        sfd.push('\n');
+    };
+    line("SplineFontDB: 3.0".to_owned());
+    line(format!("FontName: {}", postscript_name(&options.family)));
+    line(format!("FullName: {}", one_line(&options.family)));
+    line(format!("FamilyName: {}", one_line(&options.family)));
+    line("Weight: Regular".to_owned());
+    line("Version: 1.000".to_owned());
+    line("ItalicAngle: 0".to_owned());
+    line(format!("UnderlinePosition: {}", -em / 10));
+    line(format!("UnderlineWidth: {}", em / 20));
+    line(format!("Ascent: {}", ascent));
+    line(format!("Descent: {}", em - ascent));
+    line("LayerCount: 2".to_owned());
+    line(r#"Layer: 0 0 "Back" 1"#.to_owned());
+    line(r#"Layer: 1 0 "Fore" 0"#.to_owned());
+    line("Encoding: Custom".to_owned());
+    line(format!(
+        "BeginChars: {} {}",
+        font_glyphs.len(),
+        font_glyphs.len()
+    ));
+    for (index, glyph) in font_glyphs.iter().enumerate() {
+        line(String::new());
+        line(char_entry(index, glyph));
+    }
+    line("EndChars".to_owned());
+    line("EndSplineFont".to_owned());
+    sfd
+}
+
+/// A glyph as an SFD character, with its seed as a comment.
+fn char_entry(index: usize, glyph: &FontGlyph) -> String {
+    let unicode = glyph
+        .character
+        .map_or(-1, |character| i64::from(character as u32));
+    let mut entry = format!(
+        "StartChar: {}\nEncoding: {} {} {}\nWidth: {}\nFlags: W\n",
+        glyph.name, index, unicode, index, glyph.advance
+    );
+    if let Some(seed) = glyph.seed {
+        writeln!(entry, "Comment: \"seed {}\"", seed).expect("writing to a string can't fail");
+    }
+    entry.push_str("LayerCount: 2\nFore\nSplineSet\n");
+    entry.push_str(&spline_set(&glyph.outline));
+    entry.push_str("EndSplineSet\nEndChar");
+    entry
+}
+
+/// Each contour of a path in SFD's spline notation, all closed by ending where they started.
+fn spline_set(path: &BezPath) -> String {
+    let mut set = String::new();
+    let mut start = None;
+    let mut position = Point::ZERO;
+    // A contour not ending where it started closes with a line back
+    let close = |set: &mut String, start: &mut Option<Point>, position: Point| {
+        if let Some(start) = start.take() {
+            if position != start {
+                set.push_str(&format!("{} l 1\n", coordinates(&[start])));
+            }
+        }
+    };
+    for element in path.elements() {
+        match *element {
+            PathEl::MoveTo(point) => {
+                close(&mut set, &mut start, position);
+                set.push_str(&format!("{} m 1\n", coordinates(&[point])));
+                start = Some(point);
+                position = point;
+            }
+            PathEl::LineTo(point) => {
+                set.push_str(&format!("{} l 1\n", coordinates(&[point])));
+                position = point;
+            }
+            PathEl::QuadTo(control, point) => {
+                let cubic = QuadBez::new(position, control, point).raise();
+                set.push_str(&format!(
+                    "{} c 1\n",
+                    coordinates(&[cubic.p1, cubic.p2, cubic.p3])
+                ));
+                position = point;
+            }
+            PathEl::CurveTo(control_start, control_end, point) => {
+                set.push_str(&format!(
+                    "{} c 1\n",
+                    coordinates(&[control_start, control_end, point])
+                ));
+                position = point;
+            }
+            PathEl::ClosePath => close(&mut set, &mut start, position),
+        }
+    }
+    close(&mut set, &mut start, position);
+    set
+}
+
+/// Points as SFD writes them, rounded to font units.
+fn coordinates(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{} {}", point.x.round() as i64, point.y.round() as i64))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Text that fills the rest of an SFD line, without breaking onto the next.
+fn one_line(text: &str) -> String {
+    text.replace(|character: char| character.is_control(), " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alphabet, Motif, Symmetry};
+
+    #[test]
+    fn sfd_chars_comment_their_seeds() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let glyphs = vec![('a', alphabet.generate(17).unwrap())];
+        let sfd = to_sfd(&glyphs, &FontOptions::default());
+
+        assert!(sfd.starts_with("SplineFontDB: 3.0\nFontName: Symbolgen-Regular\n"));
+        assert!(sfd.contains("Ascent: 1000\nDescent: 0\n"));
+        assert!(sfd.contains("BeginChars: 3 3\n"));
+        assert!(sfd.contains("StartChar: .notdef\nEncoding: 0 -1 0\nWidth: 1000\n"));
+        assert!(sfd.contains("StartChar: space\nEncoding: 1 32 1\nWidth: 750\n"));
+        assert!(sfd.contains(
+            "StartChar: uni0061\nEncoding: 2 97 2\nWidth: 1080\nFlags: W\nComment: \"seed 17\"\n"
+        ));
+        assert!(sfd.contains(" c 1\n"));
+        assert!(sfd.ends_with("EndChar\nEndChars\nEndSplineFont\n"));
+
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((10.0, 0.0));
+        path.line_to((10.0, 10.0));
+        assert_eq!(spline_set(&path), "0 0 m 1\n10 0 l 1\n10 10 l 1\n0 0 l 1\n");
+    }
+}
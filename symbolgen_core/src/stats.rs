@@ -0,0 +1,167 @@
+//! Measurements of glyphs, for tuning alphabet settings by the numbers.
+
+use crate::{Glyph, Point, Segment, FLATTEN_SAMPLES};
+
+/// Cells along each side of the grid ink coverage is measured on.
+const COVERAGE_CELLS: usize = 32;
+/// Index of the last cell along each side of the grid.
+const LAST_CELL: usize = COVERAGE_CELLS - 1;
+
+/// Where a cell of the coverage grid moves to.
+type CellMap = fn(usize, usize) -> (usize, usize);
+
+/// Ways of reflecting or turning the coverage grid onto itself.
+const GRID_SYMMETRIES: [CellMap; 6] = [
+    |x, y| (LAST_CELL - x, y),
+    |x, y| (x, LAST_CELL - y),
+    |x, y| (LAST_CELL - x, LAST_CELL - y),
+    |x, y| (y, x),
+    |x, y| (LAST_CELL - y, LAST_CELL - x),
+    |x, y| (LAST_CELL - y, x),
+];
+
+/// Measurements of a glyph, in the units of its coordinates, so of a unit cell for glyphs
+/// as generated.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlyphStats {
+    /// Number of segments
+    pub segments: usize,
+    /// Total length of every segment
+    pub length: f64,
+    /// Fraction of a 32 by 32 grid over the unit cell that the segments pass through
+    pub coverage: f64,
+    /// How nearly the glyph matches itself reflected or turned about the centre of the unit
+    /// cell, from 0 to 1, at its most symmetric
+    pub symmetry: f64,
+    /// Smallest and largest coordinates the segments reach, or none if there are none
+    pub bounds: Option<(Point, Point)>,
+}
+
+impl GlyphStats {
+    /// Width and height of the bounds, or zero for an empty glyph.
+    pub fn extent(&self) -> (f64, f64) {
+        self.bounds
+            .map_or((0.0, 0.0), |(min, max)| (max.x - min.x, max.y - min.y))
+    }
+}
+
+impl Glyph {
+    /// Measure the glyph's segments.
+    pub fn stats(&self) -> GlyphStats {
+        let segments = self.segments();
+        let length = segments.iter().fold(0.0, |length, segment| {
+            length + segment.length(FLATTEN_SAMPLES)
+        });
+
+        let mut cells = vec![false; COVERAGE_CELLS * COVERAGE_CELLS];
+        let cell = |value: f64| (value * COVERAGE_CELLS as f64).floor() as usize;
+        let mut points: Vec<Point> = Vec::new();
+        for segment in segments {
+            // Close enough together to touch every cell the segment passes through
+            let samples = (segment.length(FLATTEN_SAMPLES) * COVERAGE_CELLS as f64 * 2.0).ceil();
+            for point in segment.sample(samples as usize + 2) {
+                if (0.0..=1.0).contains(&point.x) && (0.0..=1.0).contains(&point.y) {
+                    let (x, y) = (
+                        cell(point.x).min(COVERAGE_CELLS - 1),
+                        cell(point.y).min(COVERAGE_CELLS - 1),
+                    );
+                    cells[y * COVERAGE_CELLS + x] = true;
+                }
+            }
+            match segment {
+                Segment::Line(line) => points.extend([line.start(), line.end()]),
+                Segment::Arc(arc) => {
+                    let (min, max) = arc.bounds();
+                    points.extend([min, max]);
+                }
+                Segment::Curve(_) => points.extend(segment.sample(FLATTEN_SAMPLES)),
+            }
+        }
+        let covered = cells.iter().filter(|covered| **covered).count();
+
+        let bounds = points.split_first().map(|(first, rest)| {
+            rest.iter().fold((*first, *first), |(min, max), point| {
+                (
+                    Point::new(min.x.min(point.x), min.y.min(point.y)),
+                    Point::new(max.x.max(point.x), max.y.max(point.y)),
+                )
+            })
+        });
+        GlyphStats {
+            segments: segments.len(),
+            length,
+            coverage: covered as f64 / cells.len() as f64,
+            symmetry: symmetry(&cells),
+            bounds,
+        }
+    }
+}
+
+/// The most of the covered cells that land on or next to a covered cell when the grid is
+/// reflected or turned, as a fraction. Neighbours count as a match, since a line on the
+/// border between cells only covers the cells on one side of it.
+fn symmetry(cells: &[bool]) -> f64 {
+    let covered: Vec<(usize, usize)> = (0..cells.len())
+        .filter(|cell| cells[*cell])
+        .map(|cell| (cell % COVERAGE_CELLS, cell / COVERAGE_CELLS))
+        .collect();
+    if covered.is_empty() {
+        return 0.0;
+    }
+    let near = |(x, y): (usize, usize)| {
+        (y.saturating_sub(1)..=(y + 1).min(LAST_CELL)).any(|y| {
+            (x.saturating_sub(1)..=(x + 1).min(LAST_CELL)).any(|x| cells[y * COVERAGE_CELLS + x])
+        })
+    };
+    GRID_SYMMETRIES
+        .iter()
+        .map(|map| {
+            let matched = covered.iter().filter(|(x, y)| near(map(*x, *y))).count();
+            matched as f64 / covered.len() as f64
+        })
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Arc, Line};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn measure_lines_and_arcs() {
+        let glyph = Glyph::new(
+            0,
+            vec![
+                Line::new(Point::new(0.0, 0.5), Point::new(1.0, 0.5)).into(),
+                Arc::new(Point::new(0.5, 0.5), 0.25, 0.0, PI).into(),
+            ],
+        );
+        let stats = glyph.stats();
+        assert_eq!(stats.segments, 2);
+        assert!((stats.length - (1.0 + PI / 4.0)).abs() < 0.01);
+        assert_eq!(
+            stats.bounds,
+            Some((Point::new(0.0, 0.5), Point::new(1.0, 0.75)))
+        );
+        assert_eq!(stats.extent(), (1.0, 0.25));
+        // A full row of cells, and some more under it
+        assert!(stats.coverage > 1.0 / 32.0);
+        assert!(stats.coverage < 3.0 / 32.0);
+        // Mirrored left to right
+        assert_eq!(stats.symmetry, 1.0);
+
+        let corner = Glyph::new(
+            0,
+            vec![Line::new(Point::new(0.0, 0.0), Point::new(0.25, 0.0)).into()],
+        );
+        assert!(corner.stats().symmetry < 0.5);
+
+        let empty = Glyph::new(0, Vec::new()).stats();
+        assert_eq!(
+            (empty.length, empty.coverage, empty.symmetry, empty.bounds),
+            (0.0, 0.0, 0.0, None)
+        );
+    }
+}
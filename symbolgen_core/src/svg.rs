@@ -0,0 +1,387 @@
+//! Glyphs as SVG paths and documents.
+
+use std::f64::consts::PI;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+#[cfg(feature = "svg")]
+use crate::SheetLayout;
+use crate::{Arc, Glyph, Point, Segment, Unit};
+
+/// The shape drawn at the ends of each stroke.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
+    }
+}
+
+/// How an SVG document of glyphs is laid out and stroked.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SvgOptions {
+    /// Width of the document
+    pub width: f64,
+    /// Height of the document
+    pub height: f64,
+    /// Area of the drawing shown, as x, y, width and height. Defaults to the whole document.
+    pub view_box: Option<[f64; 4]>,
+    pub line_width: f64,
+    pub line_cap: LineCap,
+    /// Colour of the strokes, as red, green, blue and alpha
+    pub color: [u8; 4],
+    /// Colour to fill the background with, if any
+    pub background: Option<[u8; 4]>,
+    /// Unit of the width and height, which the view box is stretched over
+    pub unit: Unit,
+    /// Colour of each glyph in turn, in place of `color`. Glyphs past the end of the list
+    /// take `color`.
+    pub glyph_colors: Vec<[u8; 4]>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            height: 1.0,
+            view_box: None,
+            line_width: 0.1,
+            line_cap: LineCap::Round,
+            color: [0, 0, 0, 255],
+            background: None,
+            unit: Unit::Px,
+            glyph_colors: Vec::new(),
+        }
+    }
+}
+
+impl Glyph {
+    /// The segments as SVG path data, starting a new subpath wherever a segment doesn't
+    /// continue from the last.
+    pub fn to_svg_path(&self) -> String {
+        let mut data = String::new();
+        let mut position: Option<Point> = None;
+        for segment in self.segments() {
+            let start = segment.start();
+            if position != Some(start) {
+                command(&mut data, 'M', &[start]);
+            }
+            match segment {
+                Segment::Line(line) => command(&mut data, 'L', &[line.end()]),
+                Segment::Arc(arc) => arc_commands(&mut data, arc),
+                Segment::Curve(curve) => command(&mut data, 'C', &curve.points()[1..]),
+            }
+            position = Some(segment.end());
+        }
+        data
+    }
+}
+
+/// An SVG document drawing each glyph as a stroked path.
+///
+/// Glyphs are drawn where they are, so should already be scaled and moved into place.
+pub fn to_svg(glyphs: &[Glyph], options: &SvgOptions) -> String {
+    let [x, y, width, height] =
+        options
+            .view_box
+            .unwrap_or([0.0, 0.0, options.width, options.height]);
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}{}" height="{}{}" viewBox="{} {} {} {}">"#,
+        number(options.width),
+        options.unit.suffix(),
+        number(options.height),
+        options.unit.suffix(),
+        number(x),
+        number(y),
+        number(width),
+        number(height),
+    )
+    .expect("writing to a string can't fail");
+    if let Some(background) = options.background {
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+            number(x),
+            number(y),
+            number(width),
+            number(height),
+            paint("fill", background),
+        )
+        .expect("writing to a string can't fail");
+    }
+    writeln!(
+        svg,
+        r#"<g fill="none" {} stroke-width="{}" stroke-linecap="{}" stroke-linejoin="round">"#,
+        paint("stroke", options.color),
+        number(options.line_width),
+        options.line_cap.as_str(),
+    )
+    .expect("writing to a string can't fail");
+    for (index, glyph) in glyphs.iter().enumerate() {
+        if glyph.segments().is_empty() {
+            continue;
+        }
+        let stroke = match options.glyph_colors.get(index) {
+            Some(&color) if color != options.color => format!(" {}", paint("stroke", color)),
+            _ => String::new(),
+        };
+        writeln!(svg, r#"<path d="{}"{}/>"#, glyph.to_svg_path(), stroke)
+            .expect("writing to a string can't fail");
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// An `svg` crate document drawing each glyph, as for `to_svg`, for adding to or building
+/// on with that crate.
+#[cfg(feature = "svg")]
+pub fn to_svg_document(glyphs: &[Glyph], options: &SvgOptions) -> ::svg::Document {
+    let [x, y, width, height] =
+        options
+            .view_box
+            .unwrap_or([0.0, 0.0, options.width, options.height]);
+    let [.., alpha] = options.color;
+    let group = glyphs
+        .iter()
+        .enumerate()
+        .filter(|(_, glyph)| !glyph.segments().is_empty())
+        .fold(
+            ::svg::node::element::Group::new()
+                .set("fill", "none")
+                .set("stroke", hex(options.color))
+                .set("stroke-opacity", number(f64::from(alpha) / 255.0))
+                .set("stroke-width", number(options.line_width))
+                .set("stroke-linecap", options.line_cap.as_str())
+                .set("stroke-linejoin", "round"),
+            |group, (index, glyph)| match options.glyph_colors.get(index) {
+                Some(&color) if color != options.color => {
+                    let [.., alpha] = color;
+                    group.add(
+                        glyph
+                            .to_svg_node()
+                            .set("stroke", hex(color))
+                            .set("stroke-opacity", number(f64::from(alpha) / 255.0)),
+                    )
+                }
+                _ => group.add(glyph.to_svg_node()),
+            },
+        );
+    let mut document = ::svg::Document::new()
+        .set("width", number(options.width) + options.unit.suffix())
+        .set("height", number(options.height) + options.unit.suffix())
+        .set(
+            "viewBox",
+            format!(
+                "{} {} {} {}",
+                number(x),
+                number(y),
+                number(width),
+                number(height)
+            ),
+        );
+    if let Some(background) = options.background {
+        let [.., alpha] = background;
+        document = document.add(
+            ::svg::node::element::Rectangle::new()
+                .set("x", number(x))
+                .set("y", number(y))
+                .set("width", number(width))
+                .set("height", number(height))
+                .set("fill", hex(background))
+                .set("fill-opacity", number(f64::from(alpha) / 255.0)),
+        );
+    }
+    document.add(group)
+}
+
+#[cfg(feature = "svg")]
+impl Glyph {
+    /// The glyph as an `svg` crate path element, to add to a document. It has no stroke of
+    /// its own, so should be added to an element that sets one.
+    pub fn to_svg_node(&self) -> ::svg::node::element::Path {
+        ::svg::node::element::Path::new().set("d", self.to_svg_path())
+    }
+}
+
+#[cfg(feature = "svg")]
+impl SheetLayout {
+    /// An `svg` crate document of rows of unscaled glyphs, laid out on the whole sheet.
+    pub fn to_svg_document(&self, rows: &[Vec<Glyph>]) -> ::svg::Document {
+        let glyphs: Vec<Glyph> = (0..)
+            .zip(rows)
+            .flat_map(|(row, glyphs)| {
+                (0..).zip(glyphs).map(move |(column, glyph)| {
+                    glyph
+                        .scaled(self.scale)
+                        .translated(self.offset(row, column))
+                })
+            })
+            .collect();
+        let options = SvgOptions {
+            width: self.width(),
+            height: self.height(),
+            line_width: self.line_width,
+            unit: self.units,
+            ..SvgOptions::default()
+        };
+        to_svg_document(&glyphs, &options)
+    }
+}
+
+/// Write an SVG document drawing each glyph, as for `to_svg`.
+pub fn write_svg<W: Write>(
+    mut writer: W,
+    glyphs: &[Glyph],
+    options: &SvgOptions,
+) -> io::Result<()> {
+    writer.write_all(to_svg(glyphs, options).as_bytes())
+}
+
+/// Append a path command and its points.
+fn command(data: &mut String, letter: char, points: &[Point]) {
+    if !data.is_empty() {
+        data.push(' ');
+    }
+    data.push(letter);
+    for point in points {
+        write!(data, " {} {}", number(point.x), number(point.y))
+            .expect("writing to a string can't fail");
+    }
+}
+
+/// Append an arc, in pieces of at most half a turn so the end points are never ambiguous.
+fn arc_commands(data: &mut String, arc: &Arc) {
+    let sweep = arc.end_angle() - arc.start_angle();
+    let pieces = (sweep / PI - 1e-9).ceil().max(1.0) as usize;
+    let radius = number(arc.radius());
+    for piece in 1..=pieces {
+        let end = if piece == pieces {
+            arc.end()
+        } else {
+            arc.point_at(arc.start_angle() + sweep * piece as f64 / pieces as f64)
+        };
+        // Angles increase clockwise with y downwards, which is the positive sweep direction
+        write!(
+            data,
+            " A {} {} 0 0 1 {} {}",
+            radius,
+            radius,
+            number(end.x),
+            number(end.y)
+        )
+        .expect("writing to a string can't fail");
+    }
+}
+
+/// An attribute painting a colour, with its opacity if it isn't opaque.
+fn paint(attribute: &str, color: [u8; 4]) -> String {
+    let [.., alpha] = color;
+    let mut paint = format!(r#"{}="{}""#, attribute, hex(color));
+    if alpha < 255 {
+        write!(
+            paint,
+            r#" {}-opacity="{}""#,
+            attribute,
+            number(f64::from(alpha) / 255.0)
+        )
+        .expect("writing to a string can't fail");
+    }
+    paint
+}
+
+/// A colour as `#rrggbb`, without its alpha.
+fn hex([red, green, blue, _]: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+/// A coordinate to a few decimal places, without trailing zeros.
+fn number(value: f64) -> String {
+    let text = format!("{:.4}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_owned(),
+        text => text.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Line;
+
+    #[test]
+    fn path_data_joins_continuing_segments() {
+        let glyph = Glyph::new(
+            0,
+            vec![
+                Line::new(Point::new(0.0, 0.0), Point::new(0.5, 0.0)).into(),
+                Line::new(Point::new(0.5, 0.0), Point::new(0.5, 1.0)).into(),
+                Arc::new(Point::new(0.5, 0.5), 0.5, 0.0, PI).into(),
+            ],
+        );
+        assert_eq!(
+            glyph.to_svg_path(),
+            "M 0 0 L 0.5 0 L 0.5 1 M 1 0.5 A 0.5 0.5 0 0 1 0 0.5"
+        );
+        let svg = to_svg(&[glyph], &SvgOptions::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"stroke-linecap="round""#));
+        assert!(!svg.contains("<rect"));
+
+        let options = SvgOptions {
+            color: [255, 0, 0, 128],
+            background: Some([0, 0, 0, 255]),
+            ..SvgOptions::default()
+        };
+        let svg = to_svg(&[], &options);
+        assert!(svg.contains(r##"<rect x="0" y="0" width="1" height="1" fill="#000000"/>"##));
+        assert!(svg.contains(r##"stroke="#ff0000" stroke-opacity="0.502""##));
+
+        let line = Glyph::new(
+            0,
+            vec![Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0)).into()],
+        );
+        let options = SvgOptions {
+            glyph_colors: vec![[0, 0, 0, 255], [0, 0, 255, 255]],
+            ..SvgOptions::default()
+        };
+        let svg = to_svg(&[line.clone(), line], &options);
+        assert!(svg.contains(r#"<path d="M 0 0 L 1 0"/>"#));
+        assert!(svg.contains(r##"<path d="M 0 0 L 1 0" stroke="#0000ff"/>"##));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn document_matches_string() {
+        let glyph = Glyph::new(
+            0,
+            vec![Line::new(Point::new(0.0, 0.0), Point::new(0.5, 1.0)).into()],
+        );
+        let document =
+            to_svg_document(std::slice::from_ref(&glyph), &SvgOptions::default()).to_string();
+        assert!(document.contains(r#"viewBox="0 0 1 1""#));
+        assert!(document.contains(r#"d="M 0 0 L 0.5 1""#));
+        assert!(document.contains(r#"stroke-linecap="round""#));
+
+        let sheet = SheetLayout::default()
+            .to_svg_document(&[vec![glyph]])
+            .to_string();
+        assert!(sheet.contains(r#"d="M 25 25 L 37.5 50""#));
+    }
+}
@@ -0,0 +1,222 @@
+//! Sheets comparing alphabet settings, with one setting varying down the rows and another
+//! across the columns.
+
+use std::str::FromStr;
+
+use crate::{Alphabet, AlphabetConfig, Edge, Error, Lattice, Motif, Result, Symmetry};
+
+/// An alphabet setting, and the values it takes along one side of a sweep.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SweepAxis {
+    Resolution(Vec<i32>),
+    Density(Vec<i32>),
+    Motif(Vec<Motif>),
+    Symmetry(Vec<Symmetry>),
+    Edge(Vec<Edge>),
+    Lattice(Vec<Lattice>),
+}
+
+impl SweepAxis {
+    /// Name of the setting, as used on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SweepAxis::Resolution(_) => "resolution",
+            SweepAxis::Density(_) => "density",
+            SweepAxis::Motif(_) => "motif",
+            SweepAxis::Symmetry(_) => "symmetry",
+            SweepAxis::Edge(_) => "edge",
+            SweepAxis::Lattice(_) => "lattice",
+        }
+    }
+
+    /// The number of values the setting takes.
+    pub fn len(&self) -> usize {
+        match self {
+            SweepAxis::Resolution(values) | SweepAxis::Density(values) => values.len(),
+            SweepAxis::Motif(values) => values.len(),
+            SweepAxis::Symmetry(values) => values.len(),
+            SweepAxis::Edge(values) => values.len(),
+            SweepAxis::Lattice(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The setting and its value at `index`, as on the command line, like `density=2`.
+    pub fn label(&self, index: usize) -> String {
+        let value = match self {
+            SweepAxis::Resolution(values) | SweepAxis::Density(values) => values[index].to_string(),
+            SweepAxis::Motif(values) => values[index].to_string(),
+            SweepAxis::Symmetry(values) => values[index].to_string(),
+            SweepAxis::Edge(values) => values[index].to_string(),
+            SweepAxis::Lattice(values) => values[index].to_string(),
+        };
+        format!("{}={}", self.name(), value)
+    }
+
+    /// Set the setting to its value at `index`.
+    pub fn apply(&self, config: &mut AlphabetConfig, index: usize) {
+        match self {
+            SweepAxis::Resolution(values) => config.resolution = Some(values[index]),
+            SweepAxis::Density(values) => config.density = values[index],
+            SweepAxis::Motif(values) => config.motif = values[index].clone(),
+            SweepAxis::Symmetry(values) => config.symmetry = values[index].clone(),
+            SweepAxis::Edge(values) => config.edge = values[index].clone(),
+            SweepAxis::Lattice(values) => config.lattice = values[index].clone(),
+        }
+    }
+}
+
+impl FromStr for SweepAxis {
+    type Err = Error;
+    /// A setting and its values, like `resolution=2-8`, `density=1,3,5` or
+    /// `motif=diagonal,arcs`. Numbers may be given as inclusive ranges, and symmetries
+    /// applied in turn are joined with `+`, as in `horizontal+rotational3`.
+    fn from_str(axis: &str) -> Result<Self> {
+        let invalid = || Error::parse("sweep", axis);
+        let (name, values) = axis.split_once('=').ok_or_else(invalid)?;
+        let values: Vec<&str> = values.split(',').map(str::trim).collect();
+        let axis = match name {
+            "resolution" => SweepAxis::Resolution(numbers(&values).ok_or_else(invalid)?),
+            "density" => SweepAxis::Density(numbers(&values).ok_or_else(invalid)?),
+            "motif" => SweepAxis::Motif(parse_all(&values)?),
+            "symmetry" => {
+                let values: Vec<String> =
+                    values.iter().map(|value| value.replace('+', ",")).collect();
+                SweepAxis::Symmetry(
+                    values
+                        .iter()
+                        .map(|value| value.parse())
+                        .collect::<Result<_>>()?,
+                )
+            }
+            "edge" => SweepAxis::Edge(parse_all(&values)?),
+            "lattice" => SweepAxis::Lattice(parse_all(&values)?),
+            _ => return Err(invalid()),
+        };
+        Ok(axis)
+    }
+}
+
+/// Numbers listed one by one, or as inclusive ranges.
+fn numbers(values: &[&str]) -> Option<Vec<i32>> {
+    let mut numbers = Vec::new();
+    for value in values {
+        let mut bounds = value.splitn(2, '-').map(str::parse::<i32>);
+        match (bounds.next(), bounds.next()) {
+            (Some(Ok(number)), None) => numbers.push(number),
+            (Some(Ok(first)), Some(Ok(last))) if first <= last => numbers.extend(first..=last),
+            _ => return None,
+        }
+    }
+    Some(numbers)
+}
+
+fn parse_all<T: FromStr<Err = Error>>(values: &[&str]) -> Result<Vec<T>> {
+    values.iter().map(|value| value.parse()).collect()
+}
+
+/// A sheet where one alphabet setting varies down the rows and another across the columns.
+///
+/// Settings not swept keep their values from the base config, with a resolution of 4 if
+/// unset.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sweep {
+    pub rows: SweepAxis,
+    pub columns: SweepAxis,
+}
+
+impl Sweep {
+    pub fn new(rows: SweepAxis, columns: SweepAxis) -> Self {
+        Self { rows, columns }
+    }
+
+    /// The settings for the glyph in the given row and column.
+    pub fn config(&self, base: &AlphabetConfig, row: usize, column: usize) -> AlphabetConfig {
+        let mut config = base.clone();
+        self.rows.apply(&mut config, row);
+        self.columns.apply(&mut config, column);
+        config
+    }
+
+    /// Settings that aren't swept and their values, as on the command line, like
+    /// `motif=diagonal`.
+    pub fn fixed(&self, base: &AlphabetConfig) -> Vec<String> {
+        let mut base = base.clone();
+        base.resolution.get_or_insert(4);
+        base.settings()
+            .iter()
+            .filter(|(name, _)| *name != self.rows.name() && *name != self.columns.name())
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect()
+    }
+
+    /// The alphabet for each row and column, in rows.
+    pub fn build(&self, base: &AlphabetConfig) -> Result<Vec<Vec<Alphabet>>> {
+        (0..self.rows.len())
+            .map(|row| {
+                (0..self.columns.len())
+                    .map(|column| self.config(base, row, column).build(2))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_axes() {
+        assert_eq!(
+            "resolution=2-4,7".parse(),
+            Ok(SweepAxis::Resolution(vec![2, 3, 4, 7]))
+        );
+        assert_eq!(
+            "symmetry=vertical,horizontal+rotational3".parse(),
+            Ok(SweepAxis::Symmetry(vec![
+                Symmetry::Vertical,
+                Symmetry::Composite(vec![Symmetry::Horizontal, Symmetry::Rotational(3)])
+            ]))
+        );
+        assert!("density=3-1".parse::<SweepAxis>().is_err());
+        assert!("colour=red".parse::<SweepAxis>().is_err());
+        assert!("motif".parse::<SweepAxis>().is_err());
+    }
+
+    #[test]
+    fn cells_vary_by_row_and_column() {
+        let sweep = Sweep::new(
+            SweepAxis::Resolution(vec![2, 3]),
+            SweepAxis::Density(vec![1, 2, 3]),
+        );
+        let base = AlphabetConfig::default();
+        let config = sweep.config(&base, 1, 2);
+        assert_eq!(config.resolution, Some(3));
+        assert_eq!(config.density, 3);
+        assert_eq!(config.motif, base.motif);
+
+        assert_eq!(sweep.rows.label(1), "resolution=3");
+        assert_eq!(
+            sweep.fixed(&base),
+            [
+                "motif=diagonal",
+                "symmetry=asymmetric",
+                "edge=clamp",
+                "lattice=square",
+                "algorithm=v1"
+            ]
+        );
+
+        let alphabets = sweep.build(&base).unwrap();
+        assert_eq!(alphabets.len(), 2);
+        assert!(alphabets.iter().all(|row| row.len() == 3));
+    }
+}
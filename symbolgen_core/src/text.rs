@@ -0,0 +1,420 @@
+//! Text written in glyphs, with each character standing for the glyph of a seed.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+#[cfg(feature = "config")]
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{Alphabet, Distinct, Error, Glyph, Metrics, MetricsOptions, Result, Vector};
+
+/// Characters given glyphs by default, letters then digits.
+pub const ALPHANUMERIC: &str = "abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Characters and the seeds of the glyphs standing for them.
+///
+/// Letters without seeds of their own use those of the other case, so a map of lower case
+/// letters writes upper case too. As TOML, a map is a line for each character, like `a = 17`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "BTreeMap<String, u64>", into = "BTreeMap<String, u64>")
+)]
+pub struct CharacterMap {
+    pub seeds: BTreeMap<char, u64>,
+}
+
+impl TryFrom<BTreeMap<String, u64>> for CharacterMap {
+    type Error = Error;
+    fn try_from(seeds: BTreeMap<String, u64>) -> Result<Self> {
+        let seeds = seeds
+            .into_iter()
+            .map(|(key, seed)| {
+                let mut characters = key.chars();
+                match (characters.next(), characters.next()) {
+                    (Some(character), None) => Ok((character, seed)),
+                    _ => Err(Error::parse("character", &key)),
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { seeds })
+    }
+}
+
+impl From<CharacterMap> for BTreeMap<String, u64> {
+    fn from(map: CharacterMap) -> Self {
+        map.seeds
+            .into_iter()
+            .map(|(character, seed)| (character.to_string(), seed))
+            .collect()
+    }
+}
+
+impl CharacterMap {
+    /// Each of the characters given a different glyph of the alphabet, none of them blank,
+    /// from seeds counting up from `base_seed`. Glyphs are told apart even when reflected or
+    /// turned, so no two characters are mirror images.
+    ///
+    /// If the alphabet can't draw enough different glyphs, the last characters are left out.
+    pub fn distinct(alphabet: &Alphabet, characters: &str, base_seed: u64) -> Result<Self> {
+        let count = characters.chars().count();
+        // One more, in case one of them is blank
+        let glyphs = alphabet.generate_distinct(count + 1, base_seed, Distinct::UnderSymmetry)?;
+        let seeds = characters
+            .chars()
+            .zip(
+                glyphs
+                    .iter()
+                    .filter(|glyph| !glyph.segments().is_empty())
+                    .map(Glyph::seed),
+            )
+            .collect();
+        Ok(Self { seeds })
+    }
+
+    /// The seed of the glyph standing for a character, if it has one.
+    pub fn seed(&self, character: char) -> Option<u64> {
+        self.seeds.get(&character).copied().or_else(|| {
+            character
+                .to_lowercase()
+                .chain(character.to_uppercase())
+                .find_map(|other| self.seeds.get(&other).copied())
+        })
+    }
+
+    /// Parse a map from TOML.
+    #[cfg(feature = "config")]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|error| Error::Config(error.to_string()))
+    }
+
+    /// Read a map from a TOML file.
+    #[cfg(feature = "config")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .map_err(|error| Error::Config(format!("{}: {}", path.display(), error)))?;
+        Self::from_toml(&toml)
+    }
+
+    /// The map as TOML, which loads back to the same map.
+    #[cfg(feature = "config")]
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|error| Error::Config(error.to_string()))
+    }
+}
+
+/// Where lines shorter than the widest are put.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Align {
+    Left,
+    Centre,
+    Right,
+}
+
+impl Align {
+    /// Fraction of the room to spare put before a line.
+    fn fraction(&self) -> f64 {
+        match self {
+            Align::Left => 0.0,
+            Align::Centre => 0.5,
+            Align::Right => 1.0,
+        }
+    }
+}
+
+impl fmt::Display for Align {
+    /// The alignment's name, which parses back to the same alignment.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Align::Left => f.write_str("left"),
+            Align::Centre => f.write_str("centre"),
+            Align::Right => f.write_str("right"),
+        }
+    }
+}
+
+impl FromStr for Align {
+    type Err = Error;
+    fn from_str(align: &str) -> Result<Self> {
+        match align {
+            "left" => Ok(Align::Left),
+            "centre" => Ok(Align::Centre),
+            "right" => Ok(Align::Right),
+            _ => Err(Error::parse("alignment", align)),
+        }
+    }
+}
+
+/// How text is set in glyphs, each as wide as its metrics' advance.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct TextLayout {
+    /// Width and height of each glyph
+    pub size: f64,
+    /// Gap between glyphs, as a fraction of the size
+    pub letter_spacing: f64,
+    /// Width of a space, as a fraction of the size
+    pub word_spacing: f64,
+    /// Distance from the top of one line to the next, as a fraction of the size
+    pub line_spacing: f64,
+    /// Width that lines wrap at, between words, if any
+    pub max_width: Option<f64>,
+    pub align: Align,
+    /// How each glyph's width is measured
+    pub metrics: MetricsOptions,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            size: 1.0,
+            letter_spacing: 0.25,
+            word_spacing: 0.75,
+            line_spacing: 1.25,
+            max_width: None,
+            align: Align::Left,
+            metrics: MetricsOptions::default(),
+        }
+    }
+}
+
+/// Text set in glyphs, each scaled and moved into place, from the origin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetText {
+    pub glyphs: Vec<Glyph>,
+    pub width: f64,
+    pub height: f64,
+    /// Number of lines, after wrapping
+    pub lines: usize,
+    /// Characters with no glyph in the map, left as gaps as wide as a cell
+    pub missing: Vec<char>,
+}
+
+/// Glyphs generated so far, by seed, with their metrics.
+type Generated = HashMap<u64, (Glyph, Metrics)>;
+
+impl TextLayout {
+    /// Set text in the alphabet's glyphs, a line for each line of the text, with white space
+    /// as gaps between words. Lines longer than the maximum width wrap between words, and
+    /// every line is aligned against the widest.
+    pub fn set(&self, text: &str, map: &CharacterMap, alphabet: &Alphabet) -> Result<SetText> {
+        let mut generated = Generated::new();
+        let mut missing = Vec::new();
+        // Glyphs of each line from the start of the line, and the line's width
+        let mut lines: Vec<(Vec<Glyph>, f64)> = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line = Vec::new();
+            let mut width = 0.0;
+            for (index, (spaces, word)) in words(paragraph).into_iter().enumerate() {
+                let (glyphs, word_width) =
+                    self.set_word(word, map, alphabet, &mut generated, &mut missing)?;
+                let gap = if index == 0 {
+                    spaces as f64 * self.word_spacing
+                } else {
+                    self.letter_spacing + spaces as f64 * self.word_spacing
+                };
+                let mut x = width + gap * self.size;
+                let overflows = self.max_width.is_some_and(|max| x + word_width > max);
+                if index > 0 && overflows {
+                    lines.push((std::mem::take(&mut line), width));
+                    x = 0.0;
+                }
+                let offset = Vector::new(x, 0.0);
+                line.extend(glyphs.iter().map(|glyph| glyph.translated(offset)));
+                width = x + word_width;
+            }
+            lines.push((line, width));
+        }
+
+        let width = lines
+            .iter()
+            .fold(0.0, |widest: f64, line| widest.max(line.1));
+        let glyphs = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (line, line_width))| {
+                let offset = Vector::new(
+                    (width - line_width) * self.align.fraction(),
+                    index as f64 * self.line_spacing * self.size,
+                );
+                line.iter().map(move |glyph| glyph.translated(offset))
+            })
+            .collect();
+        Ok(SetText {
+            glyphs,
+            width,
+            height: (lines.len() - 1) as f64 * self.line_spacing * self.size + self.size,
+            lines: lines.len(),
+            missing,
+        })
+    }
+
+    /// Set a word from the origin, giving its glyphs and width.
+    fn set_word(
+        &self,
+        word: &str,
+        map: &CharacterMap,
+        alphabet: &Alphabet,
+        generated: &mut Generated,
+        missing: &mut Vec<char>,
+    ) -> Result<(Vec<Glyph>, f64)> {
+        let mut glyphs = Vec::new();
+        let mut x = 0.0;
+        let mut width = 0.0;
+        for character in word.chars() {
+            let advance = match map.seed(character) {
+                Some(seed) => {
+                    let (glyph, metrics) = match generated.entry(seed) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            let glyph = alphabet.generate(seed)?;
+                            let metrics = glyph.metrics(&self.metrics);
+                            entry.insert((glyph, metrics))
+                        }
+                    };
+                    let offset = Vector::new(x + metrics.offset * self.size, 0.0);
+                    glyphs.push(glyph.scaled(self.size).translated(offset));
+                    metrics.advance
+                }
+                None => {
+                    missing.push(character);
+                    1.0
+                }
+            };
+            width = x + advance * self.size;
+            x = width + self.letter_spacing * self.size;
+        }
+        Ok((glyphs, width))
+    }
+}
+
+/// The words of a line of text, each with the number of white space characters before it.
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut spaces = 0;
+    for word in line.split(char::is_whitespace) {
+        if word.is_empty() {
+            spaces += 1;
+        } else {
+            words.push((spaces, word));
+            spaces = 1;
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Motif, Spacing, Symmetry};
+    use std::collections::HashSet;
+
+    #[test]
+    fn map_characters_to_distinct_glyphs() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let map = CharacterMap::distinct(&alphabet, ALPHANUMERIC, 0).unwrap();
+        assert_eq!(map.seeds.len(), 36);
+        assert_eq!(map.seed('A'), map.seed('a'));
+        assert_eq!(map.seed('?'), None);
+        let seeds: HashSet<u64> = map.seeds.values().copied().collect();
+        assert_eq!(seeds.len(), 36);
+        #[cfg(feature = "config")]
+        {
+            assert_eq!(
+                CharacterMap::from_toml(&map.to_toml().unwrap()).unwrap(),
+                map
+            );
+            assert!(CharacterMap::from_toml("ab = 3").is_err());
+        }
+    }
+
+    #[test]
+    fn set_lines_of_text() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let map = CharacterMap::distinct(&alphabet, "ab", 0).unwrap();
+        let layout = TextLayout {
+            size: 10.0,
+            ..TextLayout::default()
+        };
+        let text = layout.set("ab a?", &map, &alphabet).unwrap();
+        assert_eq!(text.glyphs.len(), 3);
+        assert_eq!(text.missing, ['?']);
+        // Three glyphs and a gap, with a space between the second and third
+        assert_eq!(text.width, 4.0 * 10.0 + 3.0 * 2.5 + 7.5);
+        assert_eq!(
+            text.glyphs[2],
+            alphabet
+                .generate(map.seed('a').unwrap())
+                .unwrap()
+                .scaled(10.0)
+                .translated(Vector::new(25.0 + 7.5, 0.0))
+        );
+    }
+
+    #[test]
+    fn wrap_and_align_lines() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let map = CharacterMap::distinct(&alphabet, "ab", 0).unwrap();
+        let layout = TextLayout {
+            size: 10.0,
+            max_width: Some(30.0),
+            align: Align::Right,
+            ..TextLayout::default()
+        };
+        // The second word doesn't fit after the first, so wraps
+        let text = layout.set("ab ab\nb", &map, &alphabet).unwrap();
+        assert_eq!(text.lines, 3);
+        assert_eq!(text.width, 22.5);
+        assert_eq!(text.height, 2.0 * 12.5 + 10.0);
+        let b = alphabet.generate(map.seed('b').unwrap()).unwrap();
+        assert_eq!(
+            text.glyphs[4],
+            b.scaled(10.0).translated(Vector::new(12.5, 25.0))
+        );
+
+        let centred = TextLayout {
+            align: "centre".parse().unwrap(),
+            ..layout
+        };
+        let text = centred.set("ab ab\nb", &map, &alphabet).unwrap();
+        assert_eq!(
+            text.glyphs[4],
+            b.scaled(10.0).translated(Vector::new(6.25, 25.0))
+        );
+    }
+
+    #[test]
+    fn set_glyphs_as_wide_as_their_ink() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let map = CharacterMap::distinct(&alphabet, "ab", 0).unwrap();
+        let metrics = MetricsOptions {
+            spacing: Spacing::Proportional,
+            ..MetricsOptions::default()
+        };
+        let layout = TextLayout {
+            size: 10.0,
+            metrics: metrics.clone(),
+            ..TextLayout::default()
+        };
+        let text = layout.set("ab", &map, &alphabet).unwrap();
+        let advances: Vec<f64> = "ab"
+            .chars()
+            .map(|character| {
+                let glyph = alphabet.generate(map.seed(character).unwrap()).unwrap();
+                glyph.metrics(&metrics).advance
+            })
+            .collect();
+        assert!((text.width - (advances[0] + advances[1]) * 10.0 - 2.5).abs() < 1e-9);
+        // The second glyph's ink starts a side bearing after the first's advance and gap
+        let (min, _) = text.glyphs[1].stats().bounds.unwrap();
+        assert!((min.x - (advances[0] * 10.0 + 2.5 + 1.0)).abs() < 1e-9);
+    }
+}
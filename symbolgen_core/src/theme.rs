@@ -0,0 +1,222 @@
+//! Colours to draw glyphs in.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, Result};
+
+/// A colour, as red, green, blue and alpha.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct Color(pub [u8; 4]);
+
+impl Color {
+    pub const BLACK: Color = Color([0, 0, 0, 255]);
+    pub const WHITE: Color = Color([255, 255, 255, 255]);
+}
+
+impl FromStr for Color {
+    type Err = Error;
+    /// A colour as `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` in hex, or a CSS colour name.
+    fn from_str(color: &str) -> Result<Self> {
+        let invalid = || Error::parse("colour", color);
+        let named = match color.to_lowercase().as_str() {
+            "black" => Some([0, 0, 0, 255]),
+            "white" => Some([255, 255, 255, 255]),
+            "red" => Some([255, 0, 0, 255]),
+            "green" => Some([0, 128, 0, 255]),
+            "blue" => Some([0, 0, 255, 255]),
+            "yellow" => Some([255, 255, 0, 255]),
+            "cyan" => Some([0, 255, 255, 255]),
+            "magenta" => Some([255, 0, 255, 255]),
+            "orange" => Some([255, 165, 0, 255]),
+            "purple" => Some([128, 0, 128, 255]),
+            "gray" | "grey" => Some([128, 128, 128, 255]),
+            "transparent" => Some([0, 0, 0, 0]),
+            _ => None,
+        };
+        if let Some(channels) = named {
+            return Ok(Color(channels));
+        }
+        let digits = color.strip_prefix('#').ok_or_else(invalid)?;
+        if !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        // Each channel in one digit, as in #f80, or two
+        let (width, count) = match digits.len() {
+            3 | 4 => (1, digits.len()),
+            6 | 8 => (2, digits.len() / 2),
+            _ => return Err(invalid()),
+        };
+        let mut channels = [255; 4];
+        for (index, channel) in channels.iter_mut().take(count).enumerate() {
+            let value = u8::from_str_radix(&digits[index * width..(index + 1) * width], 16)
+                .map_err(|_| invalid())?;
+            *channel = if width == 1 { value * 17 } else { value };
+        }
+        Ok(Color(channels))
+    }
+}
+
+impl fmt::Display for Color {
+    /// The colour as `#rrggbb`, or `#rrggbbaa` if it isn't opaque.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [red, green, blue, alpha] = self.0;
+        write!(f, "#{:02x}{:02x}{:02x}", red, green, blue)?;
+        if alpha < 255 {
+            write!(f, "{:02x}", alpha)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = Error;
+    fn try_from(color: String) -> Result<Self> {
+        color.parse()
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        color.to_string()
+    }
+}
+
+/// Which glyphs take the next colour of a palette.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Cycle {
+    /// Every glyph in a row is the same colour.
+    Row,
+    /// Each glyph is a different colour from the last.
+    Glyph,
+}
+
+/// Default palette of the multi-coloured themes.
+const PALETTE: [[u8; 4]; 6] = [
+    [0x1f, 0x77, 0xb4, 255],
+    [0xff, 0x7f, 0x0e, 255],
+    [0x2c, 0xa0, 0x2c, 255],
+    [0xd6, 0x27, 0x28, 255],
+    [0x94, 0x67, 0xbd, 255],
+    [0x8c, 0x56, 0x4b, 255],
+];
+
+/// Colours of the background and the glyphs drawn over it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Theme {
+    /// Colour to fill the background with, transparent if not set
+    pub background: Option<Color>,
+    /// Colours of the glyphs, taken in turn. Text is written in the first.
+    pub palette: Vec<Color>,
+    pub cycle: Cycle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Some(Color::WHITE),
+            palette: vec![Color::BLACK],
+            cycle: Cycle::Glyph,
+        }
+    }
+}
+
+impl Theme {
+    /// The colour of a glyph, from its row and its place on the sheet.
+    pub fn color(&self, row: usize, index: usize) -> Color {
+        let turn = match self.cycle {
+            Cycle::Row => row,
+            Cycle::Glyph => index,
+        };
+        match self.palette.len() {
+            0 => Color::BLACK,
+            colors => self.palette[turn % colors],
+        }
+    }
+
+    /// The colour text is written in.
+    pub fn text(&self) -> Color {
+        self.palette.first().copied().unwrap_or(Color::BLACK)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = Error;
+    /// A built in theme: `light` for black on white, `dark` for light grey on near black,
+    /// or `rows` or `glyphs` for a colour for each row or glyph on white.
+    fn from_str(theme: &str) -> Result<Self> {
+        let palette = || PALETTE.iter().copied().map(Color).collect();
+        match theme {
+            "light" => Ok(Theme::default()),
+            "dark" => Ok(Theme {
+                background: Some(Color([0x11, 0x11, 0x11, 255])),
+                palette: vec![Color([0xee, 0xee, 0xee, 255])],
+                cycle: Cycle::Glyph,
+            }),
+            "rows" => Ok(Theme {
+                palette: palette(),
+                cycle: Cycle::Row,
+                ..Theme::default()
+            }),
+            "glyphs" => Ok(Theme {
+                palette: palette(),
+                cycle: Cycle::Glyph,
+                ..Theme::default()
+            }),
+            _ => Err(Error::parse("theme", theme)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!("#f80".parse(), Ok(Color([255, 136, 0, 255])));
+        assert_eq!("#ff880080".parse(), Ok(Color([255, 136, 0, 128])));
+        assert_eq!("Grey".parse(), Ok(Color([128, 128, 128, 255])));
+        assert!("#ff88".parse::<Color>().is_ok());
+        assert!("#ff8".parse::<Color>().is_ok());
+        assert!("#ff88z0".parse::<Color>().is_err());
+        assert!("navy".parse::<Color>().is_err());
+        assert_eq!(Color([255, 136, 0, 128]).to_string(), "#ff880080");
+    }
+
+    #[test]
+    fn themes_cycle_through_palette() {
+        let rows: Theme = "rows".parse().unwrap();
+        assert_eq!(rows.color(1, 0), rows.color(1, 5));
+        assert_ne!(rows.color(0, 0), rows.color(1, 0));
+        let glyphs: Theme = "glyphs".parse().unwrap();
+        assert_eq!(glyphs.color(0, 6), glyphs.color(3, 0));
+        assert_eq!("light".parse::<Theme>().unwrap().text(), Color::BLACK);
+        assert!("neon".parse::<Theme>().is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn theme_in_config() {
+        let config = crate::GenerationConfig::from_toml(
+            r##"
+            [theme]
+            background = "#000"
+            palette = ["white", "#ff000080"]
+            cycle = "row"
+            "##,
+        )
+        .unwrap();
+        let theme = config.theme.unwrap();
+        assert_eq!(theme.background, Some(Color([0, 0, 0, 255])));
+        assert_eq!(theme.color(1, 0), Color([255, 0, 0, 128]));
+    }
+}
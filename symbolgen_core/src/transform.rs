@@ -0,0 +1,217 @@
+//! Stages applied to every glyph after its segments are proposed.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+use crate::{graph, Alphabet, Components, Glyph, Hatch, Symmetry};
+
+/// A stage of an alphabet's pipeline, changing each glyph after its segments are proposed.
+pub trait GlyphTransform: Debug + Send + Sync {
+    fn transform(&self, alphabet: &Alphabet, glyph: &mut Glyph);
+
+    /// This stage as one of the built-in stages, if it is one.
+    ///
+    /// Only pipelines of built-in stages can be saved and loaded.
+    fn stage(&self) -> Option<Stage> {
+        None
+    }
+}
+
+/// The built-in pipeline stages.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Stage {
+    Symmetry(Symmetry),
+    Deduplicate,
+    Simplify,
+    Components(Components),
+    Hatch(Hatch),
+}
+
+impl GlyphTransform for Stage {
+    fn transform(&self, alphabet: &Alphabet, glyph: &mut Glyph) {
+        match self {
+            Stage::Symmetry(symmetry) => symmetry.transform(alphabet, glyph),
+            Stage::Deduplicate => Deduplicate.transform(alphabet, glyph),
+            Stage::Simplify => Simplify.transform(alphabet, glyph),
+            Stage::Components(components) => components.transform(alphabet, glyph),
+            Stage::Hatch(hatch) => hatch.transform(alphabet, glyph),
+        }
+    }
+
+    fn stage(&self) -> Option<Stage> {
+        Some(self.clone())
+    }
+}
+
+impl GlyphTransform for Symmetry {
+    fn transform(&self, alphabet: &Alphabet, glyph: &mut Glyph) {
+        self.apply(alphabet, &mut glyph.segments);
+    }
+
+    fn stage(&self) -> Option<Stage> {
+        Some(Stage::Symmetry(self.clone()))
+    }
+}
+
+/// Remove segments drawn more than once, in either direction.
+///
+/// Symmetric copies of segments on a mirror axis or centre of rotation land on themselves.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Deduplicate;
+
+impl GlyphTransform for Deduplicate {
+    fn transform(&self, _alphabet: &Alphabet, glyph: &mut Glyph) {
+        let mut seen = HashSet::new();
+        glyph.segments.retain(|segment| seen.insert(segment.key()));
+    }
+
+    fn stage(&self) -> Option<Stage> {
+        Some(Stage::Deduplicate)
+    }
+}
+
+/// Merge lines that continue straight on from one another.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Simplify;
+
+impl GlyphTransform for Simplify {
+    fn transform(&self, _alphabet: &Alphabet, glyph: &mut Glyph) {
+        glyph.simplify();
+    }
+
+    fn stage(&self) -> Option<Stage> {
+        Some(Stage::Simplify)
+    }
+}
+
+impl GlyphTransform for Components {
+    fn transform(&self, _alphabet: &Alphabet, glyph: &mut Glyph) {
+        let groups = match self {
+            Components::All => return,
+            _ => graph::components(&glyph.segments),
+        };
+        let keep = match self {
+            Components::DropStrays => groups.iter().filter(|group| group.len() > 1).count(),
+            _ => 0,
+        };
+        // Always keep the largest group
+        glyph.segments = groups.into_iter().take(keep.max(1)).flatten().collect();
+    }
+
+    fn stage(&self) -> Option<Stage> {
+        Some(Stage::Components(*self))
+    }
+}
+
+impl GlyphTransform for Hatch {
+    fn transform(&self, _alphabet: &Alphabet, glyph: &mut Glyph) {
+        glyph.hatch(self);
+    }
+
+    fn stage(&self) -> Option<Stage> {
+        Some(Stage::Hatch(self.clone()))
+    }
+}
+
+/// Save and load pipelines as lists of built-in stages.
+#[cfg(feature = "serde")]
+pub(crate) mod pipeline {
+    use serde::{de::Deserializer, ser::Error, ser::Serializer, Deserialize, Serialize};
+
+    use super::{GlyphTransform, Stage};
+
+    pub fn serialize<S: Serializer>(
+        pipeline: &[Box<dyn GlyphTransform>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let stages = pipeline
+            .iter()
+            .map(|stage| {
+                stage
+                    .stage()
+                    .ok_or_else(|| S::Error::custom(format!("can't save stage {:?}", stage)))
+            })
+            .collect::<Result<Vec<Stage>, _>>()?;
+        stages.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Box<dyn GlyphTransform>>, D::Error> {
+        let stages = Vec::<Stage>::deserialize(deserializer)?;
+        Ok(stages
+            .into_iter()
+            .map(|stage| Box::new(stage) as Box<dyn GlyphTransform>)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Components;
+
+    fn stages(alphabet: &Alphabet) -> Vec<Option<Stage>> {
+        alphabet
+            .pipeline
+            .iter()
+            .map(|stage| stage.stage())
+            .collect()
+    }
+
+    #[test]
+    fn deduplicate_adds_its_stage() {
+        let builder = || Alphabet::builder().symmetry(Symmetry::Horizontal);
+        assert_eq!(
+            stages(&builder().build().unwrap()),
+            [
+                Some(Stage::Symmetry(Symmetry::Horizontal)),
+                Some(Stage::Deduplicate)
+            ]
+        );
+        let keeping = builder().deduplicate(false).build().unwrap();
+        assert!(!keeping.deduplicate);
+        assert_eq!(
+            stages(&keeping),
+            [Some(Stage::Symmetry(Symmetry::Horizontal))]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pipelines_round_trip_through_serde() {
+        let alphabet = Alphabet::builder()
+            .symmetry(Symmetry::Vertical)
+            .transform(Simplify)
+            .transform(Components::Largest)
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(&alphabet).unwrap();
+        assert_eq!(
+            json["pipeline"],
+            serde_json::json!([
+                { "symmetry": "vertical" },
+                "deduplicate",
+                "simplify",
+                { "components": "largest" }
+            ])
+        );
+        let loaded: Alphabet = serde_json::from_value(json).unwrap();
+        assert_eq!(stages(&loaded), stages(&alphabet));
+        assert_eq!(loaded.generate(3).unwrap(), alphabet.generate(3).unwrap());
+
+        // Stages from outside the crate can't be saved
+        #[derive(Debug)]
+        struct Reverse;
+        impl GlyphTransform for Reverse {
+            fn transform(&self, _alphabet: &Alphabet, glyph: &mut Glyph) {
+                glyph.segments.reverse();
+            }
+        }
+        let custom = Alphabet::builder().transform(Reverse).build().unwrap();
+        assert!(serde_json::to_value(&custom).is_err());
+    }
+}
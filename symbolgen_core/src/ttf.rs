@@ -0,0 +1,264 @@
+//! Glyphs as a TrueType font, to type in an alphabet.
+
+use font_kurbo::{BezPath, CubicBez, PathEl};
+use write_fonts::tables::cmap::Cmap;
+use write_fonts::tables::glyf::{Bbox, Contour, GlyfLocaBuilder, SimpleGlyph};
+use write_fonts::tables::head::{Flags, Head};
+use write_fonts::tables::hhea::Hhea;
+use write_fonts::tables::hmtx::{Hmtx, LongMetric};
+use write_fonts::tables::maxp::Maxp;
+use write_fonts::tables::name::{Name, NameRecord};
+use write_fonts::tables::os2::{Os2, SelectionFlags};
+use write_fonts::tables::post::Post;
+use write_fonts::types::{FWord, Fixed, GlyphId, NameId, UfWord};
+use write_fonts::FontBuilder;
+
+use crate::font::{font_glyphs, postscript_name, vertical_metrics, TOLERANCE};
+use crate::{Error, FontOptions, Glyph, Result, Spacing};
+
+/// A TrueType font of the glyphs, each typed as its character.
+///
+/// Each glyph's cell is an em square, sitting on the baseline of the metrics. Strokes are
+/// expanded to outlines `weight` wide, with round ends and joins, and glyphs are made as much
+/// wider to fit them. Outlines of crossing strokes overlap rather than being merged, which
+/// renders the same. A space is added, unless a glyph is typed as one.
+pub fn to_font(glyphs: &[(char, Glyph)], options: &FontOptions) -> Result<Vec<u8>> {
+    let (ascender, descender) = vertical_metrics(options);
+    let font_glyphs = font_glyphs(glyphs, options);
+    let outlines = font_glyphs
+        .iter()
+        .map(|glyph| {
+            let quadratic = to_quadratic(&glyph.outline);
+            if quadratic.elements().is_empty() {
+                Ok(SimpleGlyph::default())
+            } else {
+                SimpleGlyph::from_bezpath(&quadratic)
+                    .map_err(|error| Error::Font(format!("{:?} outline for {}", error, glyph.name)))
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let advances: Vec<u16> = font_glyphs.iter().map(|glyph| glyph.advance).collect();
+    let mappings: Vec<(char, GlyphId)> = font_glyphs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, glyph)| Some((glyph.character?, GlyphId::new(index as u32))))
+        .collect();
+
+    // Outlines with ink, and their advances
+    let inked: Vec<(&SimpleGlyph, u16)> = outlines
+        .iter()
+        .zip(advances.iter().copied())
+        .filter(|(outline, _)| !outline.contours.is_empty())
+        .collect();
+    let bbox = inked
+        .iter()
+        .map(|(outline, _)| outline.bbox)
+        .reduce(Bbox::union)
+        .unwrap_or_default();
+    let mut builder = GlyfLocaBuilder::new();
+    for outline in &outlines {
+        builder
+            .add_glyph(outline)
+            .map_err(|error| Error::Font(error.to_string()))?;
+    }
+    let (glyf, loca, loca_format) = builder.build();
+
+    let h_metrics: Vec<LongMetric> = outlines
+        .iter()
+        .zip(&advances)
+        .map(|(outline, advance)| LongMetric::new(*advance, outline.bbox.x_min))
+        .collect();
+    let head = Head {
+        font_revision: Fixed::ONE,
+        flags: Flags::BASELINE_AT_Y_0 | Flags::LSB_AT_X_0,
+        units_per_em: options.units_per_em,
+        x_min: bbox.x_min,
+        y_min: bbox.y_min,
+        x_max: bbox.x_max,
+        y_max: bbox.y_max,
+        lowest_rec_ppem: 8,
+        index_to_loc_format: loca_format as i16,
+        ..Head::default()
+    };
+    let hhea = Hhea {
+        ascender: FWord::new(ascender),
+        descender: FWord::new(descender),
+        line_gap: FWord::new(0),
+        advance_width_max: UfWord::new(advances.iter().copied().max().unwrap_or_default()),
+        min_left_side_bearing: FWord::new(
+            inked
+                .iter()
+                .map(|(o, _)| o.bbox.x_min)
+                .min()
+                .unwrap_or_default(),
+        ),
+        min_right_side_bearing: FWord::new(
+            inked
+                .iter()
+                .map(|(o, advance)| *advance as i16 - o.bbox.x_max)
+                .min()
+                .unwrap_or_default(),
+        ),
+        x_max_extent: FWord::new(
+            inked
+                .iter()
+                .map(|(o, _)| o.bbox.x_max)
+                .max()
+                .unwrap_or_default(),
+        ),
+        caret_slope_rise: 1,
+        caret_slope_run: 0,
+        caret_offset: 0,
+        number_of_h_metrics: outlines.len() as u16,
+    };
+    let maxp = Maxp {
+        max_points: Some(
+            outlines
+                .iter()
+                .map(|o| o.contours.iter().map(Contour::len).sum::<usize>() as u16)
+                .max()
+                .unwrap_or(0),
+        ),
+        max_contours: Some(
+            outlines
+                .iter()
+                .map(|o| o.contours.len() as u16)
+                .max()
+                .unwrap_or(0),
+        ),
+        max_composite_points: Some(0),
+        max_composite_contours: Some(0),
+        max_zones: Some(1),
+        max_twilight_points: Some(0),
+        max_storage: Some(0),
+        max_function_defs: Some(0),
+        max_instruction_defs: Some(0),
+        max_stack_elements: Some(0),
+        max_size_of_instructions: Some(0),
+        max_component_elements: Some(0),
+        max_component_depth: Some(0),
+        ..Maxp::new(outlines.len() as u16)
+    };
+    let codes = mappings.iter().map(|(character, _)| *character as u32);
+    let os2 = Os2 {
+        x_avg_char_width: (advances.iter().map(|a| f64::from(*a)).sum::<f64>()
+            / advances.len() as f64)
+            .round() as i16,
+        fs_selection: SelectionFlags::REGULAR | SelectionFlags::USE_TYPO_METRICS,
+        us_first_char_index: codes.clone().min().unwrap_or(0).min(0xFFFF) as u16,
+        us_last_char_index: codes.max().unwrap_or(0).min(0xFFFF) as u16,
+        s_typo_ascender: ascender,
+        s_typo_descender: descender,
+        s_typo_line_gap: 0,
+        us_win_ascent: bbox.y_max.max(ascender).max(0) as u16,
+        us_win_descent: (-bbox.y_min.min(descender)).max(0) as u16,
+        ul_code_page_range_1: Some(1),
+        ul_code_page_range_2: Some(0),
+        sx_height: Some(ascender),
+        s_cap_height: Some(ascender),
+        us_default_char: Some(0),
+        us_break_char: Some(' ' as u16),
+        us_max_context: Some(0),
+        ..Os2::default()
+    };
+    let cmap = Cmap::from_mappings(mappings).map_err(|error| Error::Font(error.to_string()))?;
+    let post = Post {
+        is_fixed_pitch: u32::from(options.metrics.spacing == Spacing::Monospace),
+        underline_position: FWord::new(descender / 2),
+        underline_thickness: FWord::new(
+            (options.weight * f64::from(options.units_per_em)).round() as i16
+        ),
+        ..Post::new_v2(font_glyphs.iter().map(|glyph| glyph.name.as_str()))
+    };
+    let name = Name::new(font_names(&options.family));
+
+    let mut font = FontBuilder::new();
+    font.add_table(&head)
+        .and_then(|font| font.add_table(&hhea))
+        .and_then(|font| font.add_table(&maxp))
+        .and_then(|font| font.add_table(&os2))
+        .and_then(|font| font.add_table(&Hmtx::new(h_metrics, Vec::new())))
+        .and_then(|font| font.add_table(&cmap))
+        .and_then(|font| font.add_table(&loca))
+        .and_then(|font| font.add_table(&glyf))
+        .and_then(|font| font.add_table(&name))
+        .and_then(|font| font.add_table(&post))
+        .map_err(|error| Error::Font(error.to_string()))?;
+    Ok(font.build())
+}
+
+/// The path with its cubic curves split into quadratic ones, as TrueType outlines need.
+fn to_quadratic(path: &BezPath) -> BezPath {
+    let mut quadratic = BezPath::new();
+    let mut position = font_kurbo::Point::ZERO;
+    for element in path.elements() {
+        match *element {
+            PathEl::CurveTo(control_start, control_end, end) => {
+                let cubic = CubicBez::new(position, control_start, control_end, end);
+                for (_, _, quad) in cubic.to_quads(TOLERANCE) {
+                    quadratic.quad_to(quad.p1, quad.p2);
+                }
+                position = end;
+            }
+            PathEl::MoveTo(point) | PathEl::LineTo(point) | PathEl::QuadTo(_, point) => {
+                quadratic.push(*element);
+                position = point;
+            }
+            PathEl::ClosePath => quadratic.push(*element),
+        }
+    }
+    quadratic
+}
+
+/// Name table entries for the family, in its only style.
+fn font_names(family: &str) -> Vec<NameRecord> {
+    vec![
+        (NameId::FAMILY_NAME, family.to_owned()),
+        (NameId::SUBFAMILY_NAME, "Regular".to_owned()),
+        (NameId::UNIQUE_ID, format!("{};Regular", family)),
+        (NameId::FULL_NAME, family.to_owned()),
+        (NameId::VERSION_STRING, "Version 1.000".to_owned()),
+        (NameId::POSTSCRIPT_NAME, postscript_name(family)),
+    ]
+    .into_iter()
+    .map(|(id, string)| NameRecord::new(3, 1, 0x409, id, string.into()))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alphabet, Motif, Symmetry};
+    use read_fonts::{FontRef, TableProvider};
+
+    #[test]
+    fn fonts_map_characters_to_outlines() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let glyphs = vec![
+            ('a', alphabet.generate(1).unwrap()),
+            ('b', alphabet.generate(2).unwrap()),
+        ];
+        let options = FontOptions::default();
+        let bytes = to_font(&glyphs, &options).unwrap();
+
+        let font = FontRef::new(&bytes).unwrap();
+        assert_eq!(font.maxp().unwrap().num_glyphs(), 4);
+        let cmap = font.cmap().unwrap();
+        assert_eq!(
+            cmap.map_codepoint('b'),
+            Some(read_fonts::types::GlyphId::new(3))
+        );
+        assert_eq!(
+            cmap.map_codepoint(' '),
+            Some(read_fonts::types::GlyphId::new(1))
+        );
+        assert_eq!(cmap.map_codepoint('c'), None);
+        // Monospaced glyphs are a cell wide, and as wide again as a stroke
+        let hmtx = font.hmtx().unwrap();
+        assert_eq!(hmtx.h_metrics()[2].advance(), 1080);
+        assert_eq!(hmtx.h_metrics()[1].advance(), 750);
+
+        let repeated = [glyphs[0].clone(), glyphs[0].clone()];
+        assert!(to_font(&repeated, &options).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Glyphs as a UFO font source, a folder of XML files that font editors open, to refine
+//! generated alphabets by hand.
+
+use std::fmt::Write as _;
+
+use font_kurbo::{BezPath, PathEl, Point};
+
+use crate::font::{font_glyphs, vertical_metrics, FontGlyph};
+use crate::{FontOptions, Glyph, Spacing};
+
+const PLIST_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+"#;
+
+/// The files of a UFO 3 font source of the glyphs, each as its path inside the `.ufo` folder
+/// and its contents.
+///
+/// Glyphs have the same outlines and widths as in TrueType fonts, with curves left cubic, and
+/// each has a note of the seed it was generated from.
+pub fn to_ufo(glyphs: &[(char, Glyph)], options: &FontOptions) -> Vec<(String, String)> {
+    let font_glyphs = font_glyphs(glyphs, options);
+    let (ascender, descender) = vertical_metrics(options);
+    let file_names: Vec<String> = font_glyphs
+        .iter()
+        .map(|glyph| glif_file_name(&glyph.name))
+        .collect();
+
+    let metainfo = dict(&[
+        ("creator", string("com.github.tommilligan.symbolgen")),
+        ("formatVersion", integer(3)),
+    ]);
+    let fixed_pitch = options.metrics.spacing == Spacing::Monospace;
+    let fontinfo = dict(&[
+        ("familyName", string(&options.family)),
+        ("styleName", string("Regular")),
+        ("versionMajor", integer(1)),
+        ("versionMinor", integer(0)),
+        ("unitsPerEm", integer(options.units_per_em.into())),
+        ("ascender", integer(ascender.into())),
+        ("descender", integer(descender.into())),
+        ("capHeight", integer(ascender.into())),
+        ("xHeight", integer(ascender.into())),
+        (
+            "postscriptIsFixedPitch",
+            (if fixed_pitch { "<true/>" } else { "<false/>" }).to_owned(),
+        ),
+    ]);
+    let lib = dict(&[(
+        "public.glyphOrder",
+        array(font_glyphs.iter().map(|glyph| string(&glyph.name))),
+    )]);
+    let layers = array(std::iter::once(array(
+        [string("public.default"), string("glyphs")].iter().cloned(),
+    )));
+    let contents: Vec<(&str, String)> = font_glyphs
+        .iter()
+        .zip(&file_names)
+        .map(|(glyph, file)| (glyph.name.as_str(), string(file)))
+        .collect();
+
+    let mut files = vec![
+        ("metainfo.plist".to_owned(), plist(&metainfo)),
+        ("fontinfo.plist".to_owned(), plist(&fontinfo)),
+        ("lib.plist".to_owned(), plist(&lib)),
+        ("layercontents.plist".to_owned(), plist(&layers)),
+        ("glyphs/contents.plist".to_owned(), plist(&dict(&contents))),
+    ];
+    files.extend(
+        font_glyphs
+            .iter()
+            .zip(file_names)
+            .map(|(glyph, file)| (format!("glyphs/{}", file), glif(glyph))),
+    );
+    files
+}
+
+/// A glyph as a GLIF file, with its seed as a note.
+fn glif(glyph: &FontGlyph) -> String {
+    let mut glif = String::new();
+    let mut line = |text: String| {
+        glif.push_str(&text);
+        glif.push('\n');
+    };
+    line(r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned());
+    line(format!(
+        r#"<glyph name="{}" format="2">"#,
+        escape(&glyph.name)
+    ));
+    line(format!(r#"  <advance width="{}"/>"#, glyph.advance));
+    if let Some(character) = glyph.character {
+        line(format!(r#"  <unicode hex="{:04X}"/>"#, character as u32));
+    }
+    if let Some(seed) = glyph.seed {
+        line(format!("  <note>seed {}</note>", seed));
+    }
+    line("  <outline>".to_owned());
+    for contour in contours(&glyph.outline) {
+        line("    <contour>".to_owned());
+        for (point, kind) in contour {
+            let (x, y) = (point.x.round() as i64, point.y.round() as i64);
+            line(match kind {
+                Some(kind) => format!(r#"      <point x="{}" y="{}" type="{}"/>"#, x, y, kind),
+                None => format!(r#"      <point x="{}" y="{}"/>"#, x, y),
+            });
+        }
+        line("    </contour>".to_owned());
+    }
+    line("  </outline>".to_owned());
+    line("</glyph>".to_owned());
+    glif
+}
+
+/// Points of each contour of a path, all closed, with the type of each point on the curve.
+fn contours(path: &BezPath) -> Vec<Vec<(Point, Option<&'static str>)>> {
+    let mut contours = Vec::new();
+    let mut points: Vec<(Point, Option<&'static str>)> = Vec::new();
+    let mut start = Point::ZERO;
+    // A contour not ending where it started closes with a line back
+    let mut close = |points: &mut Vec<_>, start| {
+        if points.is_empty() {
+            return;
+        }
+        if points.last().map(|(point, _)| *point) != Some(start) {
+            points.push((start, Some("line")));
+        }
+        contours.push(std::mem::take(points));
+    };
+    for element in path.elements() {
+        match *element {
+            PathEl::MoveTo(point) => {
+                close(&mut points, start);
+                start = point;
+            }
+            PathEl::LineTo(point) => points.push((point, Some("line"))),
+            PathEl::QuadTo(control, point) => {
+                points.extend([(control, None), (point, Some("qcurve"))]);
+            }
+            PathEl::CurveTo(control_start, control_end, point) => {
+                points.extend([
+                    (control_start, None),
+                    (control_end, None),
+                    (point, Some("curve")),
+                ]);
+            }
+            PathEl::ClosePath => close(&mut points, start),
+        }
+    }
+    close(&mut points, start);
+    contours
+}
+
+/// The name of a glyph's file, as the UFO specification suggests, so that names differing
+/// only in case don't clash on file systems that ignore it.
+fn glif_file_name(name: &str) -> String {
+    let mut file = String::new();
+    for (index, character) in name.chars().enumerate() {
+        match character {
+            '.' if index == 0 => file.push('_'),
+            '"' | '*' | '+' | '/' | ':' | '<' | '>' | '?' | '[' | '\\' | ']' | '|' => {
+                file.push('_')
+            }
+            character if character.is_control() => file.push('_'),
+            character if character.is_uppercase() => {
+                file.push(character);
+                file.push('_');
+            }
+            character => file.push(character),
+        }
+    }
+    file + ".glif"
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn plist(value: &str) -> String {
+    format!("{}{}\n</plist>\n", PLIST_HEADER, value)
+}
+
+fn dict(entries: &[(&str, String)]) -> String {
+    let mut dict = "<dict>\n".to_owned();
+    for (key, value) in entries {
+        writeln!(dict, "<key>{}</key>\n{}", escape(key), value)
+            .expect("writing to a string can't fail");
+    }
+    dict + "</dict>"
+}
+
+fn array(values: impl Iterator<Item = String>) -> String {
+    let mut array = "<array>\n".to_owned();
+    for value in values {
+        array.push_str(&value);
+        array.push('\n');
+    }
+    array + "</array>"
+}
+
+fn string(text: &str) -> String {
+    format!("<string>{}</string>", escape(text))
+}
+
+fn integer(value: i64) -> String {
+    format!("<integer>{}</integer>", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alphabet, Motif, Symmetry};
+
+    #[test]
+    fn ufo_glyphs_note_their_seeds() {
+        let alphabet = Alphabet::new(4, 3, Symmetry::Asymmetric, Motif::Diagonal).unwrap();
+        let glyphs = vec![
+            ('a', alphabet.generate(17).unwrap()),
+            ('A', alphabet.generate(2).unwrap()),
+        ];
+        let files = to_ufo(&glyphs, &FontOptions::default());
+        let file = |path: &str| {
+            files
+                .iter()
+                .find(|(name, _)| name == path)
+                .map(|(_, contents)| contents.as_str())
+                .unwrap()
+        };
+
+        let contents = file("glyphs/contents.plist");
+        assert!(contents.contains("<key>.notdef</key>\n<string>_notdef.glif</string>"));
+        assert!(contents.contains("<key>uni0041</key>\n<string>uni0041.glif</string>"));
+        assert!(file("fontinfo.plist").contains("<key>unitsPerEm</key>\n<integer>1000</integer>"));
+
+        let a = file("glyphs/uni0061.glif");
+        assert!(a.contains(r#"<unicode hex="0061"/>"#));
+        assert!(a.contains("<note>seed 17</note>"));
+        assert!(a.contains(r#"<advance width="1080"/>"#));
+        assert!(a.contains(r#"type="curve""#));
+        assert!(!file("glyphs/space.glif").contains("<note>"));
+        assert_eq!(glif_file_name("uni004A"), "uni004A_.glif");
+    }
+}
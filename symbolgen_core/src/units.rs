@@ -0,0 +1,137 @@
+//! Units of length, for drawings that map to real paper.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, Result};
+
+/// Millimetres in an inch.
+const MILLIMETRES_PER_INCH: f64 = 25.4;
+
+/// The unit a drawing is measured in.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Unit {
+    /// Pixels, which have no physical size until drawn at some resolution.
+    #[default]
+    Px,
+    /// Points, 72 to the inch.
+    Pt,
+    Mm,
+    Cm,
+    In,
+}
+
+impl Unit {
+    /// The length of one unit in inches, or `None` for pixels.
+    pub fn inches(&self) -> Option<f64> {
+        match self {
+            Unit::Px => None,
+            Unit::Pt => Some(1.0 / 72.0),
+            Unit::Mm => Some(1.0 / MILLIMETRES_PER_INCH),
+            Unit::Cm => Some(10.0 / MILLIMETRES_PER_INCH),
+            Unit::In => Some(1.0),
+        }
+    }
+
+    /// The abbreviation written after lengths, which is empty for pixels as they are the
+    /// default in SVG.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Unit::Px => "",
+            Unit::Pt => "pt",
+            Unit::Mm => "mm",
+            Unit::Cm => "cm",
+            Unit::In => "in",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unit::Px => f.write_str("px"),
+            unit => f.write_str(unit.suffix()),
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+    fn from_str(unit: &str) -> Result<Self> {
+        match unit {
+            "px" => Ok(Unit::Px),
+            "pt" => Ok(Unit::Pt),
+            "mm" => Ok(Unit::Mm),
+            "cm" => Ok(Unit::Cm),
+            "in" => Ok(Unit::In),
+            _ => Err(Error::parse("unit", unit)),
+        }
+    }
+}
+
+/// Standard paper sizes.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Paper {
+    A3,
+    A4,
+    A5,
+    Letter,
+    Legal,
+}
+
+impl Paper {
+    /// Width and height in portrait, in millimetres.
+    pub fn millimetres(&self) -> (f64, f64) {
+        match self {
+            Paper::A3 => (297.0, 420.0),
+            Paper::A4 => (210.0, 297.0),
+            Paper::A5 => (148.0, 210.0),
+            Paper::Letter => (215.9, 279.4),
+            Paper::Legal => (215.9, 355.6),
+        }
+    }
+
+    /// Width and height in portrait, in points.
+    pub fn points(&self) -> (f64, f64) {
+        let (width, height) = self.millimetres();
+        let points = 72.0 / MILLIMETRES_PER_INCH;
+        (width * points, height * points)
+    }
+}
+
+impl FromStr for Paper {
+    type Err = Error;
+    fn from_str(paper: &str) -> Result<Self> {
+        match paper {
+            "a3" => Ok(Paper::A3),
+            "a4" => Ok(Paper::A4),
+            "a5" => Ok(Paper::A5),
+            "letter" => Ok(Paper::Letter),
+            "legal" => Ok(Paper::Legal),
+            _ => Err(Error::parse("paper", paper)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_convert_to_inches() {
+        assert_eq!(Unit::In.inches(), Some(1.0));
+        assert!((Unit::Cm.inches().unwrap() * 2.54 - 1.0).abs() < 1e-12);
+        assert_eq!(Unit::Px.inches(), None);
+        assert_eq!("cm".parse(), Ok(Unit::Cm));
+        assert_eq!(Unit::Px.to_string().parse(), Ok(Unit::Px));
+
+        let (width, height) = Paper::A4.points();
+        assert_eq!((width.round(), height.round()), (595.0, 842.0));
+    }
+}
@@ -0,0 +1,263 @@
+//! Images shown inline in terminals that draw pixels, through their graphics protocols.
+
+use std::env;
+use std::fmt::Write as _;
+use std::io::Write;
+
+use crate::{render_to_rgba, Drawing, Renderer, Result, RgbaStyle};
+
+/// Most base64 sent to kitty in one escape sequence.
+const KITTY_CHUNK: usize = 4096;
+/// Levels of each of red, green and blue in the Sixel palette.
+const SIXEL_LEVELS: usize = 6;
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A way for programs to draw pixels in a terminal.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphicsProtocol {
+    /// Kitty's graphics protocol, also in Ghostty and WezTerm
+    Kitty,
+    /// DEC Sixel graphics, in foot, mlterm, iTerm2 and some builds of xterm
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// The protocol the terminal this program runs in draws with, guessed from its
+    /// environment variables, or none if it doesn't seem to draw pixels.
+    pub fn detect() -> Option<Self> {
+        if env::var_os("KITTY_WINDOW_ID").is_some() {
+            return Some(GraphicsProtocol::Kitty);
+        }
+        let var = |name| env::var(name).unwrap_or_default();
+        Self::guess(&var("TERM"), &var("TERM_PROGRAM"))
+    }
+
+    /// The protocol a terminal with this `TERM` and `TERM_PROGRAM` draws with.
+    fn guess(term: &str, program: &str) -> Option<Self> {
+        match (term, program) {
+            ("xterm-kitty" | "xterm-ghostty", _) | (_, "ghostty" | "WezTerm") => {
+                Some(GraphicsProtocol::Kitty)
+            }
+            (_, "iTerm.app") => Some(GraphicsProtocol::Sixel),
+            _ if term.starts_with("foot")
+                || term.starts_with("mlterm")
+                || term.contains("sixel") =>
+            {
+                Some(GraphicsProtocol::Sixel)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Draws a single page as an image in a terminal's graphics protocol, followed by a new line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InlineRenderer {
+    pub protocol: GraphicsProtocol,
+    pub style: RgbaStyle,
+    /// Pixels to each unit of the drawing
+    pub scale: f64,
+}
+
+impl InlineRenderer {
+    pub fn new(protocol: GraphicsProtocol) -> Self {
+        Self {
+            protocol,
+            style: RgbaStyle::default(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl Renderer for InlineRenderer {
+    fn render(&self, drawing: &Drawing, writer: &mut dyn Write) -> Result<()> {
+        let width = (drawing.width * self.scale).round().max(1.0) as u32;
+        let height = (drawing.height * self.scale).round().max(1.0) as u32;
+        let pixels = render_to_rgba(drawing, width, height, &self.style)?;
+        let image = match self.protocol {
+            GraphicsProtocol::Kitty => kitty(&pixels, width, height),
+            GraphicsProtocol::Sixel => sixel(&pixels, width as usize, height as usize),
+        };
+        writeln!(writer, "{}", image)?;
+        Ok(())
+    }
+}
+
+/// RGBA pixels as kitty graphics escape sequences, displaying them at the cursor.
+fn kitty(pixels: &[u8], width: u32, height: u32) -> String {
+    let data = base64(pixels);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut escapes = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if index == 0 {
+            let _ = write!(
+                escapes,
+                "\x1b_Ga=T,f=32,s={},v={},m={};{}\x1b\\",
+                width, height, more, chunk
+            );
+        } else {
+            let _ = write!(escapes, "\x1b_Gm={};{}\x1b\\", more, chunk);
+        }
+    }
+    escapes
+}
+
+/// RGBA pixels as a Sixel image, in colours from a palette of evenly spaced ones. Pixels more
+/// than half transparent are left undrawn.
+fn sixel(pixels: &[u8], width: usize, height: usize) -> String {
+    let level = |value: u8| (value as usize * (SIXEL_LEVELS - 1) + 127) / 255;
+    let colors: Vec<Option<usize>> = pixels
+        .chunks_exact(4)
+        .map(|pixel| {
+            (pixel[3] >= 128).then(|| {
+                (level(pixel[0]) * SIXEL_LEVELS + level(pixel[1])) * SIXEL_LEVELS + level(pixel[2])
+            })
+        })
+        .collect();
+
+    // Transparent background, and the image's size in pixels
+    let mut image = format!("\x1bP0;1q\"1;1;{};{}", width, height);
+    let percent = |level: usize| level * 100 / (SIXEL_LEVELS - 1);
+    let mut used: Vec<usize> = colors.iter().flatten().copied().collect();
+    used.sort_unstable();
+    used.dedup();
+    for color in used {
+        let (red, green, blue) = (
+            color / SIXEL_LEVELS / SIXEL_LEVELS,
+            color / SIXEL_LEVELS % SIXEL_LEVELS,
+            color % SIXEL_LEVELS,
+        );
+        let _ = write!(
+            image,
+            "#{};2;{};{};{}",
+            color,
+            percent(red),
+            percent(green),
+            percent(blue)
+        );
+    }
+
+    // Each band of six rows is drawn a colour at a time, going back to the start in between
+    for top in (0..height).step_by(6) {
+        let rows = top..(top + 6).min(height);
+        let mut band: Vec<usize> = rows
+            .clone()
+            .flat_map(|y| colors[y * width..(y + 1) * width].iter().flatten().copied())
+            .collect();
+        band.sort_unstable();
+        band.dedup();
+        for (index, color) in band.iter().enumerate() {
+            if index > 0 {
+                image.push('$');
+            }
+            let _ = write!(image, "#{}", color);
+            let sixels = (0..width).map(|x| {
+                rows.clone().fold(0, |bits, y| {
+                    let set = colors[y * width + x] == Some(*color);
+                    bits | (u8::from(set) << (y - top))
+                })
+            });
+            push_runs(&mut image, sixels);
+        }
+        image.push('-');
+    }
+    image.push_str("\x1b\\");
+    image
+}
+
+/// Add sixels of the given bits to an image, with repeats of more than three as runs.
+fn push_runs(image: &mut String, sixels: impl Iterator<Item = u8>) {
+    let mut push = |bits: u8, count: usize| {
+        let sixel = char::from(63 + bits);
+        if count > 3 {
+            let _ = write!(image, "!{}{}", count, sixel);
+        } else {
+            image.extend(std::iter::repeat_n(sixel, count));
+        }
+    };
+    let mut run: Option<(u8, usize)> = None;
+    for bits in sixels {
+        run = match run {
+            Some((last, count)) if last == bits => Some((last, count + 1)),
+            Some((last, count)) => {
+                push(last, count);
+                Some((bits, 1))
+            }
+            None => Some((bits, 1)),
+        };
+    }
+    if let Some((bits, count)) = run {
+        push(bits, count);
+    }
+}
+
+/// Bytes in base64, with padding.
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | u32::from(*byte) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(char::from(
+                    BASE64[(group >> (18 - 6 * index)) as usize & 63],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_kitty_images() {
+        assert_eq!(base64(b"symbolgen"), "c3ltYm9sZ2Vu");
+        assert_eq!(base64(b"glyph"), "Z2x5cGg=");
+        let pixels = [0, 0, 0, 255];
+        assert_eq!(
+            kitty(&pixels, 1, 1),
+            "\x1b_Ga=T,f=32,s=1,v=1,m=0;AAAA/w==\x1b\\"
+        );
+        let chunked = kitty(&[0; 4092], 31, 33);
+        assert!(chunked.starts_with("\x1b_Ga=T,f=32,s=31,v=33,m=1;AAAA"));
+        assert_eq!(chunked.matches("\x1b_Gm=0;").count(), 1);
+    }
+
+    #[test]
+    fn encode_sixel_images() {
+        // A black column over a white one, five pixels tall, beside a transparent column
+        let mut pixels = Vec::new();
+        for _ in 0..5 {
+            pixels.extend([0, 0, 0, 255, 255, 255, 255, 255, 0, 0, 0, 0]);
+        }
+        let image = sixel(&pixels, 3, 5);
+        assert!(image.starts_with("\x1bP0;1q\"1;1;3;5#0;2;0;0;0#215;2;100;100;100#0"));
+        assert!(image.ends_with("#0^??$#215?^?-\x1b\\"));
+
+        let mut runs = String::new();
+        push_runs(&mut runs, [1, 1, 1, 1, 2, 2].iter().copied());
+        assert_eq!(runs, "!4@AA");
+    }
+
+    #[test]
+    fn guess_protocols() {
+        assert_eq!(
+            GraphicsProtocol::guess("xterm-kitty", ""),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            GraphicsProtocol::guess("foot", ""),
+            Some(GraphicsProtocol::Sixel)
+        );
+        assert_eq!(GraphicsProtocol::guess("xterm-256color", ""), None);
+    }
+}
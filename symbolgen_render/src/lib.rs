@@ -0,0 +1,255 @@
+//! Drawing sheets of glyphs in different file formats.
+//!
+//! Each format is written by a `Renderer`. SVG and HPGL need nothing more than this crate,
+//! while PNG and PDF are drawn with cairo, behind the `cairo` feature. Drawings can also be
+//! drawn on any piet render context, behind the `piet` feature, or into pixels in memory,
+//! behind the `rgba` feature. The `plotters` feature adds glyphs as chart markers.
+//!
+//! Drawings can also be written as lines of braille or block characters, to look at in a
+//! terminal, or as images in the kitty or Sixel graphics protocols, also behind `rgba`.
+//!
+//! Labels are drawn as text in SVG, HPGL, PNG and PDF, and left out of other formats.
+#![deny(clippy::all)]
+
+use std::io::{self, Write};
+
+use symbolgen_core::{Glyph, Line, Point, Progress, Unit};
+use thiserror::Error;
+
+#[cfg(feature = "rgba")]
+mod inline;
+#[cfg(feature = "piet")]
+mod piet;
+#[cfg(feature = "plotters")]
+mod plotters;
+pub mod png;
+#[cfg(feature = "rgba")]
+mod rgba;
+#[cfg(feature = "cairo")]
+mod surface;
+mod terminal;
+mod vector;
+
+#[cfg(feature = "piet")]
+pub use crate::piet::PietBackend;
+#[cfg(feature = "plotters")]
+pub use crate::plotters::GlyphMarker;
+#[cfg(feature = "rgba")]
+pub use inline::{GraphicsProtocol, InlineRenderer};
+#[cfg(feature = "rgba")]
+pub use rgba::{render_to_rgba, RgbaStyle};
+#[cfg(feature = "cairo")]
+pub use surface::{PdfRenderer, PngRenderer};
+pub use terminal::{TerminalRenderer, TerminalStyle};
+pub use vector::{HpglRenderer, SvgRenderer};
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Couldn't write drawing: {0}")]
+    Io(#[from] io::Error),
+    #[error("This format has a single page, but the drawing has {0}")]
+    Pages(usize),
+    #[error("Cairo error: {0}")]
+    Cairo(String),
+    #[error("Piet error: {0}")]
+    Piet(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Pages of glyphs, already scaled and moved into place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drawing {
+    pub width: f64,
+    pub height: f64,
+    pub line_width: f64,
+    pub pages: Vec<Vec<Glyph>>,
+    /// Text on each page, in the same order as the pages. Formats without text, and pages
+    /// with no entry here, have none.
+    pub labels: Vec<Vec<Label>>,
+    /// Unit of every length in the drawing. Drawings in pixels have no physical size, so
+    /// are drawn a pixel or point to each unit.
+    pub unit: Unit,
+    /// Lines drawn on every page as hairlines, like crop marks
+    pub marks: Vec<Line>,
+    /// Colour of each glyph on each page, as red, green, blue and alpha. Glyphs with no
+    /// entry here are drawn in the renderer's colour.
+    pub colors: Vec<Vec<[u8; 4]>>,
+}
+
+/// Which part of a label is placed at its position.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// A line of text, drawn in the same colour as the glyphs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub text: String,
+    /// Point on the baseline the text is anchored to
+    pub position: Point,
+    /// Height of the font
+    pub size: f64,
+    pub anchor: Anchor,
+}
+
+impl Drawing {
+    /// A drawing of a single glyph, filling a square of unit size.
+    pub fn glyph(glyph: &Glyph, line_width: f64) -> Self {
+        Self {
+            width: 1.0,
+            height: 1.0,
+            line_width,
+            pages: vec![vec![glyph.clone()]],
+            labels: Vec::new(),
+            unit: Unit::Px,
+            marks: Vec::new(),
+            colors: Vec::new(),
+        }
+    }
+
+    /// Width of hairlines, a quarter of a point, or of a pixel for drawings in pixels.
+    pub fn hairline(&self) -> f64 {
+        self.unit
+            .inches()
+            .map_or(0.25, |inches| 0.25 / 72.0 / inches)
+    }
+
+    /// The marks as a glyph, to draw like any other.
+    pub fn marks_glyph(&self) -> Glyph {
+        Glyph::new(0, self.marks.iter().cloned().map(Into::into).collect())
+    }
+
+    /// The labels on a page.
+    pub fn page_labels(&self, page: usize) -> &[Label] {
+        self.labels.get(page).map_or(&[], Vec::as_slice)
+    }
+
+    /// The colours of the glyphs on a page.
+    pub fn page_colors(&self, page: usize) -> &[[u8; 4]] {
+        self.colors.get(page).map_or(&[], Vec::as_slice)
+    }
+
+    /// A drawing of just one of the pages, with its labels and colours.
+    pub fn page(&self, page: usize) -> Drawing {
+        Drawing {
+            pages: only_page(&self.pages, page),
+            labels: only_page(&self.labels, page),
+            colors: only_page(&self.colors, page),
+            ..self.clone()
+        }
+    }
+
+    /// Number of glyphs on every page.
+    pub fn glyph_count(&self) -> usize {
+        self.pages.iter().map(Vec::len).sum()
+    }
+
+    /// The glyphs of a drawing of at most one page.
+    pub fn single_page(&self) -> Result<&[Glyph]> {
+        match self.pages.as_slice() {
+            [] => Ok(&[]),
+            [page] => Ok(page),
+            pages => Err(Error::Pages(pages.len())),
+        }
+    }
+}
+
+/// Just one page of something kept for each page, or nothing if there's no such page.
+fn only_page<T: Clone>(pages: &[Vec<T>], page: usize) -> Vec<Vec<T>> {
+    pages.get(page).into_iter().cloned().collect()
+}
+
+/// Writes drawings in a file format.
+pub trait Renderer {
+    fn render(&self, drawing: &Drawing, writer: &mut dyn Write) -> Result<()>;
+
+    /// Write a drawing as for `render`, calling `progress` as its glyphs are drawn. Formats
+    /// quick to write only call it once, when they are done.
+    fn render_with_progress(
+        &self,
+        drawing: &Drawing,
+        writer: &mut dyn Write,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<()> {
+        self.render(drawing, writer)?;
+        let total = drawing.glyph_count();
+        progress(Progress { done: total, total });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_page_formats_reject_pages() {
+        let glyph = Glyph::new(
+            0,
+            vec![Line::new(Point::new(1.0, 1.0), Point::new(2.0, 1.0)).into()],
+        );
+        let mut drawing = Drawing {
+            width: 3.0,
+            height: 2.0,
+            line_width: 0.5,
+            pages: vec![vec![glyph]],
+            labels: Vec::new(),
+            unit: Unit::Px,
+            marks: Vec::new(),
+            colors: Vec::new(),
+        };
+        let mut svg = Vec::new();
+        SvgRenderer::default().render(&drawing, &mut svg).unwrap();
+        assert!(String::from_utf8(svg).unwrap().contains("M 1 1 L 2 1"));
+        let mut reports = Vec::new();
+        SvgRenderer::default()
+            .render_with_progress(&drawing, &mut Vec::new(), &mut |progress| {
+                reports.push(progress)
+            })
+            .unwrap();
+        assert_eq!(reports, [Progress { done: 1, total: 1 }]);
+
+        drawing.labels = vec![vec![Label {
+            text: "a<b".to_owned(),
+            position: Point::new(1.5, 2.0),
+            size: 0.5,
+            anchor: Anchor::Middle,
+        }]];
+        let mut svg = Vec::new();
+        SvgRenderer::default().render(&drawing, &mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains(r#"text-anchor="middle">a&lt;b</text>"#));
+        assert!(svg.ends_with("</g>\n</svg>\n"));
+        let mut hpgl = Vec::new();
+        HpglRenderer::default().render(&drawing, &mut hpgl).unwrap();
+        assert!(String::from_utf8(hpgl).unwrap().contains("PU15,0;LO4;"));
+
+        let copy = drawing.pages[0][0].clone();
+        drawing.pages[0].push(copy);
+        drawing.colors = vec![vec![[255, 0, 0, 255], [0, 0, 255, 255]]];
+        let mut svg = Vec::new();
+        SvgRenderer::default().render(&drawing, &mut svg).unwrap();
+        assert!(String::from_utf8(svg)
+            .unwrap()
+            .contains(r##"M 1 1 L 2 1" stroke="#ff0000""##));
+        let mut hpgl = Vec::new();
+        HpglRenderer::default().render(&drawing, &mut hpgl).unwrap();
+        let hpgl = String::from_utf8(hpgl).unwrap();
+        assert!(hpgl.contains("PU;SP2;\nPU20,10;"));
+
+        drawing.pages.push(Vec::new());
+        let second = drawing.page(1);
+        assert_eq!(second.pages, [Vec::new()]);
+        assert!(second.labels.is_empty());
+        assert!(matches!(
+            HpglRenderer::default().render(&drawing, &mut Vec::new()),
+            Err(Error::Pages(2))
+        ));
+    }
+}
@@ -0,0 +1,80 @@
+//! Drawing on piet render contexts, so one code path draws on any platform piet supports.
+
+use ::piet::kurbo::BezPath;
+use ::piet::{Color, LineCap, RenderContext, StrokeStyle};
+
+use crate::{Drawing, Error, Result};
+
+/// Draws a single page on a piet render context, such as a window or web canvas.
+#[derive(Clone, Copy, Debug)]
+pub struct PietBackend {
+    pub color: Color,
+    /// Colour to clear the context with first, if any
+    pub background: Option<Color>,
+}
+
+impl Default for PietBackend {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+            background: Some(Color::WHITE),
+        }
+    }
+}
+
+impl PietBackend {
+    /// Draw a drawing with its top left at the context's origin.
+    ///
+    /// The context is left unfinished, for more to be drawn on it.
+    pub fn draw<R: RenderContext>(&self, drawing: &Drawing, context: &mut R) -> Result<()> {
+        let glyphs = drawing.single_page()?;
+        if let Some(background) = self.background {
+            context.clear(None, background);
+        }
+        let brush = context.solid_brush(self.color);
+        let style = StrokeStyle::new().line_cap(LineCap::Round);
+        let colors = drawing.page_colors(0);
+        for (index, glyph) in glyphs.iter().enumerate() {
+            let path = BezPath::from(glyph);
+            match colors.get(index) {
+                Some(&[red, green, blue, alpha]) => {
+                    let brush = context.solid_brush(Color::rgba8(red, green, blue, alpha));
+                    context.stroke_styled(path, &brush, drawing.line_width, &style);
+                }
+                None => context.stroke_styled(path, &brush, drawing.line_width, &style),
+            }
+        }
+        context
+            .status()
+            .map_err(|error| Error::Piet(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::piet::NullRenderContext;
+    use symbolgen_core::{Glyph, Line, Point, Unit};
+
+    #[test]
+    fn draws_single_page() {
+        let glyph = Glyph::new(
+            0,
+            vec![Line::new(Point::new(1.0, 1.0), Point::new(2.0, 1.0)).into()],
+        );
+        let mut drawing = Drawing {
+            width: 3.0,
+            height: 2.0,
+            line_width: 0.5,
+            pages: vec![vec![glyph]],
+            labels: Vec::new(),
+            unit: Unit::Px,
+            marks: Vec::new(),
+            colors: vec![vec![[255, 0, 0, 255]]],
+        };
+        let mut context = NullRenderContext::new();
+        PietBackend::default().draw(&drawing, &mut context).unwrap();
+        drawing.pages.push(Vec::new());
+        assert!(PietBackend::default().draw(&drawing, &mut context).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Glyphs as point markers on plotters charts, to tell many series apart.
+
+use ::plotters::element::{Drawable, PointCollection};
+use ::plotters::style::ShapeStyle;
+use ::plotters_backend::{BackendCoord, DrawingBackend, DrawingErrorKind};
+use symbolgen_core::Glyph;
+
+/// A glyph drawn as a marker centred on a point of a chart.
+#[derive(Clone, Debug)]
+pub struct GlyphMarker<'a, Coord> {
+    pub coord: Coord,
+    pub glyph: &'a Glyph,
+    /// Width and height of the marker in pixels
+    pub size: i32,
+    pub style: ShapeStyle,
+}
+
+impl<'a, Coord> GlyphMarker<'a, Coord> {
+    pub fn new<S: Into<ShapeStyle>>(coord: Coord, glyph: &'a Glyph, size: i32, style: S) -> Self {
+        Self {
+            coord,
+            glyph,
+            size,
+            style: style.into(),
+        }
+    }
+
+    /// A function making markers of a glyph, as taken by `PointSeries::of_element`.
+    pub fn of(glyph: &'a Glyph) -> impl Fn(Coord, i32, ShapeStyle) -> Self {
+        move |coord, size, style| Self::new(coord, glyph, size, style)
+    }
+}
+
+impl<'a, 'b, Coord> PointCollection<'b, Coord> for &'b GlyphMarker<'a, Coord> {
+    type Point = &'b Coord;
+    type IntoIter = std::iter::Once<&'b Coord>;
+
+    fn point_iter(self) -> Self::IntoIter {
+        std::iter::once(&self.coord)
+    }
+}
+
+impl<Coord, DB: DrawingBackend> Drawable<DB> for GlyphMarker<'_, Coord> {
+    fn draw<I: Iterator<Item = BackendCoord>>(
+        &self,
+        mut position: I,
+        backend: &mut DB,
+        _: (u32, u32),
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
+        let (x, y) = match position.next() {
+            Some(centre) => centre,
+            None => return Ok(()),
+        };
+        let size = f64::from(self.size);
+        for polyline in self.glyph.to_polylines() {
+            let path = polyline.iter().map(|point| {
+                (
+                    x + ((point.x - 0.5) * size).round() as i32,
+                    y + ((point.y - 0.5) * size).round() as i32,
+                )
+            });
+            backend.draw_path(path, &self.style)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::plotters::prelude::{IntoDrawingArea, SVGBackend, BLACK};
+    use symbolgen_core::{Line, Point};
+
+    #[test]
+    fn marker_centred_on_point() {
+        let glyph = Glyph::new(
+            0,
+            vec![Line::new(Point::new(0.0, 0.5), Point::new(1.0, 0.5)).into()],
+        );
+        let mut svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg, (40, 40)).into_drawing_area();
+            root.draw(&GlyphMarker::new((20, 10), &glyph, 10, BLACK))
+                .unwrap();
+            root.present().unwrap();
+        }
+        assert!(svg.contains(r#"points="25,10 15,10 ""#));
+    }
+}
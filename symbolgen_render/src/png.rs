@@ -0,0 +1,77 @@
+//! Text chunks in PNG files, to record how an image was made.
+
+/// The eight bytes every PNG file starts with.
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// A PNG with `tEXt` chunks of keywords and text added after its header.
+///
+/// Keywords must be Latin-1 of 1 to 79 characters, and text must not contain nul bytes.
+pub fn with_text(png: &[u8], entries: &[(&str, &str)]) -> Vec<u8> {
+    let header_end = chunks(png)
+        .next()
+        .map(|(_, _, end)| end)
+        .expect("PNG has no header");
+    let mut tagged = png[..header_end].to_vec();
+    for (keyword, text) in entries {
+        let mut data = keyword.as_bytes().to_vec();
+        data.push(0);
+        data.extend_from_slice(text.as_bytes());
+        tagged.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = tagged.len();
+        tagged.extend_from_slice(b"tEXt");
+        tagged.extend_from_slice(&data);
+        let crc = crc32(&tagged[start..]);
+        tagged.extend_from_slice(&crc.to_be_bytes());
+    }
+    tagged.extend_from_slice(&png[header_end..]);
+    tagged
+}
+
+/// The keyword and text of each `tEXt` chunk in a PNG.
+pub fn text(png: &[u8]) -> Vec<(String, String)> {
+    chunks(png)
+        .filter(|(kind, _, _)| kind == b"tEXt")
+        .filter_map(|(_, data, _)| {
+            let split = data.iter().position(|byte| *byte == 0)?;
+            Some((
+                String::from_utf8_lossy(&data[..split]).into_owned(),
+                String::from_utf8_lossy(&data[split + 1..]).into_owned(),
+            ))
+        })
+        .collect()
+}
+
+/// The kind and data of each chunk, and the offset just after it, stopping at the first
+/// chunk that is cut short.
+fn chunks(png: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8], usize)> {
+    let mut offset = if png.starts_with(SIGNATURE) {
+        SIGNATURE.len()
+    } else {
+        png.len()
+    };
+    std::iter::from_fn(move || {
+        let header = png.get(offset..offset + 8)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let kind = [header[4], header[5], header[6], header[7]];
+        let data = png.get(offset + 8..offset + 8 + length)?;
+        let end = offset + 12 + length;
+        if end > png.len() {
+            return None;
+        }
+        offset = end;
+        Some((kind, data, end))
+    })
+}
+
+/// The CRC-32 checksum PNG chunks end with.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
@@ -0,0 +1,146 @@
+//! Drawing into pixels in memory, ready to upload as a texture.
+
+use symbolgen_core::{Glyph, Segment};
+use tiny_skia::{Color, LineCap, LineJoin, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+use crate::{Drawing, Result};
+
+/// Colours of the strokes and the background, as red, green, blue and alpha.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RgbaStyle {
+    pub color: [u8; 4],
+    pub background: [u8; 4],
+}
+
+impl Default for RgbaStyle {
+    fn default() -> Self {
+        Self {
+            color: [0, 0, 0, 255],
+            background: [255, 255, 255, 255],
+        }
+    }
+}
+
+/// Draw a single page as anti-aliased pixels, four bytes of red, green, blue and alpha for
+/// each, row by row from the top left.
+///
+/// The drawing is scaled to fit the image, in the middle.
+pub fn render_to_rgba(
+    drawing: &Drawing,
+    width: u32,
+    height: u32,
+    style: &RgbaStyle,
+) -> Result<Vec<u8>> {
+    let glyphs = drawing.single_page()?;
+    let mut pixmap = match Pixmap::new(width, height) {
+        Some(pixmap) => pixmap,
+        None => return Ok(Vec::new()),
+    };
+    let [red, green, blue, alpha] = style.background;
+    pixmap.fill(Color::from_rgba8(red, green, blue, alpha));
+
+    let mut paint = Paint::default();
+    let [red, green, blue, alpha] = style.color;
+    paint.set_color_rgba8(red, green, blue, alpha);
+    let stroke = Stroke {
+        width: drawing.line_width as f32,
+        line_cap: LineCap::Round,
+        line_join: LineJoin::Round,
+        ..Stroke::default()
+    };
+    let (width, height) = (f64::from(width), f64::from(height));
+    let scale = (width / drawing.width).min(height / drawing.height);
+    let transform = Transform::from_row(
+        scale as f32,
+        0.0,
+        0.0,
+        scale as f32,
+        ((width - drawing.width * scale) / 2.0) as f32,
+        ((height - drawing.height * scale) / 2.0) as f32,
+    );
+    let colors = drawing.page_colors(0);
+    for (index, glyph) in glyphs.iter().enumerate() {
+        let path = match path(glyph) {
+            Some(path) => path,
+            None => continue,
+        };
+        match colors.get(index) {
+            Some(&[red, green, blue, alpha]) => {
+                let mut paint = paint.clone();
+                paint.set_color_rgba8(red, green, blue, alpha);
+                pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+            }
+            None => pixmap.stroke_path(&path, &paint, &stroke, transform, None),
+        }
+    }
+
+    Ok(pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let pixel = pixel.demultiply();
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+        })
+        .collect())
+}
+
+/// The glyph as a path, or nothing if it has no segments.
+fn path(glyph: &Glyph) -> Option<tiny_skia::Path> {
+    let mut builder = PathBuilder::new();
+    let mut position = None;
+    for segment in glyph.segments() {
+        let start = segment.start();
+        if position != Some(start) {
+            builder.move_to(start.x as f32, start.y as f32);
+        }
+        let curves = match segment {
+            Segment::Line(line) => {
+                let end = line.end();
+                builder.line_to(end.x as f32, end.y as f32);
+                Vec::new()
+            }
+            Segment::Arc(arc) => arc.to_curves(),
+            Segment::Curve(curve) => vec![curve.clone()],
+        };
+        for curve in curves {
+            let [_, control_start, control_end, end] = curve.points();
+            builder.cubic_to(
+                control_start.x as f32,
+                control_start.y as f32,
+                control_end.x as f32,
+                control_end.y as f32,
+                end.x as f32,
+                end.y as f32,
+            );
+        }
+        position = Some(segment.end());
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symbolgen_core::{Line, Point};
+
+    #[test]
+    fn strokes_drawn_over_background() {
+        let glyph = Glyph::new(
+            0,
+            vec![Line::new(Point::new(0.0, 0.5), Point::new(1.0, 0.5)).into()],
+        );
+        let pixels =
+            render_to_rgba(&Drawing::glyph(&glyph, 0.2), 10, 10, &RgbaStyle::default()).unwrap();
+        assert_eq!(pixels.len(), 10 * 10 * 4);
+        let pixel = |x: usize, y: usize| &pixels[(y * 10 + x) * 4..][..4];
+        assert_eq!(pixel(5, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(5, 4), [0, 0, 0, 255]);
+
+        let drawing = Drawing {
+            colors: vec![vec![[0, 0, 255, 255]]],
+            ..Drawing::glyph(&glyph, 0.2)
+        };
+        let pixels = render_to_rgba(&drawing, 10, 10, &RgbaStyle::default()).unwrap();
+        assert_eq!(&pixels[(4 * 10 + 5) * 4..][..4], [0, 0, 255, 255]);
+    }
+}
@@ -0,0 +1,299 @@
+//! Formats drawn with cairo.
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use cairo::{Context, FontSlant, FontWeight, Format, ImageSurface, LineCap, PdfSurface};
+use symbolgen_core::{Glyph, Progress, Segment};
+
+use crate::{png, Anchor, Drawing, Error, Label, Renderer, Result};
+
+/// Draws a single page as a PNG image, black on white unless set otherwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PngRenderer {
+    /// Keywords and text saved in the image's text chunks
+    pub text: Vec<(String, String)>,
+    /// Colour of the strokes and labels, as red, green, blue and alpha
+    pub color: [u8; 4],
+    /// Colour to fill the background with, transparent if not set
+    pub background: Option<[u8; 4]>,
+    /// Pixels to the inch, for drawings in physical units
+    pub dpi: f64,
+}
+
+impl Default for PngRenderer {
+    fn default() -> Self {
+        Self {
+            text: Vec::new(),
+            color: [0, 0, 0, 255],
+            background: Some([255, 255, 255, 255]),
+            dpi: 96.0,
+        }
+    }
+}
+
+impl Renderer for PngRenderer {
+    fn render(&self, drawing: &Drawing, writer: &mut dyn Write) -> Result<()> {
+        self.render_with_progress(drawing, writer, &mut |_| {})
+    }
+
+    fn render_with_progress(
+        &self,
+        drawing: &Drawing,
+        writer: &mut dyn Write,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<()> {
+        let glyphs = drawing.single_page()?;
+        let scale = drawing
+            .unit
+            .inches()
+            .map_or(1.0, |inches| inches * self.dpi);
+        let surface = ImageSurface::create(
+            Format::ARgb32,
+            (drawing.width * scale) as i32,
+            (drawing.height * scale) as i32,
+        )
+        .map_err(cairo_error)?;
+        let context = Context::new(&surface);
+        if drawing.unit.inches().is_some() {
+            context.scale(scale, scale);
+        }
+
+        if let Some(background) = self.background {
+            set_source(&context, background);
+            context.paint();
+        }
+        let mut done = 0;
+        stroke_glyphs(
+            &context,
+            glyphs,
+            drawing.page_colors(0),
+            self.color,
+            drawing.line_width,
+            &mut || {
+                done += 1;
+                progress(Progress {
+                    done,
+                    total: glyphs.len(),
+                });
+            },
+        );
+        draw_marks(&context, drawing);
+        draw_labels(&context, drawing.page_labels(0));
+
+        let mut image = Vec::new();
+        surface.write_to_png(&mut image).map_err(cairo_error)?;
+        if !self.text.is_empty() {
+            let entries: Vec<(&str, &str)> = self
+                .text
+                .iter()
+                .map(|(keyword, text)| (keyword.as_str(), text.as_str()))
+                .collect();
+            image = png::with_text(&image, &entries);
+        }
+        writer.write_all(&image)?;
+        Ok(())
+    }
+}
+
+/// Draws each page on a page of a PDF document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfRenderer {
+    /// Width and height of each page in points, with the drawing in the middle. Drawings in
+    /// pixels are scaled to fit, while others are drawn at their true size. Defaults to the
+    /// size of the drawing.
+    pub page_size: Option<(f64, f64)>,
+    /// Colour of the strokes and labels, as red, green, blue and alpha
+    pub color: [u8; 4],
+    /// Colour to fill each whole page with, none if not set
+    pub background: Option<[u8; 4]>,
+}
+
+impl Default for PdfRenderer {
+    fn default() -> Self {
+        Self {
+            page_size: None,
+            color: [0, 0, 0, 255],
+            background: None,
+        }
+    }
+}
+
+impl Renderer for PdfRenderer {
+    fn render(&self, drawing: &Drawing, writer: &mut dyn Write) -> Result<()> {
+        self.render_with_progress(drawing, writer, &mut |_| {})
+    }
+
+    fn render_with_progress(
+        &self,
+        drawing: &Drawing,
+        writer: &mut dyn Write,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<()> {
+        // Points to each unit of the drawing
+        let points = drawing.unit.inches().map(|inches| inches * 72.0);
+        let (width, height) = (drawing.width, drawing.height);
+        let size = points.map_or((width, height), |points| (width * points, height * points));
+        let (page_width, page_height) = self.page_size.unwrap_or(size);
+        // Cairo keeps hold of the stream until the surface is finished
+        let buffer = SharedBuffer::default();
+        let surface =
+            PdfSurface::for_stream(page_width, page_height, buffer.clone()).map_err(cairo_error)?;
+        let context = Context::new(&surface);
+        // Put the drawing in the middle of each page
+        let scale = points.unwrap_or_else(|| (page_width / width).min(page_height / height));
+        context.translate(
+            (page_width - width * scale) / 2.0,
+            (page_height - height * scale) / 2.0,
+        );
+        context.scale(scale, scale);
+        let total = drawing.glyph_count();
+        let mut done = 0;
+        for (page, glyphs) in drawing.pages.iter().enumerate() {
+            if let Some(background) = self.background {
+                set_source(&context, background);
+                context.paint();
+            }
+            stroke_glyphs(
+                &context,
+                glyphs,
+                drawing.page_colors(page),
+                self.color,
+                drawing.line_width,
+                &mut || {
+                    done += 1;
+                    progress(Progress { done, total });
+                },
+            );
+            draw_marks(&context, drawing);
+            draw_labels(&context, drawing.page_labels(page));
+            context.show_page();
+        }
+        surface.finish();
+        writer.write_all(&buffer.0.borrow())?;
+        Ok(())
+    }
+}
+
+/// Bytes written by cairo, which can be read once it's done.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Draw in a colour given as red, green, blue and alpha.
+fn set_source(context: &Context, [red, green, blue, alpha]: [u8; 4]) {
+    let channel = |value: u8| f64::from(value) / 255.0;
+    context.set_source_rgba(channel(red), channel(green), channel(blue), channel(alpha));
+}
+
+fn cairo_error<E: Debug>(error: E) -> Error {
+    Error::Cairo(format!("{:?}", error))
+}
+
+/// Stroke the paths of glyphs in their colours, or a colour for those without, calling
+/// `drawn` after each glyph.
+fn stroke_glyphs(
+    context: &Context,
+    glyphs: &[Glyph],
+    colors: &[[u8; 4]],
+    color: [u8; 4],
+    line_width: f64,
+    drawn: &mut dyn FnMut(),
+) {
+    context.set_line_width(line_width);
+    context.set_line_cap(LineCap::Round);
+    set_source(context, color);
+    if colors.is_empty() {
+        // All the same colour, so stroke them at once
+        for glyph in glyphs {
+            draw_glyph(context, glyph);
+            drawn();
+        }
+        context.stroke();
+        return;
+    }
+    for (index, glyph) in glyphs.iter().enumerate() {
+        set_source(context, colors.get(index).copied().unwrap_or(color));
+        draw_glyph(context, glyph);
+        context.stroke();
+        drawn();
+    }
+    set_source(context, color);
+}
+
+/// Stroke the drawing's marks as hairlines in the current colour.
+fn draw_marks(context: &Context, drawing: &Drawing) {
+    if !drawing.marks.is_empty() {
+        draw_glyph(context, &drawing.marks_glyph());
+        context.set_line_width(drawing.hairline());
+        context.set_line_cap(LineCap::Butt);
+        context.stroke();
+    }
+}
+
+/// Fill the text of labels in the current colour.
+fn draw_labels(context: &Context, labels: &[Label]) {
+    if labels.is_empty() {
+        return;
+    }
+    context.select_font_face("sans-serif", FontSlant::Normal, FontWeight::Normal);
+    for label in labels {
+        context.set_font_size(label.size);
+        let width = context.text_extents(&label.text).x_advance;
+        let offset = match label.anchor {
+            Anchor::Start => 0.0,
+            Anchor::Middle => width / 2.0,
+            Anchor::End => width,
+        };
+        context.move_to(label.position.x - offset, label.position.y);
+        context.show_text(&label.text);
+    }
+}
+
+/// Add the path of a glyph to the context, ready to stroke.
+fn draw_glyph(context: &Context, glyph: &Glyph) {
+    for segment in glyph.segments().iter() {
+        match segment {
+            Segment::Line(line) => {
+                let (start, end) = (line.start(), line.end());
+                context.move_to(start.x, start.y);
+                context.line_to(end.x, end.y);
+            }
+            Segment::Arc(arc) => {
+                let centre = arc.centre();
+                // Don't join the arc to the end of the previous segment
+                context.new_sub_path();
+                context.arc(
+                    centre.x,
+                    centre.y,
+                    arc.radius(),
+                    arc.start_angle(),
+                    arc.end_angle(),
+                );
+            }
+            Segment::Curve(curve) => {
+                let [start, control_start, control_end, end] = curve.points();
+                context.move_to(start.x, start.y);
+                context.curve_to(
+                    control_start.x,
+                    control_start.y,
+                    control_end.x,
+                    control_end.y,
+                    end.x,
+                    end.y,
+                );
+            }
+        }
+    }
+}
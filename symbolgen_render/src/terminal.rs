@@ -0,0 +1,167 @@
+//! Drawings as lines of text made of dots or blocks, to look at in a terminal.
+
+use std::io::Write;
+
+use symbolgen_core::Glyph;
+
+use crate::{Drawing, Renderer, Result};
+
+/// First braille pattern, with no dots raised.
+const BRAILLE_BLANK: u32 = 0x2800;
+/// Bit of each dot in a braille pattern, by column then row.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+/// Samples of a curve or arc when measuring its length.
+const LENGTH_SAMPLES: usize = 16;
+
+/// Characters that text drawings are made of.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TerminalStyle {
+    /// Braille patterns of two by four dots, for the finest detail
+    Braille,
+    /// Half blocks, with two square pixels to a character, for fonts without braille
+    Blocks,
+}
+
+impl TerminalStyle {
+    /// Pixels across and down each character.
+    fn cell(&self) -> (usize, usize) {
+        match self {
+            TerminalStyle::Braille => (2, 4),
+            TerminalStyle::Blocks => (1, 2),
+        }
+    }
+
+    /// The character showing the pixels set in a cell, given by column then row.
+    fn character(&self, pixel: impl Fn(usize, usize) -> bool) -> char {
+        match self {
+            TerminalStyle::Braille => {
+                let mut pattern = BRAILLE_BLANK;
+                for (column, dots) in BRAILLE_DOTS.iter().enumerate() {
+                    for (row, dot) in dots.iter().enumerate() {
+                        if pixel(column, row) {
+                            pattern |= dot;
+                        }
+                    }
+                }
+                char::from_u32(pattern).unwrap_or(' ')
+            }
+            TerminalStyle::Blocks => match (pixel(0, 0), pixel(0, 1)) {
+                (false, false) => ' ',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (true, true) => '█',
+            },
+        }
+    }
+}
+
+/// Draws a single page as lines of text, with the glyphs' paths a pixel wide. Terminal
+/// characters are about twice as tall as they are wide, so the pixels are about square.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerminalRenderer {
+    /// Characters across the width of the drawing
+    pub columns: usize,
+    pub style: TerminalStyle,
+}
+
+impl Default for TerminalRenderer {
+    fn default() -> Self {
+        Self {
+            columns: 20,
+            style: TerminalStyle::Braille,
+        }
+    }
+}
+
+impl TerminalRenderer {
+    /// The page as lines of text, without line endings.
+    pub fn to_lines(&self, drawing: &Drawing) -> Result<Vec<String>> {
+        let (cell_width, cell_height) = self.style.cell();
+        let width = self.columns.max(1) * cell_width;
+        let scale = width as f64 / drawing.width;
+        let rows = ((drawing.height * scale) / cell_height as f64)
+            .ceil()
+            .max(1.0) as usize;
+        let height = rows * cell_height;
+
+        let mut pixels = vec![false; width * height];
+        for glyph in drawing.single_page()? {
+            plot(&mut pixels, width, scale, glyph);
+        }
+        let lines = (0..rows)
+            .map(|row| {
+                (0..self.columns.max(1))
+                    .map(|column| {
+                        self.style.character(|x, y| {
+                            let x = column * cell_width + x;
+                            let y = row * cell_height + y;
+                            pixels[y * width + x]
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(lines)
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn render(&self, drawing: &Drawing, writer: &mut dyn Write) -> Result<()> {
+        for line in self.to_lines(drawing)? {
+            writeln!(writer, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Set every pixel the glyph's segments pass through, for a grid of pixels the given number
+/// wide, and as tall as it holds.
+fn plot(pixels: &mut [bool], width: usize, scale: f64, glyph: &Glyph) {
+    let height = pixels.len() / width;
+    let pixel = |value: f64, size: usize| {
+        let pixel = (value * scale).floor();
+        // Paths along the far edge belong to the last pixel, not one past it
+        (0.0..=size as f64)
+            .contains(&pixel)
+            .then(|| (pixel as usize).min(size - 1))
+    };
+    for segment in glyph.segments() {
+        // Half a pixel apart, to miss none on the way
+        let samples = (segment.length(LENGTH_SAMPLES) * scale * 2.0).ceil() as usize + 2;
+        for point in segment.sample(samples) {
+            if let (Some(x), Some(y)) = (pixel(point.x, width), pixel(point.y, height)) {
+                pixels[y * width + x] = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symbolgen_core::{Line, Point};
+
+    #[test]
+    fn draw_dots_and_blocks() {
+        let glyph = Glyph::new(
+            0,
+            vec![
+                Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0)).into(),
+                Line::new(Point::new(1.0, 0.0), Point::new(1.0, 1.0)).into(),
+            ],
+        );
+        let drawing = Drawing::glyph(&glyph, 0.1);
+        let braille = TerminalRenderer {
+            columns: 2,
+            style: TerminalStyle::Braille,
+        };
+        assert_eq!(braille.to_lines(&drawing).unwrap(), ["⠉⢹"]);
+
+        let blocks = TerminalRenderer {
+            columns: 2,
+            style: TerminalStyle::Blocks,
+        };
+        assert_eq!(blocks.to_lines(&drawing).unwrap(), ["▀█"]);
+    }
+}
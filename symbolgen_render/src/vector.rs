@@ -0,0 +1,220 @@
+//! Formats written as text, with nothing to rasterise.
+
+use std::fmt::Write as _;
+use std::io::Write;
+
+use symbolgen_core::{to_hpgl, to_svg, HpglOptions, LineCap, SvgOptions};
+
+use crate::{Anchor, Drawing, Label, Renderer, Result};
+
+/// Draws a single page as an SVG document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgRenderer {
+    pub line_cap: LineCap,
+    /// Colour of the strokes and labels, as red, green, blue and alpha
+    pub color: [u8; 4],
+    /// Colour to fill the background with, transparent if not set
+    pub background: Option<[u8; 4]>,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        let options = SvgOptions::default();
+        Self {
+            line_cap: options.line_cap,
+            color: options.color,
+            background: options.background,
+        }
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn render(&self, drawing: &Drawing, writer: &mut dyn Write) -> Result<()> {
+        let options = SvgOptions {
+            width: drawing.width,
+            height: drawing.height,
+            line_width: drawing.line_width,
+            line_cap: self.line_cap,
+            color: self.color,
+            background: self.background,
+            unit: drawing.unit,
+            glyph_colors: drawing.page_colors(0).to_vec(),
+            ..SvgOptions::default()
+        };
+        let mut svg = to_svg(drawing.single_page()?, &options);
+        let mut extra = String::new();
+        if !drawing.marks.is_empty() {
+            writeln!(
+                extra,
+                r#"<path d="{}" fill="none" {} stroke-width="{}"/>"#,
+                drawing.marks_glyph().to_svg_path(),
+                paint("stroke", self.color),
+                number(drawing.hairline())
+            )
+            .expect("writing to a string can't fail");
+        }
+        let labels = drawing.page_labels(0);
+        if !labels.is_empty() {
+            extra.push_str(&svg_labels(labels, self.color));
+        }
+        if !extra.is_empty() {
+            let end = svg.rfind("</svg>").unwrap_or(svg.len());
+            svg.insert_str(end, &extra);
+        }
+        writer.write_all(svg.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Draws a single page as HPGL commands for a pen plotter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HpglRenderer {
+    /// Plotter units for each pixel of the drawing. Drawings in physical units are drawn at
+    /// their true size, with 40 plotter units to the millimetre.
+    pub scale: f64,
+    /// Pen to draw with. Glyphs of other colours are drawn with the pens after it, one for
+    /// each colour in the order they first appear.
+    pub pen: u32,
+}
+
+impl Default for HpglRenderer {
+    fn default() -> Self {
+        let options = HpglOptions::default();
+        Self {
+            scale: options.scale,
+            pen: options.pen,
+        }
+    }
+}
+
+impl Renderer for HpglRenderer {
+    fn render(&self, drawing: &Drawing, writer: &mut dyn Write) -> Result<()> {
+        let options = HpglOptions {
+            scale: self.scale(drawing),
+            height: drawing.height,
+            pen: self.pen,
+            glyph_pens: self.pens(drawing.page_colors(0)),
+        };
+        let mut glyphs = drawing.single_page()?.to_vec();
+        if !drawing.marks.is_empty() {
+            glyphs.push(drawing.marks_glyph());
+        }
+        let mut hpgl = to_hpgl(&glyphs, &options);
+        let labels = drawing.page_labels(0);
+        if !labels.is_empty() {
+            // Before the pen is put away
+            let end = hpgl.rfind("PU;SP0;").unwrap_or(hpgl.len());
+            hpgl.insert_str(end, &self.hpgl_labels(labels, drawing));
+        }
+        writer.write_all(hpgl.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Plotter units to an inch, at 40 to the millimetre.
+const PLOTTER_UNITS_PER_INCH: f64 = 1016.0;
+
+impl HpglRenderer {
+    /// Plotter units for each unit of the drawing.
+    fn scale(&self, drawing: &Drawing) -> f64 {
+        drawing
+            .unit
+            .inches()
+            .map_or(self.scale, |inches| inches * PLOTTER_UNITS_PER_INCH)
+    }
+
+    /// The pen for each colour of glyph, starting from the renderer's pen.
+    fn pens(&self, colors: &[[u8; 4]]) -> Vec<u32> {
+        let mut seen: Vec<[u8; 4]> = Vec::new();
+        colors
+            .iter()
+            .map(|color| {
+                let index = seen
+                    .iter()
+                    .position(|seen| seen == color)
+                    .unwrap_or_else(|| {
+                        seen.push(*color);
+                        seen.len() - 1
+                    });
+                self.pen + index as u32
+            })
+            .collect()
+    }
+
+    /// Commands writing each label in the plotter's font, ending with the default
+    /// terminator.
+    fn hpgl_labels(&self, labels: &[Label], drawing: &Drawing) -> String {
+        let (scale, height) = (self.scale(drawing), drawing.height);
+        let mut hpgl = String::new();
+        for label in labels {
+            let origin = match label.anchor {
+                Anchor::Start => 1,
+                Anchor::Middle => 4,
+                Anchor::End => 7,
+            };
+            // Character sizes are in centimetres, with 400 plotter units to each
+            let size = label.size * scale / 400.0;
+            writeln!(
+                hpgl,
+                "PU{},{};LO{};SI{:.3},{:.3};LB{}\u{3}",
+                (label.position.x * scale).round() as i64,
+                ((height - label.position.y) * scale).round() as i64,
+                origin,
+                size * 0.5,
+                size * 0.7,
+                label.text,
+            )
+            .expect("writing to a string can't fail");
+        }
+        hpgl
+    }
+}
+
+/// SVG text elements for each label, in a colour.
+fn svg_labels(labels: &[Label], color: [u8; 4]) -> String {
+    let mut svg = format!("<g font-family=\"sans-serif\" {}>\n", paint("fill", color));
+    for label in labels {
+        let anchor = match label.anchor {
+            Anchor::Start => "start",
+            Anchor::Middle => "middle",
+            Anchor::End => "end",
+        };
+        let text = label
+            .text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="{}" text-anchor="{}">{}</text>"#,
+            number(label.position.x),
+            number(label.position.y),
+            number(label.size),
+            anchor,
+            text
+        )
+        .expect("writing to a string can't fail");
+    }
+    svg.push_str("</g>\n");
+    svg
+}
+
+/// An attribute painting a colour, with its opacity if it isn't opaque.
+fn paint(attribute: &str, [red, green, blue, alpha]: [u8; 4]) -> String {
+    let mut paint = format!(r##"{}="#{:02x}{:02x}{:02x}""##, attribute, red, green, blue);
+    if alpha < 255 {
+        write!(
+            paint,
+            r#" {}-opacity="{}""#,
+            attribute,
+            number(f64::from(alpha) / 255.0)
+        )
+        .expect("writing to a string can't fail");
+    }
+    paint
+}
+
+/// A coordinate to a few decimal places.
+fn number(value: f64) -> f64 {
+    (value * 1e4).round() / 1e4
+}